@@ -3,11 +3,18 @@ use crate::mal::MalClient;
 use crate::mal::models::anime::Anime;
 use crate::mal::models::anime::AnimeId;
 use crate::player;
+use crate::player::PlayError;
+use crate::player::mpris;
+use crate::player::downloads::DownloadManager;
+use crate::player::mappings::SourceMapping;
+use crate::player::mappings::SourceMappings;
 use crate::screens::BackgroundUpdate;
 use crate::screens::ScreenManager;
+use crate::screens::screens::{LAUNCH, LIST, SEARCH, SEASONS};
 use crate::config::Config;
 use crate::utils::store::Store;
 use crate::utils::errorBus;
+use crate::utils::hooks::{self, HookPayload, run_hook};
 
 use chrono::DateTime;
 use chrono::Local;
@@ -18,6 +25,7 @@ use ratatui::DefaultTerminal;
 use std::fs::OpenOptions;
 use std::io;
 use std::io::Write;
+use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::mpsc;
@@ -29,20 +37,53 @@ pub struct ExtraInfo {
     pub app_sx: mpsc::Sender<Event>,
     pub mal_client: Arc<MalClient>,
     pub anime_store: Store<Anime>,
+    pub downloads: Arc<DownloadManager>,
 }
 
 // these are retured when a screen handles an input
 #[derive(Debug, Clone)]
 pub enum Action {
     PlayAnime(AnimeId),
-    PlayEpisode(AnimeId, u32),
+    PlayEpisode(AnimeId, String),
     SwitchScreen(&'static str),
     ShowOverlay(AnimeId),
     NavbarSelect(bool),
     ShowError(String),
+    ShowEpisodes(AnimeId),
+    DownloadEpisode(AnimeId, String),
+    RematchSource(AnimeId),
+    SelectSource(AnimeId, player::ShowCandidate, Resume),
+    // the answer of the completion dialog: the episode played, if it was watched and the score when confirmed
+    FinishEpisode(AnimeId, player::PlayResult, bool, Option<u8>),
+    // play the episodes one after another in a single player
+    PlayEpisodes(AnimeId, Vec<String>),
+    // pick a renderer to cast the next episode to
+    ShowCast(AnimeId),
+    // a cast ended, with how far the renderer got
+    CastFinished(AnimeId, player::PlayResult),
+    // an episode watched in a player mal-cli didn't start
+    ScrobbleEpisode(AnimeId, player::PlayResult),
+    // the config file was changed from inside the app
+    ReloadConfig,
+    // from the command palette, they switch to the screen they change
+    ChangeSeason(u16, String),
+    FilterList(String),
+    ShowRanking(String),
+    LogOut,
     Quit,
 }
 
+// what is picked up again once the show on the streaming source is chosen
+#[derive(Debug, Clone)]
+pub enum Resume {
+    // re-matching from the anime popup
+    Nothing,
+    Episode(String),
+    Playlist(Vec<String>),
+    // the episode list couldn't be fetched before the show was known
+    EpisodeList,
+}
+
 // here will all the details of a specific anime or manga be stored.
 #[allow(dead_code)]
 pub enum CurrentInfo {
@@ -61,6 +102,7 @@ pub enum Event {
     ImageCached(usize, DynamicImage),
     StorageUpdate(AnimeId, Box<dyn FnOnce(&mut Anime) + Send>),
     ShowError(String),
+    ConfigChanged,
     Rerender,
 }
 
@@ -96,6 +138,7 @@ impl std::fmt::Debug for Event {
                 .debug_struct("ShowError")
                 .field("message", message)
                 .finish(),
+            Event::ConfigChanged => f.debug_struct("ConfigChanged").finish(),
             Event::Rerender => f.debug_struct("Rerender").finish(),   
             _ => f.debug_struct("OtherEvent").finish(),
         }
@@ -110,7 +153,7 @@ pub struct App {
     is_running: bool,
     terminal: DefaultTerminal,
     shared_info: ExtraInfo,
-    anime_player: player::AnimePlayer,
+    anime_player: Rc<player::AnimePlayer>,
 
     sx: mpsc::Sender<Event>,
     rx: mpsc::Receiver<Event>,
@@ -129,6 +172,7 @@ impl App {
             app_sx: sx.clone(),
             mal_client: mal_client.clone(),
             anime_store: Store::new(),
+            downloads: Arc::new(DownloadManager::new(sx.clone())),
         };
 
 
@@ -139,7 +183,7 @@ impl App {
             is_running: true,
             terminal,
             shared_info: universal_info,
-            anime_player: player::AnimePlayer::new(),
+            anime_player: Rc::new(player::AnimePlayer::new()),
 
             rx,
             sx,
@@ -151,6 +195,8 @@ impl App {
     pub fn run(&mut self) -> io::Result<()> {
         // run any background threads
         self.spawn_background();
+        hooks::fire(&Config::global().hooks.on_start, HookPayload::new("start"));
+        self.show_config_issues();
 
         // WARNING: don't use just unwrap
         while self.is_running {
@@ -175,7 +221,9 @@ impl App {
                             self.shared_info.anime_store.add_bulk(animes);
                         }
 
-                        self.screen_manager.update_screen(update);
+                        if let Some(action) = self.screen_manager.update_screen(update) {
+                            self.handle_action(action);
+                        }
                     }
                     Event::StorageUpdate(anime, updater) => {
                         self.shared_info
@@ -188,11 +236,21 @@ impl App {
                     Event::ShowError(message) => {
                         self.screen_manager.show_error(message);
                     }
+                    Event::ConfigChanged => {
+                        self.reload_config();
+                    }
                     _ => {}
                 }
             }
         }
 
+        // waited on so it isn't cut off when the app exits
+        if let Some(hook) = &Config::global().hooks.on_exit
+            && let Err(e) = run_hook(hook, &HookPayload::new("exit"), true)
+        {
+            eprintln!("Failed to run exit hook: {}", e);
+        }
+
         Ok(())
     }
 
@@ -222,50 +280,37 @@ impl App {
         file.write_all(log_entry.as_bytes()).ok();
     }
 
-    fn play_anime(&mut self, anime_id: AnimeId, episode: u32) -> Option<()> {
+    // plays the given episode, or the next one in the list if none is given
+    fn play_anime(&mut self, anime_id: AnimeId, episode: Option<String>) -> Option<()> {
         let anime = self.shared_info.anime_store.get(&anime_id)?;
 
-        let next_episode = if episode == 0 {
+        let next_episode = episode.unwrap_or_else(|| {
             std::cmp::min(
                 anime.my_list_status.num_episodes_watched + 1,
                 anime.num_episodes,
             )
-        } else {
-            episode
-        };
+            .to_string()
+        });
 
         crossterm::execute!(std::io::stderr(), DisableMouseCapture).ok();
+        ratatui::restore();
 
-        match self.anime_player.play_episode_manually(&anime, next_episode) {
+        match self.anime_player.play_episode_manually(&anime, &next_episode) {
+            // "next" from a media key or desktop widget counts the episode as watched
+            Ok(details) if mpris::take_next_requested() => {
+                self.finish_episode(anime.id, &details, true, None);
+                self.play_following(anime.id, &details.episode);
+            }
+            Ok(details) if Config::global().player.confirm_completion => {
+                self.screen_manager.confirm_completion(&anime, details);
+            }
             Ok(details) => {
-                // update teh status to now watching
-                self.shared_info
-                    .anime_store
-                    .update(anime.id, |anime_to_update| {
-                        anime_to_update.my_list_status.status = "watching".to_string();
-                    });
-
-                if details.completed {
-                    // update the store <-
-                    self.shared_info
-                        .anime_store
-                        .update(anime.id, |anime_to_update| {
-                            if anime_to_update.my_list_status.num_episodes_watched
-                                < anime_to_update.num_episodes
-                            {
-                                anime_to_update.my_list_status.num_episodes_watched += 1;
-                            } else {
-                                anime_to_update.my_list_status.status = "completed".to_string();
-                            }
-                        });
-                }
-                // get the anime again to make sure the details are up to date with the update above
-                let updated = self.shared_info.anime_store.get(&anime.id)?;
-                self.shared_info
-                    .mal_client
-                    .update_user_list_async((*updated).clone());
-                self.screen_manager.refresh();
-                self.logg_watched_info(&anime, &details);
+                self.finish_and_continue(anime.id, &details, details.completed, None);
+            }
+            // let the user pick the show when the search isnt sure
+            Err(PlayError::AmbiguousMatch(candidates)) => {
+                self.screen_manager
+                    .show_source_picker(&anime, Resume::Episode(next_episode), candidates);
             }
             Err(e) => {
                 self.screen_manager.show_error(e.to_string());
@@ -277,42 +322,338 @@ impl App {
         None
     }
 
-    fn handle_input(&mut self, event: crossterm::event::Event) {
-        // quit the app on ctrl+c
-        if let crossterm::event::Event::Key(key_event) = event {
-            if key_event.kind == crossterm::event::KeyEventKind::Press &&
-            key_event.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) &&
-            key_event.code == crossterm::event::KeyCode::Char('c')
-            {
-                self.is_running = false;
-            }
+    // finishes the episode and keeps binging when it was watched to the end
+    fn finish_and_continue(
+        &mut self,
+        anime_id: AnimeId,
+        details: &player::PlayResult,
+        watched: bool,
+        score: Option<u8>,
+    ) {
+        if let Some(updated) = self.finish_episode(anime_id, details, watched, score)
+            && Config::global().player.binge_mode
+            && watched
+            && details.fully_watched
+        {
+            self.continue_binge(&updated, &details.episode);
         }
+    }
 
-        let result = self.screen_manager.handle_input(event);
-        if let Some(action) = result {
-            match action {
-                Action::SwitchScreen(screen_name) => {
-                    self.screen_manager.change_screen(screen_name);
-                }
-                Action::ShowOverlay(anime_id) => {
-                    self.screen_manager.toggle_overlay(anime_id);
+    // updates the list after an episode was played, watched decides if it counts towards the progress
+    fn finish_episode(
+        &mut self,
+        anime_id: AnimeId,
+        details: &player::PlayResult,
+        watched: bool,
+        score: Option<u8>,
+    ) -> Option<Rc<Anime>> {
+        let anime = self.shared_info.anime_store.get(&anime_id)?;
+
+        // update teh status to now watching
+        self.shared_info
+            .anime_store
+            .update(anime.id, |anime_to_update| {
+                anime_to_update.my_list_status.status = "watching".to_string();
+                if let Some(score) = score {
+                    anime_to_update.my_list_status.score = score;
                 }
-                Action::NavbarSelect(selected) => {
-                    self.screen_manager.toggle_navbar(selected);
+            });
+
+        // specials like "12.5" dont count towards the progress on mal
+        if watched
+            && let Ok(episode) = details.episode.parse::<u32>()
+        {
+            // update the store <-
+            self.shared_info
+                .anime_store
+                .update(anime.id, |anime_to_update| anime_to_update.mark_watched(episode));
+        }
+        // get the anime again to make sure the details are up to date with the update above
+        let updated = self.shared_info.anime_store.get(&anime.id)?;
+        self.shared_info
+            .mal_client
+            .update_user_list_async((*updated).clone());
+
+        let hook_config = &Config::global().hooks;
+        if watched {
+            hooks::fire(
+                &hook_config.on_episode_complete,
+                HookPayload::new("episode_complete")
+                    .anime(&updated)
+                    .episode(&details.episode)
+                    .play_result(details),
+            );
+        }
+
+        let (before, after) = (&anime.my_list_status, &updated.my_list_status);
+        if before.status != after.status
+            || before.score != after.score
+            || before.num_episodes_watched != after.num_episodes_watched
+        {
+            hooks::fire(
+                &hook_config.on_status_change,
+                HookPayload::new("status_change")
+                    .anime(&updated)
+                    .previous_status(before)
+                    .episode(&details.episode),
+            );
+        }
+        self.screen_manager.refresh();
+        self.logg_watched_info(&anime, details);
+        Some(updated)
+    }
+
+    // plays the episodes in one mpv, the list is updated as each one finishes
+    fn play_playlist(&mut self, anime_id: AnimeId, episodes: Vec<String>) -> Option<()> {
+        let anime = self.shared_info.anime_store.get(&anime_id)?;
+        crossterm::execute!(std::io::stderr(), DisableMouseCapture).ok();
+        ratatui::restore();
+
+        let player = self.anime_player.clone();
+        let mut last = None;
+        let result = player.play_playlist(&anime, &episodes, |details| {
+            let watched = details.completed;
+            if let Some(updated) = self.finish_episode(anime_id, &details, watched, None) {
+                last = Some((updated, details, watched));
+            }
+        });
+
+        match result {
+            // "next" on the last entry closes mpv, it was already counted as watched above
+            Ok(()) if mpris::take_next_requested() => {
+                if let Some((_, details, _)) = last {
+                    self.play_following(anime_id, &details.episode);
                 }
-                Action::PlayAnime(anime_id) => {
-                    self.play_anime(anime_id, 0);
+            }
+            Ok(()) => {
+                if let Some((updated, details, watched)) = last
+                    && Config::global().player.binge_mode
+                    && watched
+                    && details.fully_watched
+                {
+                    self.continue_binge(&updated, &details.episode);
                 }
-                Action::PlayEpisode(anime_id, episode) => {
-                    self.play_anime(anime_id, episode);
+            }
+            // let the user pick the show when the search isnt sure
+            Err(PlayError::AmbiguousMatch(candidates)) => {
+                self.screen_manager
+                    .show_source_picker(&anime, Resume::Playlist(episodes), candidates);
+            }
+            Err(e) => {
+                self.screen_manager.show_error(e.to_string());
+            }
+        }
+
+        crossterm::execute!(std::io::stderr(), EnableMouseCapture).ok();
+        self.terminal = ratatui::init();
+        None
+    }
+
+    // plays the episode after the given one right away, when it is out
+    fn play_following(&mut self, anime_id: AnimeId, episode: &str) {
+        let Some(anime) = self.shared_info.anime_store.get(&anime_id) else {
+            return;
+        };
+        let Ok(episode) = episode.parse::<u32>() else {
+            return;
+        };
+
+        let is_last = anime.num_episodes != 0 && episode >= anime.num_episodes;
+        let is_newest = anime.num_released_episodes.is_some_and(|released| episode >= released);
+        if !is_last && !is_newest {
+            self.play_anime(anime_id, Some((episode + 1).to_string()));
+        }
+    }
+
+    // queues up the next episode, or the sequel once the season is done
+    fn continue_binge(&mut self, anime: &Anime, episode: &str) {
+        // specials are not part of the regular episode order
+        let Ok(episode) = episode.parse::<u32>() else {
+            return;
+        };
+
+        if anime.num_episodes != 0 && episode >= anime.num_episodes {
+            self.screen_manager.offer_sequel(anime);
+            return;
+        }
+
+        // nothing newer has aired yet
+        if let Some(released) = anime.num_released_episodes
+            && episode >= released
+        {
+            return;
+        }
+
+        self.screen_manager.start_binge(anime, episode + 1);
+    }
+
+    fn select_source(
+        &mut self,
+        anime_id: AnimeId,
+        candidate: player::ShowCandidate,
+        resume: Resume,
+    ) {
+        let mut mappings = SourceMappings::load();
+        mappings.set(
+            anime_id,
+            SourceMapping {
+                provider: candidate.provider,
+                show_id: candidate.id,
+                name: candidate.name,
+            },
+        );
+
+        if let Err(e) = mappings.save() {
+            self.screen_manager.show_error(e);
+            return;
+        }
+        // streams looked up for the show picked before are of no use now
+        player::cache::forget_anime(anime_id);
+
+        match resume {
+            Resume::Nothing => {}
+            Resume::Episode(episode) => {
+                self.play_anime(anime_id, Some(episode));
+            }
+            Resume::Playlist(episodes) => {
+                self.play_playlist(anime_id, episodes);
+            }
+            Resume::EpisodeList => self.screen_manager.show_episodes(anime_id),
+        }
+    }
+
+    // quitting is an action of the keymap like any other, ctrl+c by default
+    fn handle_input(&mut self, event: crossterm::event::Event) {
+        if let Some(action) = self.screen_manager.handle_input(event) {
+            self.handle_action(action);
+        }
+    }
+
+    fn handle_action(&mut self, action: Action) {
+        match action {
+            Action::SwitchScreen(screen_name) => {
+                self.screen_manager.change_screen(screen_name);
+            }
+            Action::ShowOverlay(anime_id) => {
+                self.screen_manager.toggle_overlay(anime_id);
+            }
+            Action::NavbarSelect(selected) => {
+                self.screen_manager.toggle_navbar(selected);
+            }
+            Action::PlayAnime(anime_id) => {
+                self.play_anime(anime_id, None);
+            }
+            Action::PlayEpisode(anime_id, episode) => {
+                self.play_anime(anime_id, Some(episode));
+            }
+            Action::ShowError(message) => {
+                self.screen_manager.show_error(message);
+            }
+            Action::ShowEpisodes(anime_id) => {
+                self.screen_manager.show_episodes(anime_id);
+            }
+            Action::DownloadEpisode(anime_id, episode) => {
+                if let Some(anime) = self.shared_info.anime_store.get(&anime_id) {
+                    self.shared_info.downloads.enqueue((*anime).clone(), episode);
                 }
-                Action::ShowError(message) => {
-                    self.screen_manager.show_error(message);
+            }
+            // the saved show stays until another one is picked, cancelling keeps it
+            Action::RematchSource(anime_id) => {
+                self.screen_manager.rematch_source(anime_id);
+            }
+            Action::SelectSource(anime_id, candidate, resume) => {
+                self.select_source(anime_id, candidate, resume);
+            }
+            Action::PlayEpisodes(anime_id, episodes) => {
+                self.play_playlist(anime_id, episodes);
+            }
+            Action::FinishEpisode(anime_id, details, watched, score) => {
+                self.finish_and_continue(anime_id, &details, watched, score);
+            }
+            Action::ShowCast(anime_id) => {
+                self.screen_manager.show_cast(anime_id);
+            }
+            Action::CastFinished(anime_id, details) => {
+                match self.shared_info.anime_store.get(&anime_id) {
+                    Some(anime) if Config::global().player.confirm_completion => {
+                        self.screen_manager.confirm_completion(&anime, details);
+                    }
+                    _ => {
+                        self.finish_and_continue(anime_id, &details, details.completed, None);
+                    }
                 }
-                Action::Quit => {
-                    self.is_running = false;
+            }
+            Action::ScrobbleEpisode(anime_id, details) => {
+                // rewatching an older episode shouldn't touch the list
+                let is_new = self.shared_info.anime_store.get(&anime_id).is_some_and(|anime| {
+                    details
+                        .episode
+                        .parse::<u32>()
+                        .is_ok_and(|episode| episode > anime.my_list_status.num_episodes_watched)
+                });
+                if is_new {
+                    self.finish_episode(anime_id, &details, true, None);
                 }
             }
+            Action::ReloadConfig => {
+                self.reload_config();
+            }
+            Action::ChangeSeason(year, season) => {
+                let update = BackgroundUpdate::new(SEASONS).set("season", (year, season));
+                self.screen_manager.open_with(SEASONS, update);
+            }
+            Action::FilterList(status) => {
+                let update = BackgroundUpdate::new(LIST).set("list_status", status);
+                self.screen_manager.open_with(LIST, update);
+            }
+            Action::ShowRanking(ranking) => {
+                let update = BackgroundUpdate::new(SEARCH).set("ranking", ranking);
+                self.screen_manager.open_with(SEARCH, update);
+            }
+            Action::LogOut => {
+                MalClient::log_out();
+                hooks::fire(&Config::global().hooks.on_logout, HookPayload::new("logout"));
+                self.screen_manager.change_screen(LAUNCH);
+            }
+            Action::Quit => {
+                self.is_running = false;
+            }
+        }
+    }
+
+    // the config still loads with problems in it, they are shown once at startup
+    fn show_config_issues(&mut self) {
+        let issues = Config::issues();
+        if issues.is_empty() {
+            return;
+        }
+        let lines: Vec<String> = issues.iter().map(|issue| issue.to_string()).collect();
+        self.screen_manager
+            .show_error(format!("Problems in the config:\n{}", lines.join("\n")));
+    }
+
+    // theme and keys are read on every use, so only what was set up at startup needs applying here
+    fn reload_config(&mut self) {
+        let reload = Config::reload();
+        let capture = Config::global().navigation.enable_mouse_capture;
+        if capture != reload.previous.navigation.enable_mouse_capture {
+            if capture {
+                crossterm::execute!(std::io::stderr(), EnableMouseCapture).ok();
+            } else {
+                crossterm::execute!(std::io::stderr(), DisableMouseCapture).ok();
+            }
+        }
+
+        let mut lines: Vec<String> = reload.issues.iter().map(|issue| issue.to_string()).collect();
+        if !reload.restart_keys.is_empty() {
+            lines.push(format!(
+                "Restart mal-cli to apply: {}",
+                reload.restart_keys.join(", ")
+            ));
+        }
+        if !lines.is_empty() {
+            self.screen_manager
+                .show_error(format!("Reloaded the config:\n{}", lines.join("\n")));
         }
     }
 
@@ -0,0 +1,687 @@
+mod output;
+
+use crate::app::Event;
+use crate::config::Config;
+use crate::config::validate::Severity;
+use crate::mal::MalClient;
+use crate::mal::models::anime::{
+    Anime, AnimeId, MyListStatus, correct_status, status_is_known,
+};
+use crate::player::{AnimePlayer, PlayResult, library};
+use crate::utils::errorBus;
+use output::Format;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io;
+use std::sync::mpsc::{self, Receiver};
+
+// biggest pages the api hands out, the user list allows more than the rest
+const LIST_PAGE: usize = 1000;
+const DEFAULT_LIMIT: usize = 25;
+
+const SEASONS: [&str; 4] = ["winter", "spring", "summer", "fall"];
+const RANKINGS: [&str; 9] = [
+    "all",
+    "airing",
+    "upcoming",
+    "tv",
+    "ova",
+    "movie",
+    "special",
+    "bypopularity",
+    "favorite",
+];
+
+pub const COMMANDS: [&str; 8] = [
+    "list", "search", "show", "set", "season", "top", "play", "config",
+];
+
+// why a command failed, each one exits with its own code
+#[derive(Debug)]
+pub enum CliError {
+    Request(String),
+    Usage(String),
+    NotLoggedIn,
+    Playback(String),
+    InvalidConfig(usize),
+    Output(io::Error),
+}
+
+impl CliError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Request(_) | CliError::Output(_) => 1,
+            CliError::Usage(_) => 2,
+            CliError::NotLoggedIn => 3,
+            CliError::Playback(_) => 4,
+            CliError::InvalidConfig(_) => 5,
+        }
+    }
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Request(msg) | CliError::Usage(msg) | CliError::Playback(msg) => {
+                write!(f, "{}", msg)
+            }
+            CliError::NotLoggedIn => write!(f, "not logged in, start mal-cli once to log in"),
+            CliError::InvalidConfig(count) => write!(f, "{} error(s) in the config", count),
+            CliError::Output(e) => write!(f, "could not print the output: {}", e),
+        }
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> Self {
+        CliError::Output(e)
+    }
+}
+
+type Result<T> = std::result::Result<T, CliError>;
+
+fn usage<T>(msg: impl Into<String>) -> Result<T> {
+    Err(CliError::Usage(msg.into()))
+}
+
+// if the arguments name a subcommand, global options may come before it
+pub fn is_command_line(args: &[String]) -> bool {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                args.next();
+            }
+            arg if arg.starts_with("--format=") => {}
+            arg => return COMMANDS.contains(&arg),
+        }
+    }
+    false
+}
+
+pub fn print_usage() {
+    println!("Commands:");
+    println!("  list [--status <status>]                   Show your anime list");
+    println!("  search <query> [--limit <n>]               Search for anime");
+    println!("  show <id>                                  Show the details of an anime");
+    println!("  set <id> [--status <status>] [--score <0-10>] [--episodes <n>]");
+    println!("                                             Update an anime on your list");
+    println!("  season <year> <season> [--limit <n>]       Show the anime of a season");
+    println!("  top [<ranking type>] [--limit <n>]         Show the top anime");
+    println!("  play <id> [--episode <n>]                  Play an episode and update your list");
+    println!("  config show                                Show every config key and where it was set");
+    println!("  config path                                Show where files are read from and saved");
+    println!("  config check                               Report problems in the config");
+    println!();
+    println!("  --format <format>    Print results as {}", Format::NAMES.join(", "));
+    println!();
+    println!("Statuses: watching, completed, on_hold, dropped, plan_to_watch");
+    println!("Seasons: {}", SEASONS.join(", "));
+    println!("Ranking types: {}", RANKINGS.join(", "));
+    println!();
+    println!("Exit codes: 0 success, 1 request failed, 2 invalid arguments, 3 not logged in,");
+    println!("            4 playback failed, 5 invalid config");
+}
+
+// positional arguments and "--name value" options of a subcommand
+struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+}
+
+impl Args {
+    fn parse(args: &[String], known: &[&str]) -> Result<Self> {
+        let mut positional = Vec::new();
+        let mut options = HashMap::new();
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let Some(option) = arg.strip_prefix("--") else {
+                positional.push(arg.clone());
+                continue;
+            };
+
+            // both "--score 8" and "--score=8"
+            let (name, value) = match option.split_once('=') {
+                Some((name, value)) => (name, value.to_string()),
+                None => match args.next() {
+                    Some(value) => (option, value.clone()),
+                    None => return usage(format!("--{} needs a value", option)),
+                },
+            };
+            if !known.contains(&name) {
+                return usage(format!("unknown option --{}", name));
+            }
+            options.insert(name.to_string(), value);
+        }
+
+        Ok(Self { positional, options })
+    }
+
+    fn positional(&self, index: usize, name: &str) -> Result<&str> {
+        match self.positional.get(index) {
+            Some(value) => Ok(value),
+            None => usage(format!("missing <{}>", name)),
+        }
+    }
+
+    fn option<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>> {
+        let Some(value) = self.options.get(name) else {
+            return Ok(None);
+        };
+        match value.parse() {
+            Ok(value) => Ok(Some(value)),
+            Err(_) => usage(format!("invalid value for --{}: {}", name, value)),
+        }
+    }
+
+    fn limit(&self) -> Result<usize> {
+        Ok(self.option("limit")?.unwrap_or(DEFAULT_LIMIT))
+    }
+}
+
+// runs a subcommand without starting the tui
+pub fn run(args: &[String]) -> Result<()> {
+    match run_command(args) {
+        // the reader stopped early, like "| head", everything it wanted was printed
+        Err(CliError::Output(e)) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => result,
+    }
+}
+
+fn run_command(args: &[String]) -> Result<()> {
+    let (format, args) = take_format(args)?;
+    let Some((command, args)) = args.split_first() else {
+        return usage("missing command");
+    };
+
+    // reading the config needs no login
+    if command == "config" {
+        return config(format, &Args::parse(args, &[])?);
+    }
+
+    // the client reports failures on the error bus, here they are printed instead of shown in a popup
+    let (tx, errors) = mpsc::channel();
+    errorBus::init(tx);
+
+    if !MalClient::user_is_logged_in() {
+        return Err(CliError::NotLoggedIn);
+    }
+    let cli = Cli {
+        client: MalClient::new(),
+        errors,
+        format,
+    };
+
+    match command.as_str() {
+        "list" => cli.list(&Args::parse(args, &["status"])?),
+        "search" => cli.search(&Args::parse(args, &["limit"])?),
+        "show" => cli.show(&Args::parse(args, &[])?),
+        "set" => cli.set(&Args::parse(args, &["status", "score", "episodes"])?),
+        "season" => cli.season(&Args::parse(args, &["limit"])?),
+        "top" => cli.top(&Args::parse(args, &["limit"])?),
+        "play" => cli.play(&Args::parse(args, &["episode"])?),
+        _ => usage(format!("unknown command {}", command)),
+    }
+}
+
+// --format can go anywhere, the rest is left for the subcommand
+fn take_format(args: &[String]) -> Result<(Format, Vec<String>)> {
+    let mut format = None;
+    let mut rest = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let name = match arg.strip_prefix("--format=") {
+            Some(name) => name,
+            None if arg == "--format" => match args.next() {
+                Some(name) => name,
+                None => return usage("--format needs a value"),
+            },
+            None => {
+                rest.push(arg.clone());
+                continue;
+            }
+        };
+        match Format::parse(name) {
+            Some(parsed) => format = Some(parsed),
+            None => {
+                return usage(format!(
+                    "unknown format {}, use one of: {}",
+                    name,
+                    Format::NAMES.join(", ")
+                ));
+            }
+        }
+    }
+
+    Ok((format.unwrap_or(Format::Table), rest))
+}
+
+// what play reports once mpv closes
+#[derive(Serialize)]
+struct Played<'a> {
+    anime_id: AnimeId,
+    title: &'a str,
+    result: &'a PlayResult,
+    progress_updated: bool,
+    my_list_status: &'a MyListStatus,
+}
+
+// where mal-cli keeps its files
+#[derive(Serialize)]
+struct ConfigPaths {
+    config_file: String,
+    data_dir: String,
+    cache_dir: String,
+    download_dir: String,
+}
+
+struct Cli {
+    client: MalClient,
+    errors: Receiver<Event>,
+    format: Format,
+}
+
+impl Cli {
+    // what the client sent to the error bus, or a general message when it sent nothing
+    fn fetched<T>(&self, value: Option<T>, what: &str) -> Result<T> {
+        value.ok_or_else(|| {
+            let errors: Vec<String> = self
+                .errors
+                .try_iter()
+                .filter_map(|event| match event {
+                    Event::ShowError(message) => Some(message),
+                    _ => None,
+                })
+                .collect();
+            if errors.is_empty() {
+                CliError::Request(format!("could not fetch {}", what))
+            } else {
+                CliError::Request(errors.join("\n"))
+            }
+        })
+    }
+
+    fn anime(&self, id: &str) -> Result<Anime> {
+        let Ok(id) = id.parse::<AnimeId>() else {
+            return usage(format!("invalid anime id: {}", id));
+        };
+        self.fetched(self.client.get_anime_details(id), &format!("anime {}", id))
+    }
+
+    fn list(&self, args: &Args) -> Result<()> {
+        let status = args
+            .option::<String>("status")?
+            .map(|status| parse_status(&status))
+            .transpose()?;
+
+        let mut animes = Vec::new();
+        loop {
+            let page = self.fetched(
+                self.client.get_anime_list(status.clone(), animes.len(), LIST_PAGE),
+                "your list",
+            )?;
+            let done = page.len() < LIST_PAGE;
+            animes.extend(page);
+            if done {
+                break;
+            }
+        }
+
+        let rows: Vec<Vec<String>> = animes
+            .iter()
+            .map(|anime| {
+                vec![
+                    anime.id.to_string(),
+                    anime.title.clone(),
+                    output::list_status(anime),
+                    output::number(anime.my_list_status.score),
+                    output::progress(anime),
+                ]
+            })
+            .collect();
+        output::records(
+            self.format,
+            &animes,
+            &["id", "title", "status", "score", "progress"],
+            &rows,
+        )?;
+        Ok(())
+    }
+
+    fn search(&self, args: &Args) -> Result<()> {
+        let query = args.positional.join(" ");
+        if query.is_empty() {
+            return usage("missing <query>");
+        }
+        let animes = self.fetched(
+            self.client.search_anime(query, 0, args.limit()?),
+            "search results",
+        )?;
+        self.print_animes(&animes, false)
+    }
+
+    fn show(&self, args: &Args) -> Result<()> {
+        let anime = self.anime(args.positional(0, "id")?)?;
+        self.print_details(&anime)
+    }
+
+    fn set(&self, args: &Args) -> Result<()> {
+        let status = args
+            .option::<String>("status")?
+            .map(|status| parse_status(&status))
+            .transpose()?;
+        let score: Option<u8> = args.option("score")?;
+        let episodes: Option<u32> = args.option("episodes")?;
+        if status.is_none() && score.is_none() && episodes.is_none() {
+            return usage("nothing to update, pass --status, --score or --episodes");
+        }
+        if score.is_some_and(|score| score > 10) {
+            return usage("the score goes from 0 to 10");
+        }
+
+        let mut anime = self.anime(args.positional(0, "id")?)?;
+
+        if let Some(status) = &status {
+            anime.my_list_status.status = status.clone();
+        } else if !status_is_known(anime.my_list_status.status.clone()) {
+            // updating an unknown status removes the anime from the list
+            return usage(format!(
+                "\"{}\" is not on your list, pass --status to add it",
+                anime.title
+            ));
+        }
+        if let Some(score) = score {
+            anime.my_list_status.score = score;
+        }
+        if let Some(episodes) = episodes {
+            let total = anime.num_episodes;
+            if total != 0 && episodes > total {
+                return usage(format!("\"{}\" only has {} episodes", anime.title, total));
+            }
+            anime.my_list_status.num_episodes_watched = episodes;
+            if status.is_none() && total != 0 && episodes == total {
+                anime.my_list_status.status = "completed".to_string();
+            }
+        }
+
+        self.update(&anime)?;
+        output::record(
+            self.format,
+            &anime,
+            &[
+                ("id", anime.id.to_string()),
+                ("title", anime.title.clone()),
+                ("status", output::list_status(&anime)),
+                ("score", output::number(anime.my_list_status.score)),
+                ("progress", output::progress(&anime)),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn season(&self, args: &Args) -> Result<()> {
+        let year = args.positional(0, "year")?;
+        let Ok(year) = year.parse::<u16>() else {
+            return usage(format!("invalid year: {}", year));
+        };
+        let season = args.positional(1, "season")?.to_lowercase();
+        if !SEASONS.contains(&season.as_str()) {
+            return usage(format!(
+                "unknown season {}, use one of: {}",
+                season,
+                SEASONS.join(", ")
+            ));
+        }
+
+        let animes = self.fetched(
+            self.client.get_seasonal_anime(year, season, 0, args.limit()?),
+            "the season",
+        )?;
+        self.print_animes(&animes, false)
+    }
+
+    fn top(&self, args: &Args) -> Result<()> {
+        let ranking = args
+            .positional
+            .first()
+            .map_or("all".to_string(), |ranking| ranking.to_lowercase());
+        if !RANKINGS.contains(&ranking.as_str()) {
+            return usage(format!(
+                "unknown ranking type {}, use one of: {}",
+                ranking,
+                RANKINGS.join(", ")
+            ));
+        }
+
+        let animes = self.fetched(
+            self.client.get_top_anime(ranking, 0, args.limit()?),
+            "the ranking",
+        )?;
+        self.print_animes(&animes, true)
+    }
+
+    fn play(&self, args: &Args) -> Result<()> {
+        let mut anime = self.anime(args.positional(0, "id")?)?;
+        let watched = anime.my_list_status.num_episodes_watched;
+        let episode = match args.option::<String>("episode")? {
+            Some(episode) => episode,
+            None if anime.num_episodes == 0 => (watched + 1).to_string(),
+            None => (watched + 1).min(anime.num_episodes).to_string(),
+        };
+
+        library::scan();
+        let details = AnimePlayer::new()
+            .quiet_hooks()
+            .play_episode_manually(&anime, &episode)
+            .map_err(|e| CliError::Playback(e.to_string()))?;
+
+        // specials like "12.5" dont count towards the progress on mal
+        let newly_watched = episode
+            .parse::<u32>()
+            .ok()
+            .filter(|&number| details.completed && number > watched);
+        if let Some(number) = newly_watched {
+            anime.my_list_status.status = "watching".to_string();
+            anime.mark_watched(number);
+            self.update(&anime)?;
+        }
+        let progress_updated = newly_watched.is_some();
+
+        if self.format == Format::Table {
+            let message = match (details.completed, progress_updated) {
+                (true, true) => format!(
+                    "Watched episode {} of \"{}\", progress {}",
+                    episode,
+                    anime.title,
+                    output::progress(&anime)
+                ),
+                (true, false) => format!(
+                    "Watched episode {} of \"{}\", progress unchanged",
+                    episode, anime.title
+                ),
+                _ => format!(
+                    "Stopped episode {} of \"{}\" at {} / {}, progress unchanged",
+                    episode, anime.title, details.current_time, details.total_time
+                ),
+            };
+            output::text(&message)?;
+            return Ok(());
+        }
+
+        let played = Played {
+            anime_id: anime.id,
+            title: &anime.title,
+            result: &details,
+            progress_updated,
+            my_list_status: &anime.my_list_status,
+        };
+        output::record(
+            self.format,
+            &played,
+            &[
+                ("anime_id", anime.id.to_string()),
+                ("title", anime.title.clone()),
+                ("episode", details.episode.clone()),
+                ("current_time", details.current_time.clone()),
+                ("total_time", details.total_time.clone()),
+                ("percentage", details.percentage.to_string()),
+                ("completed", details.completed.to_string()),
+                ("progress_updated", progress_updated.to_string()),
+                ("progress", output::progress(&anime)),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn update(&self, anime: &Anime) -> Result<()> {
+        self.client
+            .update_user_list(anime.clone())
+            .map(|_| ())
+            .map_err(|e| CliError::Request(format!("could not update \"{}\": {}", anime.title, e)))
+    }
+
+    fn print_animes(&self, animes: &[Anime], ranked: bool) -> Result<()> {
+        let rows: Vec<Vec<String>> = animes
+            .iter()
+            .map(|anime| {
+                let mut row = vec![
+                    anime.id.to_string(),
+                    anime.title.clone(),
+                    anime.media_type.clone(),
+                    output::number(anime.num_episodes),
+                    output::mean(anime),
+                    output::list_status(anime),
+                ];
+                if ranked {
+                    row.insert(0, output::number(anime.rank));
+                }
+                row
+            })
+            .collect();
+
+        let mut columns = vec!["id", "title", "type", "episodes", "mean", "list"];
+        if ranked {
+            columns.insert(0, "rank");
+        }
+        output::records(self.format, animes, &columns, &rows)?;
+        Ok(())
+    }
+
+    fn print_details(&self, anime: &Anime) -> Result<()> {
+        let genres: Vec<String> = anime.genres.iter().map(|genre| genre.to_string()).collect();
+        let mut fields = vec![
+            ("id", anime.id.to_string()),
+            ("title", anime.title.clone()),
+            ("english", anime.alternative_titles.en.clone()),
+            ("japanese", anime.alternative_titles.ja.clone()),
+            ("type", anime.media_type.clone()),
+            ("status", anime.status.clone()),
+            ("episodes", output::number(anime.num_episodes)),
+            ("aired", format!("{} to {}", anime.start_date, anime.end_date)),
+            ("season", anime.start_season.to_string()),
+            ("studios", anime.studios_as_string()),
+            ("genres", genres.join(", ")),
+            ("mean", output::mean(anime)),
+            ("rank", output::number(anime.rank)),
+            ("popularity", output::number(anime.popularity)),
+            ("list", output::list_status(anime)),
+            ("score", output::number(anime.my_list_status.score)),
+            ("progress", output::progress(anime)),
+        ];
+
+        // a table shows the synopsis as a paragraph below the fields
+        if self.format != Format::Table {
+            fields.push(("synopsis", anime.synopsis.clone()));
+        }
+        output::record(self.format, anime, &fields)?;
+        if self.format == Format::Table {
+            output::text(&format!("\n{}", anime.synopsis))?;
+        }
+        Ok(())
+    }
+}
+
+fn config(format: Format, args: &Args) -> Result<()> {
+    match args.positional(0, "show, path or check")? {
+        "show" => {
+            let entries = Config::sources();
+            let rows: Vec<Vec<String>> = entries
+                .iter()
+                .map(|entry| {
+                    vec![
+                        entry.key.clone(),
+                        entry.value.to_string(),
+                        entry.source.to_string(),
+                    ]
+                })
+                .collect();
+            output::records(format, &entries, &["key", "value", "source"], &rows)?;
+        }
+        "path" => {
+            let paths = ConfigPaths {
+                config_file: Config::config_file().display().to_string(),
+                data_dir: Config::data_dir().display().to_string(),
+                cache_dir: Config::cache_dir().display().to_string(),
+                download_dir: Config::download_dir().display().to_string(),
+            };
+            output::record(
+                format,
+                &paths,
+                &[
+                    ("config_file", paths.config_file.clone()),
+                    ("data_dir", paths.data_dir.clone()),
+                    ("cache_dir", paths.cache_dir.clone()),
+                    ("download_dir", paths.download_dir.clone()),
+                ],
+            )?;
+        }
+        "check" => {
+            let issues = Config::issues();
+            let rows: Vec<Vec<String>> = issues
+                .iter()
+                .map(|issue| {
+                    vec![
+                        format!("{:?}", issue.severity).to_lowercase(),
+                        issue.origin.clone(),
+                        issue.key.clone().unwrap_or_else(|| "-".to_string()),
+                        issue.message.clone(),
+                    ]
+                })
+                .collect();
+            if issues.is_empty() && format == Format::Table {
+                output::text(&format!("{}: no problems found", Config::config_file().display()))?;
+            } else {
+                output::records(format, &issues, &["severity", "origin", "key", "message"], &rows)?;
+            }
+
+            let errors = issues
+                .iter()
+                .filter(|issue| issue.severity == Severity::Error)
+                .count();
+            if errors > 0 {
+                return Err(CliError::InvalidConfig(errors));
+            }
+        }
+        action => {
+            return usage(format!(
+                "unknown config action {}, use show, path or check",
+                action
+            ));
+        }
+    }
+    Ok(())
+}
+
+// "on hold", "on-hold" and "on_hold" all mean the same list
+fn parse_status(status: &str) -> Result<String> {
+    let corrected = correct_status(status.to_lowercase().replace('_', " "));
+    if !status_is_known(corrected.clone()) {
+        return usage(format!(
+            "unknown status {}, use one of: watching, completed, on_hold, dropped, plan_to_watch",
+            status
+        ));
+    }
+    Ok(corrected)
+}
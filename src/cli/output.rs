@@ -0,0 +1,190 @@
+use crate::mal::models::anime::{Anime, status_is_known};
+use serde::Serialize;
+use std::io::{self, Write};
+
+// longest title shown in a table before it gets cut
+const MAX_CELL: usize = 50;
+
+// how results are printed, picked with --format
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Table,
+    Json,
+    Ndjson,
+    Tsv,
+}
+
+impl Format {
+    pub const NAMES: [&str; 4] = ["table", "json", "ndjson", "tsv"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "table" => Some(Self::Table),
+            "json" => Some(Self::Json),
+            "ndjson" => Some(Self::Ndjson),
+            "tsv" => Some(Self::Tsv),
+            _ => None,
+        }
+    }
+}
+
+// a list of records, json gets the whole items, tables and tsv get the columns
+pub fn records<T: Serialize>(
+    format: Format,
+    items: &[T],
+    columns: &[&str],
+    rows: &[Vec<String>],
+) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    match format {
+        Format::Table => {
+            let headers: Vec<String> = columns.iter().map(|c| c.to_uppercase()).collect();
+            // long titles would push the other columns off the screen
+            let cut_column = columns.iter().position(|column| *column == "title");
+            table(&mut out, &headers, rows, cut_column)
+        }
+        Format::Tsv => {
+            tsv_row(&mut out, columns.iter().map(|c| c.to_string()))?;
+            for row in rows {
+                tsv_row(&mut out, row.iter().cloned())?;
+            }
+            Ok(())
+        }
+        // the models always serialize, their fields are plain data
+        Format::Json => writeln!(out, "{}", serde_json::to_string_pretty(items).unwrap_or_default()),
+        Format::Ndjson => {
+            for item in items {
+                writeln!(out, "{}", serde_json::to_string(item).unwrap_or_default())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+// a single record, fields are (key, value) pairs for tables and tsv
+pub fn record<T: Serialize>(format: Format, item: &T, fields: &[(&str, String)]) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    match format {
+        Format::Table => {
+            let width = fields.iter().map(|(key, _)| key.len() + 1).max().unwrap_or(0);
+            for (key, value) in fields {
+                writeln!(out, "{:<width$} {}", format!("{}:", label(key)), value)?;
+            }
+            Ok(())
+        }
+        Format::Tsv => {
+            tsv_row(&mut out, fields.iter().map(|(key, _)| key.to_string()))?;
+            tsv_row(&mut out, fields.iter().map(|(_, value)| value.clone()))
+        }
+        Format::Json => writeln!(out, "{}", serde_json::to_string_pretty(item).unwrap_or_default()),
+        Format::Ndjson => writeln!(out, "{}", serde_json::to_string(item).unwrap_or_default()),
+    }
+}
+
+// plain text for the table format, like messages and the synopsis
+pub fn text(text: &str) -> io::Result<()> {
+    writeln!(io::stdout().lock(), "{}", text)
+}
+
+// "num_episodes" -> "Num episodes"
+fn label(key: &str) -> String {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect::<String>().replace('_', " "))
+        .unwrap_or_default()
+}
+
+fn table(
+    out: &mut impl Write,
+    headers: &[String],
+    rows: &[Vec<String>],
+    cut_column: Option<usize>,
+) -> io::Result<()> {
+    let rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .enumerate()
+                .map(|(i, cell)| if Some(i) == cut_column { cut(cell) } else { cell.clone() })
+                .collect()
+        })
+        .collect();
+
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    table_row(out, headers, &widths)?;
+    for row in &rows {
+        table_row(out, row, &widths)?;
+    }
+    Ok(())
+}
+
+fn table_row(out: &mut impl Write, row: &[String], widths: &[usize]) -> io::Result<()> {
+    let last = row.len().saturating_sub(1);
+    let line: Vec<String> = row
+        .iter()
+        .zip(widths)
+        .enumerate()
+        // the last column isn't padded so lines don't end in spaces
+        .map(|(i, (cell, width))| if i == last { cell.clone() } else { format!("{:<width$}", cell) })
+        .collect();
+    writeln!(out, "{}", line.join("  "))
+}
+
+fn cut(cell: &str) -> String {
+    if cell.chars().count() <= MAX_CELL {
+        return cell.to_string();
+    }
+    let cut: String = cell.chars().take(MAX_CELL - 1).collect();
+    format!("{}…", cut)
+}
+
+// tabs and line breaks inside values would break the columns
+fn tsv_row(out: &mut impl Write, cells: impl Iterator<Item = String>) -> io::Result<()> {
+    let cells: Vec<String> = cells
+        .map(|cell| cell.replace(['\t', '\n', '\r'], " "))
+        .collect();
+    writeln!(out, "{}", cells.join("\t"))
+}
+
+// "-" for the zeros the api sends when something isn't known
+pub fn number<T: PartialEq + Default + ToString>(value: T) -> String {
+    if value == T::default() {
+        "-".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn mean(anime: &Anime) -> String {
+    if anime.mean == 0.0 {
+        "-".to_string()
+    } else {
+        format!("{:.2}", anime.mean)
+    }
+}
+
+// "3/12", or "3/?" while the episode count isn't decided
+pub fn progress(anime: &Anime) -> String {
+    format!(
+        "{}/{}",
+        anime.my_list_status.num_episodes_watched,
+        number(anime.num_episodes).replace('-', "?")
+    )
+}
+
+// the status on the user's list, "-" when it isn't on it
+pub fn list_status(anime: &Anime) -> String {
+    let status = &anime.my_list_status.status;
+    if status_is_known(status.clone()) {
+        status.clone()
+    } else {
+        "-".to_string()
+    }
+}
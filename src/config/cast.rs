@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+fn def_discovery_seconds() -> u64 {
+    3
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Cast {
+    // how long renderers get to answer the search, in seconds
+    #[serde(default = "def_discovery_seconds")]
+    pub discovery_seconds: u64,
+
+    // port of the local server passing streams to the renderer, 0 picks a free one
+    #[serde(default)]
+    pub proxy_port: u16,
+}
+
+impl Default for Cast {
+    fn default() -> Self {
+        Self {
+            discovery_seconds: def_discovery_seconds(),
+            proxy_port: 0,
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+fn def_max_concurrent() -> usize {
+    2
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Download {
+    // where downloaded episodes are saved, defaults to <data dir>/downloads
+    pub directory: Option<String>,
+
+    // how many episodes are downloaded at the same time
+    #[serde(default = "def_max_concurrent")]
+    pub max_concurrent: usize,
+}
+
+impl Default for Download {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            max_concurrent: def_max_concurrent(),
+        }
+    }
+}
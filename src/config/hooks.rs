@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+// commands run on app events, each gets a json payload on stdin
+// the playback hooks live in [player]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Hooks {
+    /// when mal-cli starts
+    pub on_start: Option<String>,
+
+    /// when mal-cli exits, mal-cli waits for it to finish
+    pub on_exit: Option<String>,
+
+    /// after logging in to MyAnimeList
+    pub on_login: Option<String>,
+
+    /// after logging out
+    pub on_logout: Option<String>,
+
+    /// when the status, score or progress of an anime on the list changes
+    /// Replaces: {title}, {status}, {episode}
+    pub on_status_change: Option<String>,
+
+    /// when an episode was watched far enough to count as completed
+    /// Replaces: {title}, {episode}
+    pub on_episode_complete: Option<String>,
+}
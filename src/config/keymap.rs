@@ -0,0 +1,769 @@
+use super::navigation::{NavDirection, Navigation};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+// how long the app waits for the next key of a sequence like "g p"
+const SEQUENCE_TIMEOUT: Duration = Duration::from_millis(1000);
+
+// everything a key can be bound to
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAction {
+    // bound in [navigation] unless the keymap says otherwise
+    Up,
+    Down,
+    Left,
+    Right,
+    Select,
+    Close,
+    Download,
+    Playlist,
+
+    // moving between the parts of a screen, ctrl + the navigation keys by default
+    FocusUp,
+    FocusDown,
+    FocusLeft,
+    FocusRight,
+
+    // the anime that is selected, or the one in the popup
+    Play,
+    PlayFromStart,
+    PickEpisode,
+    OpenInBrowser,
+    ChangeSource,
+    ToggleSkip,
+    Cast,
+
+    FocusSearch,
+    NextFilter,
+    Refresh,
+
+    // through the screens and anime that were opened before
+    Back,
+    Forward,
+
+    Help,
+    CommandPalette,
+    Quit,
+}
+
+impl KeyAction {
+    pub const ALL: [KeyAction; 27] = [
+        KeyAction::Up,
+        KeyAction::Down,
+        KeyAction::Left,
+        KeyAction::Right,
+        KeyAction::Select,
+        KeyAction::Close,
+        KeyAction::Download,
+        KeyAction::Playlist,
+        KeyAction::FocusUp,
+        KeyAction::FocusDown,
+        KeyAction::FocusLeft,
+        KeyAction::FocusRight,
+        KeyAction::Play,
+        KeyAction::PlayFromStart,
+        KeyAction::PickEpisode,
+        KeyAction::OpenInBrowser,
+        KeyAction::ChangeSource,
+        KeyAction::ToggleSkip,
+        KeyAction::Cast,
+        KeyAction::FocusSearch,
+        KeyAction::NextFilter,
+        KeyAction::Refresh,
+        KeyAction::Back,
+        KeyAction::Forward,
+        KeyAction::Help,
+        KeyAction::CommandPalette,
+        KeyAction::Quit,
+    ];
+
+    // what the action does, for the help
+    pub fn description(&self) -> &'static str {
+        match self {
+            KeyAction::Up => "move up",
+            KeyAction::Down => "move down",
+            KeyAction::Left => "move left",
+            KeyAction::Right => "move right",
+            KeyAction::Select => "open or pick the highlighted item",
+            KeyAction::Close => "close",
+            KeyAction::Download => "download the highlighted episode",
+            KeyAction::Playlist => "mark the first, then the last episode to play",
+            KeyAction::FocusUp => "go to the part above",
+            KeyAction::FocusDown => "go to the part below",
+            KeyAction::FocusLeft => "go to the part on the left",
+            KeyAction::FocusRight => "go to the part on the right",
+            KeyAction::Play => "play the next episode",
+            KeyAction::PlayFromStart => "play from the first episode",
+            KeyAction::PickEpisode => "pick an episode",
+            KeyAction::OpenInBrowser => "open the MyAnimeList page",
+            KeyAction::ChangeSource => "change the streaming source",
+            KeyAction::ToggleSkip => "skip openings and endings",
+            KeyAction::Cast => "cast to a tv",
+            KeyAction::FocusSearch => "go to the search field",
+            KeyAction::NextFilter => "switch to the next filter",
+            KeyAction::Refresh => "reload the screen",
+            KeyAction::Back => "go back to where you were",
+            KeyAction::Forward => "go forward again",
+            KeyAction::Help => "show the keys",
+            KeyAction::CommandPalette => "run a command or jump to an anime",
+            KeyAction::Quit => "quit mal-cli",
+        }
+    }
+}
+
+// where a key was pressed, each can have its own bindings
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyContext {
+    Launch,
+    Login,
+    Info,
+    Overview,
+    Seasons,
+    Search,
+    List,
+    Downloads,
+    Profile,
+    Settings,
+    Navbar,
+    AnimePopup,
+    EpisodePopup,
+    SourcePopup,
+    CastPopup,
+    BingePopup,
+    CompletionPopup,
+    ErrorPopup,
+    HelpPopup,
+    CommandPalette,
+}
+
+impl KeyContext {
+    pub const ALL: [KeyContext; 20] = [
+        KeyContext::Launch,
+        KeyContext::Login,
+        KeyContext::Info,
+        KeyContext::Overview,
+        KeyContext::Seasons,
+        KeyContext::Search,
+        KeyContext::List,
+        KeyContext::Downloads,
+        KeyContext::Profile,
+        KeyContext::Settings,
+        KeyContext::Navbar,
+        KeyContext::AnimePopup,
+        KeyContext::EpisodePopup,
+        KeyContext::SourcePopup,
+        KeyContext::CastPopup,
+        KeyContext::BingePopup,
+        KeyContext::CompletionPopup,
+        KeyContext::ErrorPopup,
+        KeyContext::HelpPopup,
+        KeyContext::CommandPalette,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            KeyContext::Launch => "Launch",
+            KeyContext::Login => "Login",
+            KeyContext::Info => "Info",
+            KeyContext::Overview => "Overview",
+            KeyContext::Seasons => "Seasons",
+            KeyContext::Search => "Search",
+            KeyContext::List => "List",
+            KeyContext::Downloads => "Downloads",
+            KeyContext::Profile => "Profile",
+            KeyContext::Settings => "Settings",
+            KeyContext::Navbar => "Tabs",
+            KeyContext::AnimePopup => "Anime",
+            KeyContext::EpisodePopup => "Episodes",
+            KeyContext::SourcePopup => "Source",
+            KeyContext::CastPopup => "Cast",
+            KeyContext::BingePopup => "Next Episode",
+            KeyContext::CompletionPopup => "Episode Watched",
+            KeyContext::ErrorPopup => "Error",
+            KeyContext::HelpPopup => "Help",
+            KeyContext::CommandPalette => "Commands",
+        }
+    }
+
+    // the actions that do something here, screens with several parts can narrow it down
+    pub fn actions(&self) -> &'static [KeyAction] {
+        use KeyAction::*;
+        match self {
+            KeyContext::Launch | KeyContext::Login | KeyContext::Info => &[Up, Down, Select],
+            KeyContext::Overview => &[Up, Down, Left, Right, Select, Play, PickEpisode, OpenInBrowser, FocusUp],
+            KeyContext::Seasons => &[Up, Down, Left, Right, Select, Play, PickEpisode, OpenInBrowser, FocusUp, FocusDown],
+            KeyContext::Search | KeyContext::List => &[
+                Up, Down, Left, Right, Select, Play, PickEpisode, OpenInBrowser,
+                FocusUp, FocusDown, FocusLeft, FocusRight, FocusSearch, NextFilter,
+            ],
+            KeyContext::Downloads => &[Up, Down, Select, Download, FocusUp],
+            KeyContext::Profile => &[Up, Down, Left, Right, Select, FocusUp],
+            KeyContext::Settings => &[Up, Down, Select, FocusUp],
+            KeyContext::Navbar => &[Left, Right, Select, FocusDown],
+            KeyContext::AnimePopup => &[
+                Up, Down, Left, Right, Select, Close,
+                Play, PlayFromStart, PickEpisode, OpenInBrowser, ChangeSource, ToggleSkip, Cast,
+            ],
+            KeyContext::EpisodePopup => &[Up, Down, Select, Download, Playlist, Close],
+            KeyContext::SourcePopup | KeyContext::CastPopup => &[Up, Down, Select, Close],
+            KeyContext::BingePopup => &[Select, Close],
+            KeyContext::CompletionPopup => &[Left, Right, Up, Down, Select, Close],
+            KeyContext::ErrorPopup => &[Close],
+            KeyContext::HelpPopup => &[Up, Down, Close],
+            KeyContext::CommandPalette => &[Up, Down, Select, Close],
+        }
+    }
+
+    // what the mouse does here
+    pub fn mouse(&self) -> &'static str {
+        match self {
+            KeyContext::Launch | KeyContext::Login | KeyContext::Info => "click a button to press it",
+            KeyContext::Overview
+            | KeyContext::Seasons
+            | KeyContext::Search
+            | KeyContext::List
+            | KeyContext::Profile => "click an anime to open it, scroll to move through the list, click a tab at the top to switch screens",
+            KeyContext::Downloads => "click a download to pick it, scroll to move through the list",
+            KeyContext::Settings => "click a setting to change it, scroll to move through the list",
+            KeyContext::Navbar => "click a tab to switch to it",
+            KeyContext::AnimePopup => "click a button to press it, the status buttons open a list, click outside to close",
+            KeyContext::EpisodePopup | KeyContext::SourcePopup | KeyContext::CastPopup => {
+                "click an entry to pick it, scroll to move through the list, click outside to close"
+            }
+            KeyContext::BingePopup | KeyContext::CompletionPopup => "click a button to press it",
+            KeyContext::ErrorPopup => "click anywhere to close it",
+            KeyContext::HelpPopup => "scroll to move, click anywhere to close it",
+            KeyContext::CommandPalette => "click an entry to run it, scroll to move, click outside to close",
+        }
+    }
+}
+
+// one key with its modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyPress {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyPress {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // shift is part of the character already, and of backtab
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self {
+            code,
+            modifiers: modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT),
+        }
+    }
+
+    // a key that types something in a text field
+    pub fn is_text(&self) -> bool {
+        matches!(self.code, KeyCode::Char(_))
+            && !self.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+    }
+
+    // "ctrl+k", "enter", "G"
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut parts: Vec<&str> = text.split('+').collect();
+        // "ctrl++" binds the plus key
+        if text.ends_with("++") {
+            parts.truncate(parts.len() - 2);
+            parts.push("+");
+        }
+        let key = parts.pop().unwrap_or_default();
+
+        for modifier in parts {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                other => return Err(format!("unknown modifier \"{}\" in \"{}\"", other, text)),
+            };
+        }
+
+        let code = match key.to_lowercase().as_str() {
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "enter" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "space" => KeyCode::Char(' '),
+            name if name.len() > 1 && name.starts_with('f') => match name[1..].parse::<u8>() {
+                Ok(number) if (1..=12).contains(&number) => KeyCode::F(number),
+                _ => return Err(format!("unknown key \"{}\"", key)),
+            },
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return Err(format!("unknown key \"{}\"", key)),
+                }
+            }
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl From<KeyEvent> for KeyPress {
+    fn from(event: KeyEvent) -> Self {
+        Self::new(event.code, event.modifiers)
+    }
+}
+
+impl fmt::Display for KeyPress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(number) => write!(f, "f{}", number),
+            code => write!(f, "{}", code.to_string().to_lowercase().replace(' ', "")),
+        }
+    }
+}
+
+// one or more keys pressed after each other, written "g p" in the config
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+pub struct KeyBinding(pub Vec<KeyPress>);
+
+impl KeyBinding {
+    fn single(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self(vec![KeyPress::new(code, modifiers)])
+    }
+}
+
+impl TryFrom<String> for KeyBinding {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        let keys = text
+            .split_whitespace()
+            .map(KeyPress::parse)
+            .collect::<Result<Vec<KeyPress>, String>>()?;
+        if keys.is_empty() {
+            return Err("a key binding can't be empty".to_string());
+        }
+        Ok(Self(keys))
+    }
+}
+
+impl From<KeyBinding> for String {
+    fn from(binding: KeyBinding) -> Self {
+        binding.to_string()
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys: Vec<String> = self.0.iter().map(|key| key.to_string()).collect();
+        write!(f, "{}", keys.join(" "))
+    }
+}
+
+type Bindings = BTreeMap<KeyAction, Vec<KeyBinding>>;
+
+// a sequence that was dropped because a shorter binding matches before it is finished
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shadowed {
+    // where it was set, eg. "keymap.screens.list.play"
+    pub key: String,
+    pub binding: KeyBinding,
+    pub by: (KeyAction, KeyBinding),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Keymap {
+    // replaces the keys of an action everywhere, eg. quit = ["ctrl+c", "ctrl+q"]
+    #[serde(default)]
+    pub global: Bindings,
+
+    // replaces the keys of an action on one screen or popup, eg. [keymap.screens.list]
+    #[serde(default)]
+    pub screens: BTreeMap<KeyContext, Bindings>,
+}
+
+impl Keymap {
+    // the keys of an action where it is used, the first one is what hints show
+    pub fn keys(&self, navigation: &Navigation, context: Option<KeyContext>, action: KeyAction) -> Vec<KeyBinding> {
+        context
+            .and_then(|context| self.screens.get(&context))
+            .and_then(|bindings| bindings.get(&action))
+            .or_else(|| self.global.get(&action))
+            .cloned()
+            .unwrap_or_else(|| default_keys(navigation, action))
+    }
+
+    // the first key of an action, for hints like "[p] play"
+    pub fn hint(&self, navigation: &Navigation, context: Option<KeyContext>, action: KeyAction) -> String {
+        self.keys(navigation, context, action)
+            .first()
+            .map(|binding| binding.to_string())
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    // the keys and what they do, one line per action that has keys
+    pub fn help(&self, navigation: &Navigation, context: Option<KeyContext>, actions: &[KeyAction]) -> Vec<(String, &'static str)> {
+        actions
+            .iter()
+            .filter_map(|action| {
+                let keys: Vec<String> = self
+                    .keys(navigation, context, *action)
+                    .iter()
+                    .map(|binding| binding.to_string())
+                    .collect();
+                (!keys.is_empty()).then(|| (keys.join(", "), action.description()))
+            })
+            .collect()
+    }
+
+    // takes out the sequences that start with another binding of the same screen, eg. "g p" when
+    // "g" is bound too, they could never be finished
+    pub fn drop_shadowed(&mut self, navigation: &Navigation) -> Vec<Shadowed> {
+        let mut dropped = Vec::new();
+        let contexts = std::iter::once(None).chain(KeyContext::ALL.into_iter().map(Some));
+        for context in contexts {
+            while let Some((action, binding, by)) = self.first_shadowed(navigation, context) {
+                // only sequences can be shadowed and the defaults have none, so it was set in the file
+                let overridden = context.filter(|context| {
+                    self.screens
+                        .get(context)
+                        .is_some_and(|bindings| bindings.contains_key(&action))
+                });
+                let (key, keys) = match overridden {
+                    Some(context) => (
+                        format!("keymap.screens.{}.{}", config_name(&context), config_name(&action)),
+                        self.screens.get_mut(&context).and_then(|b| b.get_mut(&action)),
+                    ),
+                    None => (format!("keymap.global.{}", config_name(&action)), self.global.get_mut(&action)),
+                };
+                match keys {
+                    Some(keys) => keys.retain(|key| *key != binding),
+                    None => break,
+                }
+                dropped.push(Shadowed { key, binding, by });
+            }
+        }
+        dropped
+    }
+
+    fn first_shadowed(
+        &self,
+        navigation: &Navigation,
+        context: Option<KeyContext>,
+    ) -> Option<(KeyAction, KeyBinding, (KeyAction, KeyBinding))> {
+        let bindings = self.bindings(navigation, context);
+        bindings.iter().find_map(|(action, binding)| {
+            bindings
+                .iter()
+                .find(|(_, shorter)| shorter.0.len() < binding.0.len() && binding.0.starts_with(&shorter.0))
+                .map(|by| (*action, binding.clone(), by.clone()))
+        })
+    }
+
+    // every binding in effect, the ones set for the context come first so they win
+    fn bindings(&self, navigation: &Navigation, context: Option<KeyContext>) -> Vec<(KeyAction, KeyBinding)> {
+        let overridden = context.and_then(|context| self.screens.get(&context));
+        let mut bindings: Vec<(u8, KeyAction, KeyBinding)> = Vec::new();
+        for action in KeyAction::ALL {
+            let (rank, keys) = match (overridden.and_then(|b| b.get(&action)), self.global.get(&action)) {
+                (Some(keys), _) => (0, keys.clone()),
+                (None, Some(keys)) => (1, keys.clone()),
+                (None, None) => (2, default_keys(navigation, action)),
+            };
+            bindings.extend(keys.into_iter().map(|binding| (rank, action, binding)));
+        }
+        bindings.sort_by_key(|(rank, _, _)| *rank);
+        bindings
+            .into_iter()
+            .map(|(_, action, binding)| (action, binding))
+            .collect()
+    }
+}
+
+// the name an action or screen has in the config file
+fn config_name<T: Serialize>(value: &T) -> String {
+    match toml::Value::try_from(value) {
+        Ok(toml::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+fn default_keys(navigation: &Navigation, action: KeyAction) -> Vec<KeyBinding> {
+    let plain = |codes: &[KeyCode]| -> Vec<KeyBinding> {
+        codes
+            .iter()
+            .map(|code| KeyBinding::single(*code, KeyModifiers::NONE))
+            .collect()
+    };
+    let ctrl = |codes: &[KeyCode]| -> Vec<KeyBinding> {
+        codes
+            .iter()
+            .map(|code| KeyBinding::single(*code, KeyModifiers::CONTROL))
+            .collect()
+    };
+    let char = |c: char| vec![KeyBinding::single(KeyCode::Char(c), KeyModifiers::NONE)];
+
+    match action {
+        KeyAction::Up => plain(&navigation.nav_up),
+        KeyAction::Down => plain(&navigation.nav_down),
+        KeyAction::Left => plain(&navigation.nav_left),
+        KeyAction::Right => plain(&navigation.nav_right),
+        KeyAction::Select => plain(&navigation.select),
+        KeyAction::Close => plain(&navigation.close),
+        KeyAction::Download => plain(&navigation.download),
+        KeyAction::Playlist => plain(&navigation.playlist),
+        KeyAction::FocusUp => ctrl(&navigation.nav_up),
+        KeyAction::FocusDown => ctrl(&navigation.nav_down),
+        KeyAction::FocusLeft => ctrl(&navigation.nav_left),
+        KeyAction::FocusRight => ctrl(&navigation.nav_right),
+        KeyAction::Play => char('w'),
+        KeyAction::PlayFromStart => char('W'),
+        KeyAction::PickEpisode => char('e'),
+        KeyAction::OpenInBrowser => char('o'),
+        KeyAction::ChangeSource => char('s'),
+        KeyAction::ToggleSkip => char('S'),
+        KeyAction::Cast => char('c'),
+        KeyAction::FocusSearch => char('/'),
+        KeyAction::NextFilter => char('f'),
+        KeyAction::Refresh => vec![
+            KeyBinding::single(KeyCode::Char('r'), KeyModifiers::NONE),
+            KeyBinding::single(KeyCode::F(5), KeyModifiers::NONE),
+        ],
+        KeyAction::Back => vec![
+            KeyBinding::single(KeyCode::Left, KeyModifiers::ALT),
+            KeyBinding::single(KeyCode::Char('b'), KeyModifiers::NONE),
+        ],
+        KeyAction::Forward => vec![
+            KeyBinding::single(KeyCode::Right, KeyModifiers::ALT),
+            KeyBinding::single(KeyCode::Char('B'), KeyModifiers::NONE),
+        ],
+        KeyAction::Help => char('?'),
+        KeyAction::CommandPalette => vec![
+            KeyBinding::single(KeyCode::Char(':'), KeyModifiers::NONE),
+            KeyBinding::single(KeyCode::Char('p'), KeyModifiers::CONTROL),
+        ],
+        KeyAction::Quit => vec![KeyBinding::single(KeyCode::Char('c'), KeyModifiers::CONTROL)],
+    }
+}
+
+// a key press and what it is bound to where it was pressed
+#[derive(Debug, Clone, Copy)]
+pub struct KeyInput {
+    pub event: KeyEvent,
+    pub action: Option<KeyAction>,
+}
+
+impl KeyInput {
+    // for clicks that do what a key would
+    pub fn from_action(action: KeyAction) -> Self {
+        Self {
+            event: KeyEvent::new(KeyCode::Null, KeyModifiers::NONE),
+            action: Some(action),
+        }
+    }
+
+    pub fn is(&self, action: KeyAction) -> bool {
+        self.action == Some(action)
+    }
+
+    pub fn direction(&self) -> NavDirection {
+        match self.action {
+            Some(KeyAction::Up) => NavDirection::Up,
+            Some(KeyAction::Down) => NavDirection::Down,
+            Some(KeyAction::Left) => NavDirection::Left,
+            Some(KeyAction::Right) => NavDirection::Right,
+            _ => NavDirection::None,
+        }
+    }
+
+    // where the focus is asked to move
+    pub fn focus_direction(&self) -> NavDirection {
+        match self.action {
+            Some(KeyAction::FocusUp) => NavDirection::Up,
+            Some(KeyAction::FocusDown) => NavDirection::Down,
+            Some(KeyAction::FocusLeft) => NavDirection::Left,
+            Some(KeyAction::FocusRight) => NavDirection::Right,
+            _ => NavDirection::None,
+        }
+    }
+}
+
+// the keys of a sequence pressed so far
+#[derive(Debug, Default)]
+pub struct KeySequence {
+    pending: Vec<KeyPress>,
+    last_press: Option<Instant>,
+}
+
+impl KeySequence {
+    // what a key press means, None while it could be the start of a longer binding
+    // when typing, plain characters are left as they are
+    pub fn press(
+        &mut self,
+        keymap: &Keymap,
+        navigation: &Navigation,
+        context: Option<KeyContext>,
+        event: KeyEvent,
+        typing: bool,
+    ) -> Option<KeyInput> {
+        let press = KeyPress::from(event);
+        if typing && press.is_text() {
+            self.pending.clear();
+            return Some(KeyInput { event, action: None });
+        }
+        if self.last_press.is_some_and(|time| time.elapsed() > SEQUENCE_TIMEOUT) {
+            self.pending.clear();
+        }
+
+        let bindings = keymap.bindings(navigation, context);
+        self.pending.push(press);
+        let pending = &self.pending;
+
+        if let Some((action, _)) = bindings.iter().find(|(_, binding)| binding.0 == *pending) {
+            let action = *action;
+            self.pending.clear();
+            return Some(KeyInput { event, action: Some(action) });
+        }
+
+        let is_prefix = bindings
+            .iter()
+            .any(|(_, binding)| binding.0.len() > pending.len() && binding.0.starts_with(pending));
+        if is_prefix {
+            self.last_press = Some(Instant::now());
+            return None;
+        }
+
+        // a sequence that went nowhere, the last key may still mean something on its own
+        let started_over = self.pending.len() > 1;
+        self.pending.clear();
+        if started_over {
+            return self.press(keymap, navigation, context, event, typing);
+        }
+        Some(KeyInput { event, action: None })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(text: &str) -> KeyBinding {
+        KeyBinding::try_from(text.to_string()).unwrap()
+    }
+
+    fn event(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    // "g r" refreshes everywhere, "x" plays on the list
+    fn keymap() -> Keymap {
+        let mut keymap = Keymap::default();
+        keymap.global.insert(KeyAction::Refresh, vec![binding("g r")]);
+        keymap
+            .screens
+            .insert(KeyContext::List, [(KeyAction::Play, vec![binding("x")])].into_iter().collect());
+        keymap
+    }
+
+    #[test]
+    fn parses_keys_with_modifiers() {
+        assert_eq!(KeyPress::parse("ctrl+k"), Ok(KeyPress::new(KeyCode::Char('k'), KeyModifiers::CONTROL)));
+        assert_eq!(KeyPress::parse("Alt+Enter"), Ok(KeyPress::new(KeyCode::Enter, KeyModifiers::ALT)));
+        assert_eq!(KeyPress::parse("ctrl++"), Ok(KeyPress::new(KeyCode::Char('+'), KeyModifiers::CONTROL)));
+        assert_eq!(KeyPress::parse("F5"), Ok(KeyPress::new(KeyCode::F(5), KeyModifiers::NONE)));
+        // shift is part of the character
+        assert_eq!(KeyPress::parse("shift+G"), KeyPress::parse("G"));
+        assert_eq!(KeyPress::parse("f13"), Err("unknown key \"f13\"".to_string()));
+        assert_eq!(KeyPress::parse("meta+k"), Err("unknown modifier \"meta\" in \"meta+k\"".to_string()));
+    }
+
+    #[test]
+    fn bindings_are_written_the_way_they_are_read() {
+        for text in ["k", "G", "ctrl+k", "alt+enter", "space", "esc", "backtab", "pagedown", "shift+up", "f12", "g p"] {
+            assert_eq!(binding(text).to_string(), text);
+        }
+        assert_eq!(binding("Ctrl+K  PageUp").to_string(), "ctrl+K pageup");
+        assert!(KeyBinding::try_from(" ".to_string()).is_err());
+    }
+
+    #[test]
+    fn sequences_fire_on_their_last_key() {
+        let (keymap, navigation) = (keymap(), Navigation::default());
+        let mut keys = KeySequence::default();
+
+        assert!(keys.press(&keymap, &navigation, None, event('g'), false).is_none());
+        let input = keys.press(&keymap, &navigation, None, event('r'), false).unwrap();
+        assert!(input.is(KeyAction::Refresh));
+
+        // the keymap replaces the default "r"
+        let input = keys.press(&keymap, &navigation, None, event('r'), false).unwrap();
+        assert_eq!(input.action, None);
+    }
+
+    #[test]
+    fn a_sequence_that_goes_nowhere_starts_over() {
+        let (keymap, navigation) = (keymap(), Navigation::default());
+        let mut keys = KeySequence::default();
+
+        assert!(keys.press(&keymap, &navigation, Some(KeyContext::List), event('g'), false).is_none());
+        let input = keys.press(&keymap, &navigation, Some(KeyContext::List), event('x'), false).unwrap();
+        assert!(input.is(KeyAction::Play));
+    }
+
+    #[test]
+    fn a_late_key_starts_over() {
+        let (keymap, navigation) = (keymap(), Navigation::default());
+        let mut keys = KeySequence::default();
+
+        assert!(keys.press(&keymap, &navigation, None, event('g'), false).is_none());
+        keys.last_press = Instant::now().checked_sub(SEQUENCE_TIMEOUT * 2);
+        let input = keys.press(&keymap, &navigation, None, event('r'), false).unwrap();
+        assert_eq!(input.action, None);
+        assert!(keys.pending.is_empty());
+
+        assert!(keys.press(&keymap, &navigation, None, event('g'), false).is_none());
+        keys.last_press = Instant::now().checked_sub(SEQUENCE_TIMEOUT * 2);
+        let input = keys.press(&keymap, &navigation, None, event('w'), false).unwrap();
+        assert!(input.is(KeyAction::Play));
+    }
+
+    #[test]
+    fn typing_keeps_plain_letters() {
+        let (keymap, navigation) = (keymap(), Navigation::default());
+        let mut keys = KeySequence::default();
+
+        let input = keys.press(&keymap, &navigation, Some(KeyContext::Search), event('r'), true).unwrap();
+        assert_eq!(input.action, None);
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        let input = keys.press(&keymap, &navigation, Some(KeyContext::Search), ctrl_c, true).unwrap();
+        assert!(input.is(KeyAction::Quit));
+    }
+}
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Library {
+    // directories scanned (recursively) for anime already on disk
+    #[serde(default)]
+    pub directories: Vec<String>,
+}
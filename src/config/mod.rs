@@ -1,87 +1,256 @@
+pub mod cast;
+pub mod download;
+pub mod hooks;
+pub mod keymap;
+pub mod library;
+pub mod mpris;
 pub mod navigation;
 pub mod network;
+pub mod overrides;
 pub mod player;
+pub mod skip;
 pub mod theme;
-
+pub mod validate;
+
+use cast::Cast;
+use download::Download;
+use hooks::Hooks;
+use keymap::Keymap;
+use library::Library;
+use mpris::Mpris;
 use navigation::Navigation;
 use network::Network;
 use player::Player;
+use skip::Skip;
 use theme::Theme;
+use validate::Issue;
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::process::Command;
-use std::sync::OnceLock;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::SystemTime;
+
 
+// swapped on a reload, the old one lives on until the last Config::global() handed out is dropped
+static CONFIG: RwLock<Option<Loaded>> = RwLock::new(None);
+static PATHS: OnceLock<Paths> = OnceLock::new();
+// when the app last saved the file itself, it reloads on its own so the watcher skips that change
+static WRITTEN: Mutex<Option<SystemTime>> = Mutex::new(None);
 
-static CONFIG: OnceLock<Config> = OnceLock::new();
+// keys only read when the app starts, a change to them is reported instead of applied
+const RESTART_KEYS: [&str; 3] = ["library.directories", "mpris.scrobble", "mpris.poll_interval"];
 
 
 const CONFIG_FILE: &str = "config.toml";
+const APP_DIR: &str = "mal-cli";
+
+// locations given on the command line, they win over the xdg ones
+#[derive(Debug, Default)]
+pub struct Paths {
+    pub config_file: Option<PathBuf>,
+    pub data_dir: Option<PathBuf>,
+}
+
+// a config together with what was wrong in the file it came from
+#[derive(Clone)]
+struct Loaded {
+    config: Arc<Config>,
+    issues: Arc<[Issue]>,
+}
+
+// what a reload changed
+pub struct Reload {
+    pub previous: Arc<Config>,
+    pub issues: Arc<[Issue]>,
+    // changed keys that only count after a restart
+    pub restart_keys: Vec<&'static str>,
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Config {
     #[serde(default = "Navigation::default")]
     pub navigation: Navigation,
 
+    #[serde(default = "Keymap::default")]
+    pub keymap: Keymap,
+
     #[serde(default = "Network::default")]
     pub network: Network,
 
     #[serde(default = "Player::default")]
     pub player: Player,
 
+    #[serde(default = "Skip::default")]
+    pub skip: Skip,
+
+    #[serde(default = "Download::default")]
+    pub download: Download,
+
+    #[serde(default = "Cast::default")]
+    pub cast: Cast,
+
+    #[serde(default = "Library::default")]
+    pub library: Library,
+
+    #[serde(default = "Mpris::default")]
+    pub mpris: Mpris,
+
+    #[serde(default = "Hooks::default")]
+    pub hooks: Hooks,
+
     #[serde(default = "Theme::default")]
     pub theme: Theme,
 }
 
 impl Config {
     // initialize the global config done before the app even runs 
-    pub fn init() -> &'static Config {
-        CONFIG.get_or_init(Self::read_from_file)
+    pub fn init() -> Arc<Config> {
+        let mut current = CONFIG.write().unwrap_or_else(|e| e.into_inner());
+        current.get_or_insert_with(Self::load).config.clone()
+    }
+
+    // tests run with the defaults instead of the user's file
+    #[cfg(test)]
+    pub fn init_defaults() -> Arc<Config> {
+        let mut current = CONFIG.write().unwrap_or_else(|e| e.into_inner());
+        let loaded = current.get_or_insert_with(|| Loaded {
+            config: Arc::new(Config::default()),
+            issues: Arc::from([]),
+        });
+        loaded.config.clone()
+    }
+
+    // reads the config file again, the new values are used from the next Config::global() on
+    pub fn reload() -> Reload {
+        let loaded = Self::load();
+        let mut current = CONFIG.write().unwrap_or_else(|e| e.into_inner());
+        let previous = current
+            .replace(loaded.clone())
+            .map(|previous| previous.config)
+            .unwrap_or_else(|| loaded.config.clone());
+
+        let (old, new) = (toml::Value::try_from(&*previous), toml::Value::try_from(&*loaded.config));
+        let restart_keys = match (old, new) {
+            (Ok(old), Ok(new)) => RESTART_KEYS
+                .into_iter()
+                .filter(|key| overrides::lookup_value(&old, key) != overrides::lookup_value(&new, key))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Reload {
+            previous,
+            issues: loaded.issues,
+            restart_keys,
+        }
+    }
+
+    fn load() -> Loaded {
+        let (config, issues) = validate::load(&Self::config_file());
+        Loaded {
+            config: Arc::new(config),
+            issues: issues.into(),
+        }
     }
 
-    // get the global config
-    pub fn global() -> &'static Config {
-        CONFIG.get().expect("Config not initialized - call Config::init() first")
+    // what was wrong with the config when it was last read
+    pub fn issues() -> Arc<[Issue]> {
+        CONFIG
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .map(|loaded| loaded.issues.clone())
+            .unwrap_or_else(|| Arc::from([]))
+    }
+
+    // get the global config, hold on to it no longer than needed so reloads are picked up
+    pub fn global() -> Arc<Config> {
+        let current = CONFIG.read().unwrap_or_else(|e| e.into_inner());
+        current
+            .as_ref()
+            .expect("Config not initialized - call Config::init() first")
+            .config
+            .clone()
     }
 
     pub fn default() -> Self {
         Self {
             navigation: Navigation::default(),
+            keymap: Keymap::default(),
             network: Network::default(),
             player: Player::default(),
+            skip: Skip::default(),
+            download: Download::default(),
+            cast: Cast::default(),
+            library: Library::default(),
+            mpris: Mpris::default(),
+            hooks: Hooks::default(),
             theme: Theme::default(),
         }
     }
 
 
-    // where configs are stored (default)
-    pub fn config_dir() -> PathBuf {
-        std::env::var("HOME")
-            .ok()
-            .map(|home| PathBuf::from(home).join(".config/mal-cli"))
-            .expect("Failed to get app directory")
+    // has to happen before the config is read, only the first call counts
+    pub fn set_paths(paths: Paths) {
+        PATHS.set(paths).ok();
+    }
+
+
+    // the file configs are read from, --config or $XDG_CONFIG_HOME/mal-cli/config.toml
+    pub fn config_file() -> PathBuf {
+        PATHS
+            .get()
+            .and_then(|paths| paths.config_file.clone())
+            .unwrap_or_else(|| xdg_dir("XDG_CONFIG_HOME", ".config").join(CONFIG_FILE))
     }
 
 
-    // where logging and such is saved (episodes watched)
+    // where logging and such is saved (episodes watched), --data-dir or $XDG_DATA_HOME/mal-cli
     pub fn data_dir() -> PathBuf {
-        std::env::var("HOME")
-            .ok()
-            .map(|home| PathBuf::from(home).join(".local/share/mal-cli"))
-            .expect("Failed to get app directory")
+        PATHS
+            .get()
+            .and_then(|paths| paths.data_dir.clone())
+            .unwrap_or_else(|| xdg_dir("XDG_DATA_HOME", ".local/share"))
+    }
+
+
+    // files that are made again when missing, $XDG_CACHE_HOME/mal-cli
+    pub fn cache_dir() -> PathBuf {
+        xdg_dir("XDG_CACHE_HOME", ".cache")
+    }
+
+
+    // where downloaded episodes are saved
+    pub fn download_dir() -> PathBuf {
+        Self::global()
+            .download
+            .directory
+            .as_deref()
+            .map(Self::expand_path)
+            .unwrap_or_else(|| Self::data_dir().join("downloads"))
+    }
+
+
+    // paths from the config can start with ~/
+    pub fn expand_path(path: &str) -> PathBuf {
+        match (path.strip_prefix("~/"), std::env::var("HOME")) {
+            (Some(rest), Ok(home)) => PathBuf::from(home).join(rest),
+            _ => PathBuf::from(path),
+        }
     }
 
 
     // used to update the config file with new configs
     pub fn save_to_file(config: &Config) {
-        let config_path = Self::config_dir();
-        if !config_path.exists() {
-            std::fs::create_dir_all(&config_path).unwrap_or_else(|_| {
+        let config_file_path = Self::config_file();
+        if let Some(config_path) = config_file_path.parent()
+            && !config_path.exists()
+        {
+            std::fs::create_dir_all(config_path).unwrap_or_else(|_| {
                 eprintln!("Failed to create config directory");
             });
         }
-        let config_file_path = config_path.join(CONFIG_FILE);
         let toml = toml::to_string(&config)
             .map_err(|e| {
                 eprintln!("Failed to serialize config: {}", e);
@@ -96,8 +265,7 @@ impl Config {
 
     // creates default configs if no file exists already
     fn create_if_not_exists() {
-        let config_path = Self::config_dir().join(CONFIG_FILE);
-        if !config_path.exists() {
+        if !Self::config_file().exists() {
             Self::save_to_file(&Config::default());
         }
     }
@@ -111,41 +279,84 @@ impl Config {
             .or_else(|_| std::env::var("VISUAL"))
             .unwrap_or("nano".to_string());
 
-        let config_path = Self::config_dir().join(CONFIG_FILE);
+        let config_path = Self::config_file();
 
         Command::new(editor)
-            .arg(config_path)
+            .arg(&config_path)
             .status()
             .map_err(|e| {
                 eprintln!(
-                    "Failed to open editor: {} try edit manually: ~/.config/mal-cli/config.toml",
-                    e
+                    "Failed to open editor: {} try edit manually: {}",
+                    e,
+                    config_path.display()
                 );
             })
             .ok();
     }
 
 
-    // read the configs
-    pub fn read_from_file() -> Config {
-        // in case file generation fails use default
-        let config_path = Self::config_dir().join(CONFIG_FILE);
-        if !config_path.exists() {
-            return Config::default();
+    // changes one key, eg. "theme.primary", and saves the file, None removes it
+    // the rest of the file is kept as it is, the values set by environment variables stay out of it
+    pub fn set_key(key: &str, value: Option<toml::Value>) -> Result<(), String> {
+        // checked on its own, keys the file already has wrong don't stop other changes
+        if let Some(value) = &value {
+            let mut table = toml::Table::try_from(Config::default()).map_err(|e| e.to_string())?;
+            overrides::insert(&mut table, key, value.clone());
+            toml::Value::Table(table)
+                .try_into::<Config>()
+                .map_err(|e| e.message().trim().to_string())?;
         }
 
-        // println!("Loading config from: {:?}", config_path);
+        let path = Self::config_file();
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        std::fs::write(&path, validate::with_key(&contents, key, value.as_ref()))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        *WRITTEN.lock().unwrap() = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        Ok(())
+    }
 
-        let contents = std::fs::read_to_string(&config_path).unwrap_or_else(|_| {
-            eprintln!("Failed to read config file, using default");
-            String::new()
-        });
+    // the modification time the app left the config file at, if it saved it
+    pub fn written() -> Option<SystemTime> {
+        *WRITTEN.lock().unwrap()
+    }
 
-        let config: Config = toml::from_str(&contents).unwrap_or_else(|_| {
-            eprintln!("Failed to parse config file, using default");
-            Config::default()
-        });
 
-        config
+    // every key in use and where its value came from
+    pub fn sources() -> Vec<overrides::Entry> {
+        let issues = Self::issues();
+        let invalid: Vec<&str> = issues
+            .iter()
+            .filter_map(|issue| issue.key.as_deref())
+            .collect();
+        overrides::entries(
+            &Self::global(),
+            &validate::file_table(&Self::config_file()),
+            &Self::config_file().display().to_string(),
+            &invalid,
+        )
     }
 }
+
+
+// $<var>/mal-cli, or the fallback below $HOME, xdg says relative paths are to be ignored
+fn xdg_dir(var: &str, fallback: &str) -> PathBuf {
+    std::env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .filter(|home| !home.is_empty())
+                .map(|home| PathBuf::from(home).join(fallback))
+        })
+        // without a home there is no better place than the temp dir
+        .unwrap_or_else(std::env::temp_dir)
+        .join(APP_DIR)
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+fn def_true() -> bool {
+    true
+}
+
+fn def_poll_interval() -> u64 {
+    5
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Mpris {
+    // show the playing episode to desktop widgets and media keys
+    #[serde(default = "def_true")]
+    pub publish: bool,
+
+    // update mal when an episode is watched in a player started outside mal-cli
+    #[serde(default)]
+    pub scrobble: bool,
+
+    // only these players are followed, eg. ["mpv", "vlc"], all of them when empty
+    #[serde(default)]
+    pub players: Vec<String>,
+
+    // how often the players are checked, in seconds
+    #[serde(default = "def_poll_interval")]
+    pub poll_interval: u64,
+}
+
+impl Default for Mpris {
+    fn default() -> Self {
+        Self {
+            publish: true,
+            scrobble: false,
+            players: Vec::new(),
+            poll_interval: def_poll_interval(),
+        }
+    }
+}
@@ -34,11 +34,20 @@ fn def_close() -> Vec<KeyCode> {
     vec![KeyCode::Esc, KeyCode::Char('q')]
 }
 
+fn def_download() -> Vec<KeyCode> {
+    vec![KeyCode::Char('d')]
+}
+
+fn def_playlist() -> Vec<KeyCode> {
+    vec![KeyCode::Char('p')]
+}
+
 fn def_mouse_capture() -> bool {
     true
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct Navigation {
 
     // basic navigation in the application
@@ -60,6 +69,14 @@ pub struct Navigation {
     #[serde(default = "def_close")]
     pub close: Vec<KeyCode>,
 
+    // queue the hovered episode for download
+    #[serde(default = "def_download")]
+    pub download: Vec<KeyCode>,
+
+    // mark the first and then the last episode of a range to play in one go
+    #[serde(default = "def_playlist")]
+    pub playlist: Vec<KeyCode>,
+
     // enable mouse capture in the terimnal for mouse navigation 
     #[serde(default = "def_mouse_capture")]
     pub enable_mouse_capture: bool,
@@ -74,33 +91,9 @@ impl Default for Navigation {
             nav_right: def_right(),
             select: def_select(),
             close: def_close(),
+            download: def_download(),
+            playlist: def_playlist(),
             enable_mouse_capture: def_mouse_capture(),
         }
     }
 }
-impl Navigation {
-    // get the navigation direction based on the key pressed
-    pub fn get_direction(&self, key: &KeyCode) -> NavDirection {
-        if self.nav_up.contains(key) {
-            NavDirection::Up
-        } else if self.nav_down.contains(key) {
-            NavDirection::Down
-        } else if self.nav_left.contains(key) {
-            NavDirection::Left
-        } else if self.nav_right.contains(key) {
-            NavDirection::Right
-        } else {
-            NavDirection::None 
-        }
-    }
-
-    // if the select key is pressed
-    pub fn is_select(&self, key: &KeyCode) -> bool {
-        self.select.contains(key)
-    }
-
-    // if the close key is pressed
-    pub fn is_close(&self, key: &KeyCode) -> bool {
-        self.close.contains(key)
-    }
-}
@@ -13,6 +13,7 @@ fn def_max_port_retries() -> u16 {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct Network {
     // the backend used for oauth and refreshing of token
     #[serde(default = "def_auth_server")]
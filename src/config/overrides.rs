@@ -0,0 +1,171 @@
+use super::Config;
+use serde::Serialize;
+use std::fmt;
+use toml::{Table, Value};
+
+// MAL_CLI_NETWORK__AUTH_SERVER sets auth_server in [network]
+pub const ENV_PREFIX: &str = "MAL_CLI_";
+const ENV_SEPARATOR: &str = "__";
+
+// where the value of a key came from
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "name", rename_all = "lowercase")]
+pub enum Source {
+    Default,
+    File(String),
+    Env(String),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::File(path) => write!(f, "{}", path),
+            Source::Env(var) => write!(f, "${}", var),
+        }
+    }
+}
+
+// one key of the config as mal-cli uses it
+#[derive(Debug, Clone, Serialize)]
+pub struct Entry {
+    pub key: String,
+    pub value: Value,
+    pub source: Source,
+}
+
+// the MAL_CLI_ variables naming a key, as ("network.auth_server", variable, value), sorted by key
+pub fn env_overrides() -> Vec<(String, String, String)> {
+    let mut overrides: Vec<(String, String, String)> = std::env::vars()
+        .filter_map(|(var, value)| {
+            let key = var.strip_prefix(ENV_PREFIX)?;
+            let path: Vec<String> = key.split(ENV_SEPARATOR).map(str::to_lowercase).collect();
+            // MAL_CLI_DEBUG and the like aren't keys
+            if path.len() < 2 || path.iter().any(String::is_empty) {
+                return None;
+            }
+            Some((path.join("."), var, value))
+        })
+        .collect();
+    overrides.sort();
+    overrides
+}
+
+// puts the environment overrides on top of the file
+pub fn apply_env(table: &mut Table) {
+    let defaults = defaults();
+    for (key, _, value) in env_overrides() {
+        let value = parse_value(&value, lookup(&defaults, &key));
+        insert(table, &key, value);
+    }
+}
+
+// the effective config, key by key, with where each value came from, invalid keys are at their default
+pub fn entries(config: &Config, file: &Table, file_path: &str, invalid: &[&str]) -> Vec<Entry> {
+    let env = env_overrides();
+    let mut entries = Vec::new();
+    let effective = Value::try_from(config)
+        .ok()
+        .and_then(|value| value.as_table().cloned())
+        .unwrap_or_default();
+    flatten(&effective, "", &mut |key, value| {
+        let source = if invalid.contains(&key) {
+            Source::Default
+        } else if let Some((_, var, _)) = env.iter().find(|(k, _, _)| k == key) {
+            Source::Env(var.clone())
+        } else if lookup(file, key).is_some() {
+            Source::File(file_path.to_string())
+        } else {
+            Source::Default
+        };
+        entries.push(Entry {
+            key: key.to_string(),
+            value: value.clone(),
+            source,
+        });
+    });
+    entries
+}
+
+// the variable a key is set by, if any
+pub fn env_var_for(key: &str) -> Option<String> {
+    env_overrides()
+        .into_iter()
+        .find(|(k, _, _)| k == key)
+        .map(|(_, var, _)| var)
+}
+
+fn defaults() -> Table {
+    Value::try_from(Config::default())
+        .ok()
+        .and_then(|value| value.as_table().cloned())
+        .unwrap_or_default()
+}
+
+// strings stay strings, eg. a port in a url, everything else is read as toml and falls back to a string
+fn parse_value(value: &str, default: Option<&Value>) -> Value {
+    if let Some(Value::String(_)) = default {
+        return Value::String(value.to_string());
+    }
+    toml::from_str::<Table>(&format!("value = {}", value))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| Value::String(value.to_string()))
+}
+
+// a dotted key in a whole config turned into toml
+pub fn lookup_value<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    lookup(value.as_table()?, key)
+}
+
+fn lookup<'a>(table: &'a Table, key: &str) -> Option<&'a Value> {
+    let mut parts = key.split('.');
+    let mut value = table.get(parts.next()?)?;
+    for part in parts {
+        value = value.as_table()?.get(part)?;
+    }
+    Some(value)
+}
+
+pub fn insert(table: &mut Table, key: &str, value: Value) {
+    let Some((section, rest)) = key.split_once('.') else {
+        table.insert(key.to_string(), value);
+        return;
+    };
+    let entry = table
+        .entry(section.to_string())
+        .or_insert_with(|| Value::Table(Table::new()));
+    if !entry.is_table() {
+        *entry = Value::Table(Table::new());
+    }
+    if let Value::Table(inner) = entry {
+        insert(inner, rest, value);
+    }
+}
+
+pub fn remove(table: &mut Table, key: &str) {
+    match key.split_once('.') {
+        Some((section, rest)) => {
+            if let Some(Value::Table(inner)) = table.get_mut(section) {
+                remove(inner, rest);
+            }
+        }
+        None => {
+            table.remove(key);
+        }
+    }
+}
+
+fn flatten(table: &Table, prefix: &str, visit: &mut impl FnMut(&str, &Value)) {
+    for (name, value) in table {
+        let key = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+        match value {
+            Value::Table(inner) => flatten(inner, &key, visit),
+            value => visit(&key, value),
+        }
+    }
+}
@@ -1,6 +1,27 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+fn def_true() -> bool {
+    true
+}
+
+fn def_providers() -> Vec<String> {
+    vec!["allanime".to_string()]
+}
+
+fn def_completion_threshold() -> u8 {
+    90
+}
+
+fn def_stream_cache_minutes() -> u64 {
+    20
+}
+
+fn def_binge_countdown() -> u64 {
+    10
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct Player {
     /// Prevent the regular playback method and use an external player instead
     #[serde(default)]
@@ -10,6 +31,40 @@ pub struct Player {
     #[serde(default)]
     pub always_complete_episode: bool,
 
+    /// percentage of an episode that has to be watched for it to count
+    #[serde(default = "def_completion_threshold")]
+    pub completion_threshold: u8,
+
+    /// an episode also counts when stopped less than this many seconds before the end
+    pub completion_seconds_left: Option<u64>,
+
+    /// ask before marking an episode as watched, and offer to rate the anime
+    #[serde(default)]
+    pub confirm_completion: bool,
+
+    /// continue with the next episode after one has been watched to the end
+    #[serde(default)]
+    pub binge_mode: bool,
+
+    /// seconds to wait before the next episode starts in binge mode
+    #[serde(default = "def_binge_countdown")]
+    pub binge_countdown: u64,
+
+    /// subtitle language picked first when a stream or file has several, eg. "en"
+    pub subtitle_language: Option<String>,
+
+    /// resolve the next episode in the background while one plays
+    #[serde(default = "def_true")]
+    pub prefetch_next: bool,
+
+    /// how long a resolved stream is reused, 0 turns the cache off
+    #[serde(default = "def_stream_cache_minutes")]
+    pub stream_cache_minutes: u64,
+
+    /// stream providers tried in order until one has the episode
+    #[serde(default = "def_providers")]
+    pub providers: Vec<String>,
+
     /// Hook to run before playback starts
     /// Replaces: {title}, {episode}
     pub pre_playback_hook: Option<String>,
@@ -19,3 +74,23 @@ pub struct Player {
     /// referrer might be empty
     pub post_playback_hook: Option<String>,
 }
+
+impl Default for Player {
+    fn default() -> Self {
+        Self {
+            disable_default_player: false,
+            always_complete_episode: false,
+            completion_threshold: def_completion_threshold(),
+            completion_seconds_left: None,
+            confirm_completion: false,
+            binge_mode: false,
+            binge_countdown: def_binge_countdown(),
+            subtitle_language: None,
+            prefetch_next: true,
+            stream_cache_minutes: def_stream_cache_minutes(),
+            providers: def_providers(),
+            pre_playback_hook: None,
+            post_playback_hook: None,
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+fn def_true() -> bool {
+    true
+}
+
+fn def_api_url() -> String {
+    "https://api.aniskip.com".to_string()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Skip {
+    // look up opening/ending times and add them to mpv as chapters
+    #[serde(default = "def_true")]
+    pub enabled: bool,
+
+    // jump over the opening and ending, can be changed per anime in the popup
+    #[serde(default)]
+    pub auto_skip: bool,
+
+    // any aniskip compatible api, eg. a local stand-in
+    #[serde(default = "def_api_url")]
+    pub api_url: String,
+}
+
+impl Default for Skip {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            auto_skip: false,
+            api_url: def_api_url(),
+        }
+    }
+}
@@ -16,6 +16,7 @@ fn def_dropped() -> Color { Color::Rgb(163, 0, 0) }
 fn def_plan_to_watch() -> Color { Color::Rgb(176, 86, 255) }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct Theme {
     #[serde(default = "def_primary")]
     pub primary: Color,
@@ -0,0 +1,528 @@
+use super::overrides;
+use super::{Cast, Config, Download, Hooks, Keymap, Library, Mpris, Navigation, Network, Player, Skip, Theme};
+use crate::utils::stringManipulation::levenshtein_distance;
+use regex::Regex;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::path::Path;
+use toml::{Table, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+// something wrong in the config, the value it is about is left at its default
+#[derive(Debug, Clone, Serialize)]
+pub struct Issue {
+    pub severity: Severity,
+    // "config.toml:12", or the variable when it came from the environment
+    pub origin: String,
+    pub key: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        match &self.key {
+            Some(key) => write!(f, "{}: {}: {}: {}", self.origin, severity, key, self.message),
+            None => write!(f, "{}: {}: {}", self.origin, severity, self.message),
+        }
+    }
+}
+
+// reads the config, keeping every valid key and reporting the rest
+pub fn load(path: &Path) -> (Config, Vec<Issue>) {
+    let mut checker = Checker {
+        file: path.display().to_string(),
+        contents: String::new(),
+        env: overrides::env_overrides(),
+        issues: Vec::new(),
+    };
+    if path.exists() {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => checker.contents = contents,
+            Err(e) => checker.issue(
+                Severity::Error,
+                None,
+                None,
+                format!("could not be read ({}), using the defaults", e),
+            ),
+        }
+    }
+
+    let mut table = checker.parse();
+    overrides::apply_env(&mut table);
+
+    let mut config = Config {
+        navigation: checker.section::<Navigation>(&mut table, "navigation"),
+        keymap: checker.section::<Keymap>(&mut table, "keymap"),
+        network: checker.section::<Network>(&mut table, "network"),
+        player: checker.section::<Player>(&mut table, "player"),
+        skip: checker.section::<Skip>(&mut table, "skip"),
+        download: checker.section::<Download>(&mut table, "download"),
+        cast: checker.section::<Cast>(&mut table, "cast"),
+        library: checker.section::<Library>(&mut table, "library"),
+        mpris: checker.section::<Mpris>(&mut table, "mpris"),
+        hooks: checker.section::<Hooks>(&mut table, "hooks"),
+        theme: checker.section::<Theme>(&mut table, "theme"),
+    };
+
+    for shadowed in config.keymap.drop_shadowed(&config.navigation) {
+        let section = shadowed.key.rsplit_once('.').map_or("keymap", |(section, _)| section);
+        let origin = checker.origin(&shadowed.key, Some(section));
+        let (action, by) = shadowed.by;
+        checker.issue(
+            Severity::Error,
+            Some(origin),
+            Some(shadowed.key.clone()),
+            format!(
+                "\"{}\" starts with \"{}\" ({}) so it can never be finished, ignored",
+                shadowed.binding,
+                by,
+                action.description(),
+            ),
+        );
+    }
+
+    // whatever is left isn't a section mal-cli knows
+    for name in table.keys() {
+        let origin = checker.origin(name, None);
+        checker.issue(
+            Severity::Warning,
+            Some(origin),
+            Some(name.clone()),
+            "unknown section, ignored".to_string(),
+        );
+    }
+
+    (config, checker.issues)
+}
+
+// the keys of the file, sections with broken toml are left out
+pub fn file_table(path: &Path) -> Table {
+    file_table_of(&std::fs::read_to_string(path).unwrap_or_default())
+}
+
+// the file with one key, eg. "theme.primary", changed or removed, None removes it
+// only the lines of that key are touched, comments and keys mal-cli doesn't know stay
+pub fn with_key(contents: &str, key: &str, value: Option<&Value>) -> String {
+    let Some((section, name)) = key.split_once('.') else {
+        return contents.to_string();
+    };
+    let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+    let line = value.map(|value| format!("{} = {}", name, value));
+
+    match key_line(contents, section, name) {
+        Some(number) => {
+            let start = number - 1;
+            let end = value_end(&lines, start);
+            let indent: String = lines[start].chars().take_while(|c| c.is_whitespace()).collect();
+            lines.splice(start..end, line.map(|line| format!("{}{}", indent, line)));
+        }
+        // set in another form, eg. an inline table, so the section is written anew
+        None if overrides::lookup_value(&Value::Table(file_table_of(contents)), key).is_some() => {
+            let mut table = file_table_of(contents);
+            match value {
+                Some(value) => overrides::insert(&mut table, key, value.clone()),
+                None => overrides::remove(&mut table, key),
+            }
+            return toml::to_string(&table).unwrap_or_else(|_| contents.to_string());
+        }
+        None => {
+            let Some(line) = line else {
+                return contents.to_string();
+            };
+            let header = header_regex();
+            match lines
+                .iter()
+                .position(|l| header.captures(l).is_some_and(|captures| captures[1] == *section))
+            {
+                Some(index) => lines.insert(index + 1, line),
+                None => {
+                    if lines.last().is_some_and(|last| !last.trim().is_empty()) {
+                        lines.push(String::new());
+                    }
+                    lines.push(format!("[{}]", section));
+                    lines.push(line);
+                }
+            }
+        }
+    }
+
+    let mut edited = lines.join("\n");
+    edited.push('\n');
+    edited
+}
+
+// the line after a value, arrays can go on over several lines
+fn value_end(lines: &[String], start: usize) -> usize {
+    let header = header_regex();
+    let mut text = String::new();
+    for (index, line) in lines.iter().enumerate().skip(start) {
+        if index > start && header.is_match(line) {
+            break;
+        }
+        text.push_str(line);
+        text.push('\n');
+        if text.parse::<Table>().is_ok() {
+            return index + 1;
+        }
+    }
+    // broken toml, only its first line is replaced
+    start + 1
+}
+
+fn file_table_of(contents: &str) -> Table {
+    let mut checker = Checker {
+        file: String::new(),
+        contents: contents.to_string(),
+        env: Vec::new(),
+        issues: Vec::new(),
+    };
+    checker.parse()
+}
+
+struct Checker {
+    file: String,
+    contents: String,
+    // ("network.auth_server", variable, value)
+    env: Vec<(String, String, String)>,
+    issues: Vec<Issue>,
+}
+
+impl Checker {
+    fn issue(&mut self, severity: Severity, origin: Option<String>, key: Option<String>, message: String) {
+        self.issues.push(Issue {
+            severity,
+            origin: origin.unwrap_or_else(|| self.file.clone()),
+            key,
+            message,
+        });
+    }
+
+    // the whole file, or when it has a syntax error every section that parses on its own
+    fn parse(&mut self) -> Table {
+        let error = match toml::from_str::<Table>(&self.contents) {
+            Ok(table) => return table,
+            Err(error) => error,
+        };
+        let mut reported = false;
+
+        let mut table = Table::new();
+        for (first_line, chunk) in chunks(&self.contents) {
+            match toml::from_str::<Table>(&chunk) {
+                Ok(parsed) => merge(&mut table, parsed),
+                Err(e) => {
+                    reported = true;
+                    let line = e.span().map(|span| first_line + line_at(&chunk, span.start) - 1);
+                    let origin = self.at_line(line);
+                    self.issue(
+                        Severity::Error,
+                        Some(origin),
+                        None,
+                        format!("{}, the section is ignored", e.message().trim()),
+                    );
+                }
+            }
+        }
+
+        // a problem across sections, like one defined twice, only shows in the whole file
+        if !reported {
+            let line = error.span().map(|span| line_at(&self.contents, span.start));
+            let origin = self.at_line(line);
+            self.issue(Severity::Error, Some(origin), None, error.message().trim().to_string());
+        }
+        table
+    }
+
+    // one section, keys that don't fit are dropped so the others still count
+    fn section<T: DeserializeOwned + Default>(&mut self, table: &mut Table, name: &str) -> T {
+        let Some(value) = table.remove(name) else {
+            return T::default();
+        };
+        let Value::Table(mut keys) = value else {
+            let origin = self.origin(name, None);
+            self.issue(
+                Severity::Error,
+                Some(origin),
+                Some(name.to_string()),
+                "has to be a section, using the defaults".to_string(),
+            );
+            return T::default();
+        };
+
+        let names: Vec<String> = keys.keys().cloned().collect();
+        for key in names {
+            let single: Table = [(key.clone(), keys[&key].clone())].into_iter().collect();
+            let Err(e) = Value::Table(single).try_into::<T>() else {
+                continue;
+            };
+            keys.remove(&key);
+
+            let full_key = format!("{}.{}", name, key);
+            let origin = self.origin(&full_key, Some(name));
+            let message = e.message().trim();
+            let (severity, message) = match unknown_field(message) {
+                Some(known) => (Severity::Warning, unknown_key_message(&key, &known)),
+                None => (Severity::Error, format!("{}, using the default", message)),
+            };
+            self.issue(severity, Some(origin), Some(full_key), message);
+        }
+
+        Value::Table(keys).try_into().unwrap_or_default()
+    }
+
+    // the variable that set a key, or the line of the file it is on
+    fn origin(&self, key: &str, section: Option<&str>) -> String {
+        if let Some((_, var, _)) = self.env.iter().find(|(k, _, _)| k == key) {
+            return format!("${}", var);
+        }
+        let line = match section {
+            // a table like [keymap.screens.list] has its own header
+            Some(section) => key_line(&self.contents, section, key.rsplit('.').next().unwrap_or(key))
+                .or_else(|| section_line(&self.contents, key)),
+            None => section_line(&self.contents, key),
+        };
+        self.at_line(line)
+    }
+
+    fn at_line(&self, line: Option<usize>) -> String {
+        match line {
+            Some(line) => format!("{}:{}", self.file, line),
+            None => self.file.clone(),
+        }
+    }
+}
+
+// the fields serde expected, when it complained about an unknown one
+fn unknown_field(message: &str) -> Option<Vec<String>> {
+    if !message.starts_with("unknown field") {
+        return None;
+    }
+    // "unknown field `x`, expected one of `a`, `b`"
+    Some(message.split('`').skip(3).step_by(2).map(str::to_string).collect())
+}
+
+fn unknown_key_message(key: &str, known: &[String]) -> String {
+    let closest = known
+        .iter()
+        .map(|name| (levenshtein_distance(key, name), name))
+        .min_by_key(|(distance, _)| *distance)
+        .filter(|(distance, _)| *distance <= 3);
+    match closest {
+        Some((_, name)) => format!("unknown key, ignored (did you mean `{}`?)", name),
+        None => "unknown key, ignored".to_string(),
+    }
+}
+
+// 1 based line of a byte offset
+fn line_at(text: &str, offset: usize) -> usize {
+    text[..offset.min(text.len())].matches('\n').count() + 1
+}
+
+fn header_regex() -> Regex {
+    Regex::new(r"^\s*\[\s*([A-Za-z0-9_.\-]+)\s*\]\s*(#.*)?$").unwrap()
+}
+
+// the file split at its [section] headers, with the line each part starts on
+fn chunks(contents: &str) -> Vec<(usize, String)> {
+    let header = header_regex();
+    let mut chunks: Vec<(usize, String)> = vec![(1, String::new())];
+    for (index, line) in contents.lines().enumerate() {
+        if header.is_match(line) {
+            chunks.push((index + 1, String::new()));
+        }
+        if let Some((_, chunk)) = chunks.last_mut() {
+            chunk.push_str(line);
+            chunk.push('\n');
+        }
+    }
+    chunks
+}
+
+fn merge(table: &mut Table, parsed: Table) {
+    for (key, value) in parsed {
+        match (table.get_mut(&key), value) {
+            (Some(Value::Table(existing)), Value::Table(inner)) => merge(existing, inner),
+            (_, value) => {
+                table.insert(key, value);
+            }
+        }
+    }
+}
+
+fn section_line(contents: &str, section: &str) -> Option<usize> {
+    let header = header_regex();
+    contents
+        .lines()
+        .position(|line| {
+            header.captures(line).is_some_and(|captures| {
+                captures[1] == *section || captures[1].starts_with(&format!("{}.", section))
+            })
+        })
+        .map(|index| index + 1)
+}
+
+// the line "key = ..." is on inside [section]
+fn key_line(contents: &str, section: &str, key: &str) -> Option<usize> {
+    let header = header_regex();
+    let mut current = String::new();
+    for (index, line) in contents.lines().enumerate() {
+        if let Some(captures) = header.captures(line) {
+            current = captures[1].to_string();
+            continue;
+        }
+        let Some((name, _)) = line.split_once('=') else {
+            continue;
+        };
+        if current == section && name.trim().trim_matches('"') == key {
+            return Some(index + 1);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::keymap::{KeyAction, KeyBinding, KeyContext};
+
+    const FILE: &str = "# my settings\n\
+        [player]\n\
+        # watched at 90%\n\
+        completion_threshold = 90\n\
+        binge_mode = \"yes\"\n\
+        providers = [\n    \"allanime\",\n]\n\
+        unknown_key = 1\n\
+        \n\
+        [theme]\n\
+        primary = \"Red\"\n";
+
+    #[test]
+    fn changes_only_the_line_of_the_key() {
+        let edited = with_key(FILE, "player.completion_threshold", Some(&Value::Integer(80)));
+        assert_eq!(edited, FILE.replace("completion_threshold = 90", "completion_threshold = 80"));
+    }
+
+    #[test]
+    fn replaces_values_over_several_lines() {
+        let providers = Value::Array(vec![Value::from("gogo")]);
+        let edited = with_key(FILE, "player.providers", Some(&providers));
+        assert!(edited.contains("providers = [\"gogo\"]\nunknown_key = 1\n"));
+        assert!(!edited.contains("allanime"));
+    }
+
+    #[test]
+    fn keeps_invalid_and_unknown_keys() {
+        let edited = with_key(FILE, "theme.primary", Some(&Value::from("Blue")));
+        assert!(edited.contains("binge_mode = \"yes\"\n"));
+        assert!(edited.contains("unknown_key = 1\n"));
+        assert!(edited.contains("# watched at 90%\n"));
+        assert!(edited.ends_with("[theme]\nprimary = \"Blue\"\n"));
+    }
+
+    #[test]
+    fn adds_missing_keys_and_sections() {
+        let edited = with_key(FILE, "theme.error", Some(&Value::from("Yellow")));
+        assert!(edited.ends_with("[theme]\nerror = \"Yellow\"\nprimary = \"Red\"\n"));
+
+        let edited = with_key(FILE, "cast.proxy_port", Some(&Value::Integer(8000)));
+        assert!(edited.starts_with(FILE));
+        assert!(edited.ends_with("\n[cast]\nproxy_port = 8000\n"));
+
+        assert_eq!(with_key("", "theme.text", Some(&Value::from("White"))), "[theme]\ntext = \"White\"\n");
+    }
+
+    #[test]
+    fn removes_keys() {
+        let edited = with_key(FILE, "player.providers", None);
+        assert!(edited.contains("binge_mode = \"yes\"\nunknown_key = 1\n"));
+        assert_eq!(with_key(FILE, "player.pre_playback_hook", None), FILE);
+    }
+
+    #[test]
+    fn rewrites_keys_set_in_another_form() {
+        let edited = with_key("theme = { primary = \"Red\" }\n", "theme.primary", Some(&Value::from("Blue")));
+        assert_eq!(file_table_of(&edited)["theme"]["primary"].as_str(), Some("Blue"));
+    }
+
+    // what load makes of a file with these contents
+    fn load_contents(name: &str, contents: &str) -> (Config, Vec<Issue>) {
+        let path = std::env::temp_dir().join(format!("mal-cli-{}-{}.toml", name, std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        let loaded = load(&path);
+        std::fs::remove_file(&path).ok();
+        loaded
+    }
+
+    #[test]
+    fn drops_sequences_that_start_with_another_binding() {
+        let contents = "[keymap.global]\nrefresh = [\"g\"]\n\n[keymap.screens.list]\nplay = [\"x\", \"g p\"]\n";
+        let (config, issues) = load_contents("shadowed", contents);
+
+        let play = config.keymap.screens[&KeyContext::List][&KeyAction::Play].clone();
+        assert_eq!(play, vec![KeyBinding::try_from("x".to_string()).unwrap()]);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].key.as_deref(), Some("keymap.screens.list.play"));
+        assert!(issues[0].origin.ends_with(":5"));
+    }
+
+    // (severity, line, key) of every issue
+    fn found(issues: &[Issue]) -> Vec<(Severity, Option<String>, Option<String>)> {
+        issues
+            .iter()
+            .map(|issue| {
+                let line = issue.origin.rsplit_once(':').map(|(_, line)| line.to_string());
+                (issue.severity, line, issue.key.clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reports_wrong_values_at_their_line() {
+        let contents = "[player]\ncompletion_threshold = \"high\"\nbinge_mode = true\n";
+        let (config, issues) = load_contents("wrong-value", contents);
+
+        assert!(config.player.binge_mode);
+        assert_eq!(config.player.completion_threshold, Config::default().player.completion_threshold);
+        assert_eq!(
+            found(&issues),
+            vec![(Severity::Error, Some("2".to_string()), Some("player.completion_threshold".to_string()))]
+        );
+        assert!(issues[0].message.ends_with("using the default"));
+    }
+
+    #[test]
+    fn warns_about_unknown_keys_and_sections() {
+        let contents = "[player]\nbinge_mod = true\n\n[players]\nbinge_mode = true\n";
+        let (_, issues) = load_contents("unknown", contents);
+
+        assert_eq!(
+            found(&issues),
+            vec![
+                (Severity::Warning, Some("2".to_string()), Some("player.binge_mod".to_string())),
+                (Severity::Warning, Some("4".to_string()), Some("players".to_string())),
+            ]
+        );
+        assert!(issues[0].message.contains("binge_mode"));
+    }
+
+    #[test]
+    fn keeps_the_sections_around_a_broken_one() {
+        let contents = "[player]\nbinge_mode = true\n\n[theme]\nprimary = = \"Red\"\n\n[cast]\nproxy_port = 8000\n";
+        let (config, issues) = load_contents("broken", contents);
+
+        assert!(config.player.binge_mode);
+        assert_eq!(config.cast.proxy_port, 8000);
+        assert_eq!(found(&issues), vec![(Severity::Error, Some("5".to_string()), None)]);
+        assert!(issues[0].message.ends_with("the section is ignored"));
+    }
+}
@@ -0,0 +1,34 @@
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime};
+use crate::app::Event;
+use crate::config::Config;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+// editors write in more than one step, the file is read once they are done
+const SETTLE_TIME: Duration = Duration::from_millis(200);
+
+
+// watches the config file and tells the app when it changed, so edits apply without a restart
+pub fn config_watcher(sx: mpsc::Sender<Event>) {
+    let path = Config::config_file();
+    let modified = || std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+
+    let mut last: Option<SystemTime> = modified();
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let current = modified();
+        if current == last {
+            continue;
+        }
+
+        std::thread::sleep(SETTLE_TIME);
+        last = modified();
+        // saved from the settings screen, which reloaded already
+        if last.is_some() && last == Config::written() {
+            continue;
+        }
+        if sx.send(Event::ConfigChanged).is_err() {
+            return;
+        }
+    }
+}
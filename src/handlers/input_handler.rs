@@ -14,18 +14,11 @@ pub fn input_handler(sx: mpsc::Sender<Event>) {
                         continue;
                     }
 
+                    // quitting is up to the keymap, the thread ends with the app
                     if sx.send(Event::Input(event)).is_err() {
                         // this happens when the receiver is dropped
                         return;
                     }
-
-                    // handle quit  TODO: (change to something better)
-                    if key_event.kind == crossterm::event::KeyEventKind::Press &&
-                        key_event.code == crossterm::event::KeyCode::Char('c') &&
-                        key_event.modifiers.contains(crossterm::event::KeyModifiers::CONTROL)
-                    {
-                        return;
-                    }
                 }
 
                 crossterm::event::Event::Mouse(mouse_event) => {
@@ -0,0 +1,17 @@
+use std::sync::mpsc;
+use crate::app::Event;
+use crate::player::library;
+use crate::screens::BackgroundUpdate;
+
+
+// scans the local library once at startup so episodes on disk can be played
+pub fn library_scanner(sx: mpsc::Sender<Event>) {
+    let found = library::scan();
+    if found == 0 {
+        return;
+    }
+
+    // the popup refreshes its play button on any update
+    let update = BackgroundUpdate::new("popup").set("library", found);
+    sx.send(Event::BackgroundNotice(update)).ok();
+}
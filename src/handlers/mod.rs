@@ -1,4 +1,7 @@
+mod config_handler;
 mod input_handler;
+mod library_handler;
+mod mpris_handler;
 use std::sync::mpsc;
 use crate::app::Event;
 
@@ -6,6 +9,9 @@ use crate::app::Event;
 pub fn get_handlers() -> Vec<fn(mpsc::Sender<Event>)> {
     vec![
         input_handler::input_handler,
+        library_handler::library_scanner,
+        mpris_handler::mpris_scrobbler,
+        config_handler::config_watcher,
         // add more handlers here
     ]
 }
@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+use zbus::blocking::{Connection, Proxy, fdo::DBusProxy, proxy::Builder};
+use zbus::proxy::CacheProperties;
+use zbus::zvariant::{OwnedValue, Value};
+use crate::app::Event;
+use crate::config::Config;
+use crate::player::{self, PlaybackPosition};
+use crate::player::library::{self, LibraryFile};
+use crate::screens::BackgroundUpdate;
+use crate::send_error;
+
+const MPRIS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+const MPRIS_PATH: &str = "/org/mpris/MediaPlayer2";
+const PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+// what mal-cli publishes itself while playing
+const OWN_PLAYER: &str = "mal_cli";
+
+// what a player is showing right now
+struct NowPlaying {
+    // the url or title, changes when the player moves to another file
+    key: String,
+    file: LibraryFile,
+    position: PlaybackPosition,
+}
+
+// an episode followed since the player started it
+struct Tracked {
+    key: String,
+    reported: bool,
+}
+
+// follows players started outside mal-cli and reports episodes once they count as watched
+pub fn mpris_scrobbler(sx: mpsc::Sender<Event>) {
+    let config = &Config::global().mpris;
+    if !config.scrobble {
+        return;
+    }
+
+    match Connection::session() {
+        Ok(connection) => follow_players(&connection, sx),
+        Err(e) => send_error!("Could not connect to the session bus for scrobbling: {}", e),
+    }
+}
+
+// checks the players on the bus until the app is gone
+fn follow_players(connection: &Connection, sx: mpsc::Sender<Event>) {
+    let dbus = match DBusProxy::new(connection) {
+        Ok(dbus) => dbus,
+        Err(e) => {
+            send_error!("Could not list the media players: {}", e);
+            return;
+        }
+    };
+
+    let mut tracked: HashMap<String, Tracked> = HashMap::new();
+    loop {
+        // the mpv mal-cli started is on the bus too with mpv-mpris, the app reports that one itself
+        if player::is_playing() {
+            std::thread::sleep(poll_interval());
+            continue;
+        }
+
+        let players: Vec<String> = dbus
+            .list_names()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|name| name.to_string())
+            .filter(|name| is_followed(name))
+            .collect();
+        tracked.retain(|name, _| players.contains(name));
+
+        for name in players {
+            let Some(playing) = now_playing(connection, &name) else {
+                continue;
+            };
+
+            let entry = tracked.entry(name).or_insert_with(|| Tracked {
+                key: playing.key.clone(),
+                reported: false,
+            });
+            if entry.key != playing.key {
+                entry.key = playing.key.clone();
+                entry.reported = false;
+            }
+
+            let result = playing.position.result(&playing.file.episode, false);
+            if entry.reported || !result.completed {
+                continue;
+            }
+            entry.reported = true;
+
+            // matched against the list by the app, it owns the store
+            let update = BackgroundUpdate::new("scrobble")
+                .set("title", playing.file.title)
+                .set("result", result);
+            if sx.send(Event::BackgroundNotice(update)).is_err() {
+                return;
+            }
+        }
+
+        std::thread::sleep(poll_interval());
+    }
+}
+
+fn poll_interval() -> Duration {
+    Duration::from_secs(Config::global().mpris.poll_interval.max(1))
+}
+
+// "org.mpris.MediaPlayer2.mpv.instance123" is followed for "mpv"
+fn is_followed(name: &str) -> bool {
+    let Some(player) = name.strip_prefix(MPRIS_PREFIX) else {
+        return false;
+    };
+    if player.starts_with(OWN_PLAYER) {
+        return false;
+    }
+
+    let players = &Config::global().mpris.players;
+    players.is_empty()
+        || players.iter().any(|p| {
+            player.eq_ignore_ascii_case(p)
+                || player
+                    .to_lowercase()
+                    .starts_with(&format!("{}.", p.to_lowercase()))
+        })
+}
+
+fn now_playing(connection: &Connection, name: &str) -> Option<NowPlaying> {
+    // position is never announced, so nothing is worth caching
+    let proxy: Proxy = Builder::new(connection)
+        .destination(name)
+        .ok()?
+        .path(MPRIS_PATH)
+        .ok()?
+        .interface(PLAYER_INTERFACE)
+        .ok()?
+        .cache_properties(CacheProperties::No)
+        .build()
+        .ok()?;
+
+    let metadata: HashMap<String, OwnedValue> = proxy.get_property("Metadata").ok()?;
+    let url = metadata.get("xesam:url").and_then(as_string);
+    let title = metadata.get("xesam:title").and_then(as_string);
+
+    let file = url
+        .as_deref()
+        .and_then(|url| url::Url::parse(url).ok())
+        .and_then(|url| url.to_file_path().ok())
+        .and_then(|path| LibraryFile::parse(&path))
+        .or_else(|| title.as_deref().and_then(parse_title))?;
+
+    // both in microseconds
+    let length = metadata.get("mpris:length").and_then(as_number)?;
+    let time: i64 = proxy.get_property("Position").ok()?;
+
+    Some(NowPlaying {
+        key: url.or(title).unwrap_or_default(),
+        file,
+        position: PlaybackPosition {
+            time: time as f64 / 1_000_000.0,
+            duration: length as f64 / 1_000_000.0,
+        },
+    })
+}
+
+// players show the file name as the title when the file has no tags
+fn parse_title(title: &str) -> Option<LibraryFile> {
+    let path = Path::new(title);
+    if library::is_video(path) {
+        LibraryFile::parse(path)
+    } else {
+        LibraryFile::from_name(title, Path::new(""))
+    }
+}
+
+fn as_string(value: &OwnedValue) -> Option<String> {
+    match &**value {
+        Value::Str(s) => Some(s.to_string()),
+        _ => None,
+    }
+}
+
+// the spec says int64, some players send other integer types
+fn as_number(value: &OwnedValue) -> Option<i64> {
+    match &**value {
+        Value::I64(n) => Some(*n),
+        Value::U64(n) => i64::try_from(*n).ok(),
+        Value::I32(n) => Some(i64::from(*n)),
+        Value::U32(n) => Some(i64::from(*n)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::PlayResult;
+    use zbus::blocking::connection::Builder as ConnectionBuilder;
+    use zbus::interface;
+
+    // a player started outside mal-cli, near the end of an untagged file
+    struct FakePlayer;
+
+    #[interface(name = "org.mpris.MediaPlayer2.Player")]
+    impl FakePlayer {
+        #[zbus(property)]
+        fn metadata(&self) -> HashMap<String, OwnedValue> {
+            HashMap::from([
+                (
+                    "xesam:title".to_string(),
+                    OwnedValue::try_from(Value::from("[Group] Some Show - 05 [1080p].mkv")).unwrap(),
+                ),
+                ("mpris:length".to_string(), OwnedValue::from(1_440_000_000i64)),
+            ])
+        }
+
+        #[zbus(property)]
+        fn position(&self) -> i64 {
+            1_400_000_000
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a session bus, run with dbus-run-session -- cargo test -- --ignored"]
+    fn reports_an_episode_watched_in_another_player() {
+        Config::init_defaults();
+        let _player = ConnectionBuilder::session()
+            .unwrap()
+            .name("org.mpris.MediaPlayer2.fakeplayer")
+            .unwrap()
+            .serve_at(MPRIS_PATH, FakePlayer)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let (sx, rx) = mpsc::channel();
+        let connection = Connection::session().unwrap();
+        std::thread::spawn(move || follow_players(&connection, sx));
+
+        let Ok(Event::BackgroundNotice(mut update)) = rx.recv_timeout(Duration::from_secs(10)) else {
+            panic!("nothing was reported");
+        };
+        assert_eq!(update.id, "scrobble");
+        assert_eq!(update.take::<String>("title").as_deref(), Some("Some Show"));
+        let result = update.take::<PlayResult>("result").unwrap();
+        assert_eq!(result.episode, "5");
+        assert!(result.completed);
+    }
+
+    #[test]
+    fn follows_other_players_only() {
+        Config::init_defaults();
+        assert!(is_followed("org.mpris.MediaPlayer2.mpv.instance123"));
+        assert!(!is_followed("org.mpris.MediaPlayer2.mal_cli.instance123"));
+        assert!(!is_followed("org.freedesktop.Notifications"));
+    }
+}
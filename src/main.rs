@@ -1,4 +1,5 @@
 mod app;
+mod cli;
 mod config;
 mod handlers;
 mod mal;
@@ -12,10 +13,42 @@ use crossterm::event::PushKeyboardEnhancementFlags;
 use crossterm::event::KeyboardEnhancementFlags;
 use crossterm::execute;
 use anyhow::Result;
-use config::Config;
+use config::{Config, Paths};
 
-fn parse_cli() -> bool {
-    for arg in std::env::args().skip(1) {
+// --config and --data-dir count for the tui and every command, the other arguments are left
+fn take_paths(args: Vec<String>) -> Result<(Paths, Vec<String>), String> {
+    let mut paths = Paths::default();
+    let mut rest = Vec::new();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let (name, value) = match arg.split_once('=') {
+            Some((name, value)) if name == "--config" || name == "--data-dir" => {
+                (name.to_string(), value.to_string())
+            }
+            _ if arg == "--config" || arg == "--data-dir" => {
+                let value = args.next().ok_or_else(|| format!("{} needs a value", arg))?;
+                (arg, value)
+            }
+            _ => {
+                rest.push(arg);
+                continue;
+            }
+        };
+
+        let path = Config::expand_path(&value);
+        if name == "--config" {
+            paths.config_file = Some(path);
+        } else {
+            paths.data_dir = Some(path);
+        }
+    }
+
+    Ok((paths, rest))
+}
+
+fn parse_cli(args: &[String]) -> bool {
+    for arg in args {
         match arg.as_str() {
             "-v" | "--version" => {
                 println!("{}", env!("CARGO_PKG_VERSION"));
@@ -26,15 +59,22 @@ fn parse_cli() -> bool {
                 return true;
             }
             "-c" | "--config-path" => {
+                println!("{}", Config::config_file().display());
                 return true;
             }
             "-h" | "--help" => {
                 println!("Usage: mal-cli [OPTIONS]");
+                println!("       mal-cli <COMMAND> [ARGS]");
                 println!();
                 println!("Options:");
-                println!("  -h, --help       Show this help message");
-                println!("  -v, --version    Show version information");
-                println!("  -e, --edit       Edit the configuration file");
+                println!("  -h, --help          Show this help message");
+                println!("  -v, --version       Show version information");
+                println!("  -e, --edit          Edit the configuration file");
+                println!("  -c, --config-path   Print the path of the configuration file");
+                println!("  --config <file>     Read the configuration from this file");
+                println!("  --data-dir <dir>    Keep login, history and downloads in this directory");
+                println!();
+                cli::print_usage();
                 return true;
             }
             _ => {}
@@ -47,7 +87,26 @@ fn parse_cli() -> bool {
 #[tokio::main]
 async fn main() -> Result<()> {
 
-    let run_command = parse_cli();
+    let (paths, args) = match take_paths(std::env::args().skip(1).collect()) {
+        Ok(taken) => taken,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(2);
+        }
+    };
+    Config::set_paths(paths);
+
+    // subcommands print their results and exit without the tui
+    if cli::is_command_line(&args) {
+        Config::init();
+        if let Err(e) = cli::run(&args) {
+            eprintln!("error: {}", e);
+            std::process::exit(e.exit_code());
+        }
+        return Ok(());
+    }
+
+    let run_command = parse_cli(&args);
     if run_command {
         return Ok(());
     }
@@ -299,6 +299,29 @@ impl MalClient {
         )
     }
 
+    // the list endpoints leave out fields like related_anime, this gets all of them for one anime
+    pub fn get_anime_details(&self, anime_id: AnimeId) -> Option<Anime> {
+        let token = self.identity
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|id| id.access_token.clone())
+            .ok_or_else(|| send_error!("no identity/token")).ok()?;
+
+        network::send_request::<Anime>(
+            "GET",
+            format!("{}/anime/{}", BASE_URL, anime_id),
+            params![
+                "fields" => fields::ALL.join(","),
+                "nsfw" => "true",
+            ],
+            vec![("Authorization".to_string(), format!("Bearer {}", token))],
+            None,
+        )
+        .map_err(|e| send_error!("Error fetching anime {}: {}", anime_id, e))
+        .ok()
+    }
+
     pub fn get_favorited_anime(&self, username: String) -> Option<Vec<FavoriteAnime>> {
         self.send_request::<FavoriteAnime>(
             format!("{}/users/{}/favorites", EXTRA_URL, username),
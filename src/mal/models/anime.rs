@@ -434,6 +434,17 @@ impl Anime {
             .collect::<Vec<String>>()
             .join(", ")
     }
+
+    // counts the episode towards the progress, the anime is completed after the last one
+    pub fn mark_watched(&mut self, episode: u32) {
+        let total = self.num_episodes;
+        let status = &mut self.my_list_status;
+        status.num_episodes_watched = status.num_episodes_watched.max(episode);
+        if total != 0 && status.num_episodes_watched >= total {
+            status.num_episodes_watched = total;
+            status.status = "completed".to_string();
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -0,0 +1,74 @@
+use super::mappings::SourceMapping;
+use super::providers::Stream;
+use crate::config::Config;
+use crate::mal::models::anime::AnimeId;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+// the anime and "<provider>:<show id>:<episode>:<translation>"
+type StreamKey = (AnimeId, String);
+
+// resolved streams and when, source urls expire so these do too
+static STREAMS: LazyLock<Mutex<HashMap<StreamKey, (Instant, Stream)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// shows found by searching, kept for the session so the search only runs once per anime and provider
+static SHOWS: LazyLock<Mutex<HashMap<(String, AnimeId), SourceMapping>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn stream_key(anime_id: AnimeId, provider: &str, show_id: &str, episode: &str, translation: &str) -> StreamKey {
+    (anime_id, format!("{}:{}:{}:{}", provider, show_id, episode, translation))
+}
+
+fn max_age() -> Duration {
+    Duration::from_secs(Config::global().player.stream_cache_minutes * 60)
+}
+
+pub fn get_stream(anime_id: AnimeId, provider: &str, show_id: &str, episode: &str, translation: &str) -> Option<Stream> {
+    let key = stream_key(anime_id, provider, show_id, episode, translation);
+    let mut streams = STREAMS.lock().unwrap();
+
+    match streams.get(&key) {
+        Some((resolved, stream)) if resolved.elapsed() < max_age() => Some(stream.clone()),
+        Some(_) => {
+            streams.remove(&key);
+            None
+        }
+        None => None,
+    }
+}
+
+pub fn set_stream(anime_id: AnimeId, provider: &str, show_id: &str, episode: &str, translation: &str, stream: &Stream) {
+    if max_age().is_zero() {
+        return;
+    }
+
+    let mut streams = STREAMS.lock().unwrap();
+    streams.retain(|_, (resolved, _)| resolved.elapsed() < max_age());
+    streams.insert(
+        stream_key(anime_id, provider, show_id, episode, translation),
+        (Instant::now(), stream.clone()),
+    );
+}
+
+pub fn get_show(provider: &str, anime_id: AnimeId) -> Option<SourceMapping> {
+    SHOWS
+        .lock()
+        .unwrap()
+        .get(&(provider.to_string(), anime_id))
+        .cloned()
+}
+
+pub fn set_show(anime_id: AnimeId, show: &SourceMapping) {
+    SHOWS
+        .lock()
+        .unwrap()
+        .insert((show.provider.clone(), anime_id), show.clone());
+}
+
+// drops everything known about the anime, eg. when the user wants to pick the show again
+pub fn forget_anime(anime_id: AnimeId) {
+    SHOWS.lock().unwrap().retain(|(_, id), _| *id != anime_id);
+    STREAMS.lock().unwrap().retain(|(id, _), _| *id != anime_id);
+}
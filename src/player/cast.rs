@@ -0,0 +1,688 @@
+use super::PlaybackPosition;
+use super::downloads::get_agent;
+use super::providers::Stream;
+use crate::config::Config;
+use regex::Regex;
+use rouille::{Request, Response, ResponseBody};
+use std::fmt::Write;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::net::{IpAddr, UdpSocket};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, OnceLock, mpsc};
+use std::time::{Duration, Instant};
+use url::Url;
+
+const SSDP_ADDRESS: &str = "239.255.255.250:1900";
+const RENDERER_TYPE: &str = "urn:schemas-upnp-org:device:MediaRenderer:1";
+const AV_TRANSPORT: &str = "urn:schemas-upnp-org:service:AVTransport:1";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+// a renderer that never starts playing is given up on
+const START_TIMEOUT: Duration = Duration::from_secs(30);
+// a renderer that stops on its own this close to the end played the whole file, in seconds
+const END_MARGIN: f64 = 5.0;
+
+// a upnp media renderer found on the network, eg. a tv
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    pub name: String,
+    control_url: String,
+    address: IpAddr,
+}
+
+impl std::fmt::Display for Renderer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.name, self.address)
+    }
+}
+
+// asks the network for media renderers and waits for the configured time
+pub fn discover() -> Result<Vec<Renderer>, String> {
+    let socket = UdpSocket::bind("0.0.0.0:0")
+        .map_err(|e| format!("Could not search for renderers: {}", e))?;
+    socket.set_read_timeout(Some(Duration::from_millis(250))).ok();
+
+    let search = format!(
+        "M-SEARCH * HTTP/1.1\r\nHOST: {}\r\nMAN: \"ssdp:discover\"\r\nMX: 2\r\nST: {}\r\n\r\n",
+        SSDP_ADDRESS, RENDERER_TYPE
+    );
+    // udp gets lost, so ask twice
+    for _ in 0..2 {
+        socket
+            .send_to(search.as_bytes(), SSDP_ADDRESS)
+            .map_err(|e| format!("Could not search for renderers: {}", e))?;
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(Config::global().cast.discovery_seconds);
+    let mut locations: Vec<String> = Vec::new();
+    let mut buffer = [0u8; 4096];
+    while Instant::now() < deadline {
+        let Ok((length, _)) = socket.recv_from(&mut buffer) else {
+            continue;
+        };
+        let reply = String::from_utf8_lossy(&buffer[..length]);
+        if let Some(location) = http_header(&reply, "location")
+            && !locations.contains(&location)
+        {
+            locations.push(location);
+        }
+    }
+
+    Ok(locations.iter().filter_map(|l| describe(l)).collect())
+}
+
+// "LOCATION: http://..." from an ssdp reply
+fn http_header(message: &str, name: &str) -> Option<String> {
+    message.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then(|| value.trim().to_string())
+    })
+}
+
+// reads the device description, none when the device can't play over AVTransport
+fn describe(location: &str) -> Option<Renderer> {
+    let description = get_agent()
+        .get(location)
+        .call()
+        .ok()?
+        .body_mut()
+        .read_to_string()
+        .ok()?;
+
+    let service = patterns()
+        .service
+        .captures_iter(&description)
+        .map(|caps| caps[1].to_string())
+        .find(|service| xml_tag(service, "serviceType").is_some_and(|t| t.contains("AVTransport")))?;
+
+    // control urls are relative to URLBase, or to the description itself
+    let base = xml_tag(&description, "URLBase").unwrap_or_else(|| location.to_string());
+    let control_url = Url::parse(&base)
+        .ok()?
+        .join(&xml_tag(&service, "controlURL")?)
+        .ok()?;
+    let address = control_url
+        .socket_addrs(|| Some(80))
+        .ok()?
+        .first()?
+        .ip();
+
+    Some(Renderer {
+        name: xml_tag(&description, "friendlyName").unwrap_or_else(|| address.to_string()),
+        control_url: control_url.to_string(),
+        address,
+    })
+}
+
+struct Patterns {
+    service: Regex,
+    playlist_uri: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| Patterns {
+        service: Regex::new(r"(?s)<service>(.*?)</service>").unwrap(),
+        playlist_uri: Regex::new(r#"URI="([^"]+)""#).unwrap(),
+    })
+}
+
+// the text of the first <name> (with any namespace prefix), unescaped
+fn xml_tag(xml: &str, name: &str) -> Option<String> {
+    let pattern = format!(r"(?s)<(?:\w+:)?{}(?:\s[^>]*)?>(.*?)</(?:\w+:)?{}>", name, name);
+    let caps = Regex::new(&pattern).ok()?.captures(xml)?;
+    Some(unescape(caps[1].trim()))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// calls an AVTransport action and returns the response body
+fn soap(renderer: &Renderer, action: &str, args: &[(&str, &str)]) -> Result<String, String> {
+    let mut arguments = String::new();
+    for (key, value) in std::iter::once(&("InstanceID", "0")).chain(args) {
+        let _ = write!(arguments, "<{}>{}</{}>", key, escape(value), key);
+    }
+    let envelope = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+         <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+         s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+         <s:Body><u:{action} xmlns:u=\"{service}\">{arguments}</u:{action}></s:Body></s:Envelope>",
+        action = action,
+        service = AV_TRANSPORT,
+        arguments = arguments
+    );
+
+    get_agent()
+        .post(&renderer.control_url)
+        // plenty of renderers drop kept alive connections between calls
+        .header("Connection", "close")
+        .header("Content-Type", "text/xml; charset=\"utf-8\"")
+        .header("SOAPAction", format!("\"{}#{}\"", AV_TRANSPORT, action))
+        .send(&envelope)
+        .map_err(|e| format!("{} failed on {}: {}", action, renderer.name, e))?
+        .body_mut()
+        .read_to_string()
+        .map_err(|e| format!("{} failed on {}: {}", action, renderer.name, e))
+}
+
+// the item description renderers show while playing
+fn didl(title: &str, url: &str) -> String {
+    format!(
+        "<DIDL-Lite xmlns=\"urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/\" \
+         xmlns:dc=\"http://purl.org/dc/elements/1.1/\" \
+         xmlns:upnp=\"urn:schemas-upnp-org:metadata-1-0/upnp/\">\
+         <item id=\"0\" parentID=\"-1\" restricted=\"1\"><dc:title>{}</dc:title>\
+         <upnp:class>object.item.videoItem</upnp:class>\
+         <res protocolInfo=\"http-get:*:{}:*\">{}</res></item></DIDL-Lite>",
+        escape(title),
+        mime_type(url),
+        escape(url)
+    )
+}
+
+fn mime_type(url: &str) -> &'static str {
+    let path = Url::parse(url)
+        .map(|u| u.path().to_string())
+        .unwrap_or_else(|_| url.to_string());
+    let extension = Path::new(&path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "m3u8" => "application/vnd.apple.mpegurl",
+        "mkv" => "video/x-matroska",
+        "webm" => "video/webm",
+        "ts" => "video/mp2t",
+        "avi" => "video/x-msvideo",
+        _ => "video/mp4",
+    }
+}
+
+// "0:23:41.250" -> 1421.25, renderers answer "NOT_IMPLEMENTED" when they don't know
+fn parse_time(time: &str) -> Option<f64> {
+    let mut seconds = 0.0;
+    for part in time.split(':') {
+        seconds = seconds * 60.0 + part.trim().parse::<f64>().ok()?;
+    }
+    Some(seconds)
+}
+
+// plays the url on the renderer and follows it until it stops or stop is set, returns how far it got
+pub fn play(
+    renderer: &Renderer,
+    url: &str,
+    title: &str,
+    stop: &AtomicBool,
+    mut on_progress: impl FnMut(PlaybackPosition),
+) -> Result<(PlaybackPosition, bool), String> {
+    soap(
+        renderer,
+        "SetAVTransportURI",
+        &[("CurrentURI", url), ("CurrentURIMetaData", &didl(title, url))],
+    )?;
+    soap(renderer, "Play", &[("Speed", "1")])?;
+
+    let started = Instant::now();
+    let mut playing = false;
+    let mut position = PlaybackPosition::default();
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        if stop.load(Ordering::SeqCst) {
+            soap(renderer, "Stop", &[]).ok();
+            return Ok((position, false));
+        }
+
+        // some renderers reset the position once they stop, so keep the last one
+        if let Ok(info) = soap(renderer, "GetPositionInfo", &[]) {
+            if let Some(time) = xml_tag(&info, "RelTime").and_then(|t| parse_time(&t))
+                && time > 0.0
+            {
+                position.time = time;
+            }
+            if let Some(duration) = xml_tag(&info, "TrackDuration").and_then(|t| parse_time(&t))
+                && duration > 0.0
+            {
+                position.duration = duration;
+            }
+            on_progress(position);
+        }
+
+        let state = soap(renderer, "GetTransportInfo", &[])
+            .ok()
+            .and_then(|info| xml_tag(&info, "CurrentTransportState"));
+        match state.as_deref() {
+            Some("PLAYING") | Some("PAUSED_PLAYBACK") => playing = true,
+            // stopped after playing, or the renderer went away
+            Some("STOPPED") | Some("NO_MEDIA_PRESENT") | None if playing => break,
+            _ if started.elapsed() > START_TIMEOUT => {
+                return Err(format!("{} did not start playing", renderer.name));
+            }
+            _ => {}
+        }
+    }
+
+    // the position is polled, so the last one can be a little before the end
+    let reached_end = position.duration > 0.0 && position.duration - position.time <= END_MARGIN;
+    Ok((position, reached_end))
+}
+
+// the local http server passing a stream on to the renderer, it stops when dropped
+pub struct Proxy {
+    stop: mpsc::Sender<()>,
+}
+
+impl Drop for Proxy {
+    fn drop(&mut self) {
+        self.stop.send(()).ok();
+    }
+}
+
+// the url the renderer can play, behind a local proxy when the stream needs headers or is on disk
+pub fn playable_url(stream: &Stream, renderer: &Renderer) -> Result<(String, Option<Proxy>), String> {
+    let is_file = !stream.url.starts_with("http");
+    if !is_file && stream.headers.is_empty() {
+        return Ok((stream.url.clone(), None));
+    }
+
+    // the address of this machine on the network the renderer is on
+    let local_ip = UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| {
+            socket.connect((renderer.address, 1900))?;
+            socket.local_addr()
+        })
+        .map_err(|e| format!("Could not find a local address for {}: {}", renderer.name, e))?
+        .ip();
+
+    // the proxy only fetches the stream and what its playlists point to
+    let served = stream.clone();
+    let allowed = Arc::new(Mutex::new(HashSet::from([stream.url.clone()])));
+    let server = rouille::Server::new(
+        (local_ip, Config::global().cast.proxy_port),
+        move |request| proxy_request(request, &served, &allowed),
+    )
+    .map_err(|e| format!("Could not start the cast proxy: {}", e))?;
+
+    let base = format!("http://{}:{}", local_ip, server.server_addr().port());
+    let url = if is_file {
+        // the extension tells renderers what they are getting
+        let extension = Path::new(&stream.url)
+            .extension()
+            .map(|e| e.to_string_lossy().to_string())
+            .unwrap_or_else(|| "mp4".to_string());
+        format!("{}/file.{}", base, extension)
+    } else {
+        proxied_url(&base, &stream.url)
+    };
+
+    let (_handle, stop) = server.stoppable();
+    Ok((url, Some(Proxy { stop })))
+}
+
+fn proxied_url(base: &str, url: &str) -> String {
+    let extension = if mime_type(url) == "application/vnd.apple.mpegurl" { "m3u8" } else { "bin" };
+    let encoded: String = url::form_urlencoded::byte_serialize(url.as_bytes()).collect();
+    format!("{}/stream.{}?url={}", base, extension, encoded)
+}
+
+fn proxy_request(request: &Request, stream: &Stream, allowed: &Mutex<HashSet<String>>) -> Response {
+    let path = request.url();
+    if path.starts_with("/file") && !stream.url.starts_with("http") {
+        return serve_file(request, Path::new(&stream.url));
+    }
+
+    let Some(url) = request.get_param("url").filter(|_| path.starts_with("/stream")) else {
+        return Response::empty_404();
+    };
+    // anyone on the network can reach the proxy, so it doesn't fetch urls it didn't hand out
+    if !allowed.lock().unwrap().contains(&url) {
+        return Response::empty_404().with_status_code(403);
+    }
+    forward(request, stream, &url, allowed)
+}
+
+// fetches the url with the stream's headers, playlists are rewritten to point back here
+fn forward(request: &Request, stream: &Stream, url: &str, allowed: &Mutex<HashSet<String>>) -> Response {
+    let mut upstream = get_agent().get(url);
+    for (key, value) in &stream.headers {
+        upstream = upstream.header(key, value);
+    }
+    if let Some(range) = request.header("Range") {
+        upstream = upstream.header("Range", range);
+    }
+
+    let response = match upstream.call() {
+        Ok(response) => response,
+        Err(ureq::Error::StatusCode(code)) => return Response::empty_404().with_status_code(code),
+        Err(_) => return Response::empty_404().with_status_code(502),
+    };
+
+    let status = response.status().as_u16();
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    let content_type = header("Content-Type").unwrap_or_else(|| mime_type(url).to_string());
+
+    if content_type.to_lowercase().contains("mpegurl") || mime_type(url).contains("mpegurl") {
+        let Ok(playlist) = response.into_body().read_to_string() else {
+            return Response::empty_404().with_status_code(502);
+        };
+        let base = format!("http://{}", request.header("Host").unwrap_or_default());
+        let playlist = rewrite_playlist(&playlist, url, &base, &mut allowed.lock().unwrap());
+        return Response::from_data("application/vnd.apple.mpegurl", playlist).with_status_code(status);
+    }
+
+    let mut headers = vec![("Content-Type".into(), content_type.into())];
+    for name in ["Content-Range", "Accept-Ranges"] {
+        if let Some(value) = header(name) {
+            headers.push((name.into(), value.into()));
+        }
+    }
+    let length = header("Content-Length").and_then(|l| l.parse::<usize>().ok());
+    let reader = response.into_body().into_reader();
+
+    Response {
+        status_code: status,
+        headers,
+        data: match length {
+            Some(length) => ResponseBody::from_reader_and_size(reader, length),
+            None => ResponseBody::from_reader(reader),
+        },
+        upgrade: None,
+    }
+}
+
+// every segment, key and variant of an hls playlist goes through the proxy as well
+// the urls it points to are added to the ones the proxy may fetch
+fn rewrite_playlist(playlist: &str, playlist_url: &str, base: &str, allowed: &mut HashSet<String>) -> String {
+    let mut resolve = |uri: &str| {
+        let url = Url::parse(playlist_url)
+            .and_then(|url| url.join(uri))
+            .map(String::from)
+            .unwrap_or_else(|_| uri.to_string());
+        allowed.insert(url.clone());
+        url
+    };
+
+    playlist
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            if line.starts_with('#') {
+                patterns()
+                    .playlist_uri
+                    .replace_all(line, |caps: &regex::Captures| {
+                        format!("URI=\"{}\"", proxied_url(base, &resolve(&caps[1])))
+                    })
+                    .to_string()
+            } else if line.is_empty() {
+                String::new()
+            } else {
+                proxied_url(base, &resolve(line))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// a file on disk, with range requests so the renderer can seek
+fn serve_file(request: &Request, path: &Path) -> Response {
+    let Ok(mut file) = File::open(path) else {
+        return Response::empty_404();
+    };
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let content_type = mime_type(&path.to_string_lossy());
+
+    let Some((start, end)) = request.header("Range").and_then(|r| parse_range(r, size)) else {
+        return Response::from_file(content_type, file).with_unique_header("Accept-Ranges", "bytes");
+    };
+    if file.seek(SeekFrom::Start(start)).is_err() {
+        return Response::empty_404().with_status_code(416);
+    }
+
+    let length = end - start + 1;
+    Response {
+        status_code: 206,
+        headers: vec![
+            ("Content-Type".into(), content_type.into()),
+            ("Accept-Ranges".into(), "bytes".into()),
+            ("Content-Range".into(), format!("bytes {}-{}/{}", start, end, size).into()),
+        ],
+        data: ResponseBody::from_reader_and_size(file.take(length), length as usize),
+        upgrade: None,
+    }
+}
+
+// "bytes=100-", "bytes=100-199" or "bytes=-100" as an inclusive range
+fn parse_range(header: &str, size: u64) -> Option<(u64, u64)> {
+    let (start, end) = header.strip_prefix("bytes=")?.split(',').next()?.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => (size.saturating_sub(suffix.parse().ok()?), size.checked_sub(1)?),
+        (start, "") => (start.parse().ok()?, size.checked_sub(1)?),
+        (start, end) => (start.parse().ok()?, end.parse::<u64>().ok()?.min(size.checked_sub(1)?)),
+    };
+    (start <= end).then_some((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    const DESCRIPTION: &str = r#"<?xml version="1.0"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0">
+  <device>
+    <deviceType>urn:schemas-upnp-org:device:MediaRenderer:1</deviceType>
+    <friendlyName>Stub &amp; TV</friendlyName>
+    <serviceList>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:RenderingControl:1</serviceType>
+        <controlURL>/rendering</controlURL>
+      </service>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:AVTransport:1</serviceType>
+        <controlURL>/transport</controlURL>
+      </service>
+    </serviceList>
+  </device>
+</root>"#;
+
+    // a renderer on localhost that plays a 10 second file in two polls, the calls it got are kept
+    fn stub_renderer() -> (String, Arc<Mutex<Vec<String>>>, mpsc::Sender<()>) {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let polls = AtomicUsize::new(0);
+        let received = calls.clone();
+        let server = rouille::Server::new("127.0.0.1:0", move |request| {
+            if request.url() == "/description.xml" {
+                return Response::from_data("text/xml", DESCRIPTION);
+            }
+
+            let action = request
+                .header("SOAPAction")
+                .and_then(|header| header.trim_matches('"').split_once('#'))
+                .map(|(_, action)| action.to_string())
+                .unwrap_or_default();
+            let mut body = String::new();
+            request.data().unwrap().read_to_string(&mut body).unwrap();
+            received.lock().unwrap().push(format!("{} {}", action, body));
+
+            let reply = match action.as_str() {
+                "GetPositionInfo" => {
+                    let time = 4 * (polls.fetch_add(1, Ordering::SeqCst) + 1);
+                    format!("<RelTime>0:00:{:02}</RelTime><TrackDuration>0:00:10</TrackDuration>", time)
+                }
+                "GetTransportInfo" if polls.load(Ordering::SeqCst) < 2 => {
+                    "<CurrentTransportState>PLAYING</CurrentTransportState>".to_string()
+                }
+                "GetTransportInfo" => "<CurrentTransportState>STOPPED</CurrentTransportState>".to_string(),
+                _ => String::new(),
+            };
+            Response::from_data(
+                "text/xml",
+                format!("<s:Envelope><s:Body><u:{0}Response>{1}</u:{0}Response></s:Body></s:Envelope>", action, reply),
+            )
+        })
+        .unwrap();
+
+        let location = format!("http://{}/description.xml", server.server_addr());
+        let (_handle, stop) = server.stoppable();
+        (location, calls, stop)
+    }
+
+    #[test]
+    fn describes_the_av_transport_of_a_renderer() {
+        let (location, _, _stop) = stub_renderer();
+        let renderer = describe(&location).unwrap();
+
+        assert_eq!(renderer.name, "Stub & TV");
+        assert!(renderer.control_url.ends_with("/transport"));
+        assert_eq!(renderer.address, IpAddr::from([127, 0, 0, 1]));
+    }
+
+    #[test]
+    fn plays_until_the_renderer_stops_at_the_end() {
+        let (location, calls, _stop) = stub_renderer();
+        let renderer = describe(&location).unwrap();
+        let mut progress = Vec::new();
+
+        let (position, reached_end) = play(
+            &renderer,
+            "http://example.com/episode.mp4",
+            "Show - Episode 1",
+            &AtomicBool::new(false),
+            |position| progress.push(position.time),
+        )
+        .unwrap();
+
+        assert!(reached_end);
+        assert_eq!(position.time, 8.0);
+        assert_eq!(position.duration, 10.0);
+        assert_eq!(progress, vec![4.0, 8.0]);
+
+        let calls = calls.lock().unwrap();
+        assert!(calls[0].starts_with("SetAVTransportURI"));
+        assert!(calls[0].contains("<CurrentURI>http://example.com/episode.mp4</CurrentURI>"));
+        assert!(calls[0].contains("&lt;dc:title&gt;Show - Episode 1&lt;/dc:title&gt;"));
+        assert!(calls[1].starts_with("Play"));
+    }
+
+    #[test]
+    fn stopping_tells_the_renderer_and_is_not_the_end() {
+        let (location, calls, _stop) = stub_renderer();
+        let renderer = describe(&location).unwrap();
+
+        let (_, reached_end) = play(&renderer, "http://example.com/a.mp4", "A", &AtomicBool::new(true), |_| {}).unwrap();
+
+        assert!(!reached_end);
+        assert!(calls.lock().unwrap().last().unwrap().starts_with("Stop"));
+    }
+
+    // needs multicast on the network and port 1900 free
+    #[test]
+    #[ignore = "sends ssdp multicast, run with --ignored on a network that allows it"]
+    fn discovers_a_renderer_over_ssdp() {
+        crate::config::Config::init_defaults();
+        let (location, _, _stop) = stub_renderer();
+
+        let socket = UdpSocket::bind("0.0.0.0:1900").unwrap();
+        socket
+            .join_multicast_v4(&"239.255.255.250".parse().unwrap(), &"0.0.0.0".parse().unwrap())
+            .unwrap();
+        std::thread::spawn(move || {
+            let mut buffer = [0u8; 1024];
+            while let Ok((length, from)) = socket.recv_from(&mut buffer) {
+                if String::from_utf8_lossy(&buffer[..length]).starts_with("M-SEARCH") {
+                    let reply = format!("HTTP/1.1 200 OK\r\nST: {}\r\nLOCATION: {}\r\n\r\n", RENDERER_TYPE, location);
+                    socket.send_to(reply.as_bytes(), from).ok();
+                }
+            }
+        });
+
+        let renderers = discover().unwrap();
+        assert!(renderers.iter().any(|renderer| renderer.name == "Stub & TV"));
+    }
+
+    #[test]
+    fn proxy_refuses_urls_it_did_not_hand_out() {
+        let stream = Stream {
+            url: "https://cdn.example.com/master.m3u8".to_string(),
+            headers: vec![("Referer".to_string(), "https://example.com".to_string())],
+            subtitles: Vec::new(),
+        };
+        let allowed = Mutex::new(HashSet::from([stream.url.clone()]));
+        let request = Request::fake_http("GET", "/stream.bin?url=http%3A%2F%2F192.168.1.1%2Fadmin", vec![], vec![]);
+
+        assert_eq!(proxy_request(&request, &stream, &allowed).status_code, 403);
+    }
+
+    #[test]
+    fn rewrites_playlists_through_the_proxy() {
+        let playlist = "#EXTM3U\n#EXT-X-KEY:METHOD=AES-128,URI=\"key.bin\"\nseg1.ts\n\nhttps://other.example.com/seg2.ts";
+        let mut allowed = HashSet::new();
+
+        let rewritten = rewrite_playlist(playlist, "https://cdn.example.com/hls/index.m3u8", "http://10.0.0.2:8000", &mut allowed);
+
+        assert_eq!(
+            rewritten,
+            "#EXTM3U\n\
+             #EXT-X-KEY:METHOD=AES-128,URI=\"http://10.0.0.2:8000/stream.bin?url=https%3A%2F%2Fcdn.example.com%2Fhls%2Fkey.bin\"\n\
+             http://10.0.0.2:8000/stream.bin?url=https%3A%2F%2Fcdn.example.com%2Fhls%2Fseg1.ts\n\
+             \n\
+             http://10.0.0.2:8000/stream.bin?url=https%3A%2F%2Fother.example.com%2Fseg2.ts"
+        );
+        assert!(allowed.contains("https://cdn.example.com/hls/seg1.ts"));
+        assert!(allowed.contains("https://cdn.example.com/hls/key.bin"));
+        assert!(allowed.contains("https://other.example.com/seg2.ts"));
+    }
+
+    #[test]
+    fn parses_byte_ranges() {
+        assert_eq!(parse_range("bytes=100-", 1000), Some((100, 999)));
+        assert_eq!(parse_range("bytes=100-199", 1000), Some((100, 199)));
+        assert_eq!(parse_range("bytes=-100", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=900-5000", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=0-0,10-20", 1000), Some((0, 0)));
+        assert_eq!(parse_range("bytes=500-100", 1000), None);
+        assert_eq!(parse_range("bytes=0-", 0), None);
+        assert_eq!(parse_range("items=0-10", 1000), None);
+    }
+
+    #[test]
+    fn reads_xml_tags() {
+        let xml = "<s:Body><u:Reply><RelTime>0:01:02</RelTime><Title a=\"1\"> A &amp; B </Title></u:Reply></s:Body>";
+
+        assert_eq!(xml_tag(xml, "RelTime").as_deref(), Some("0:01:02"));
+        assert_eq!(xml_tag(xml, "Title").as_deref(), Some("A & B"));
+        assert_eq!(xml_tag(xml, "Reply").as_deref(), Some("<RelTime>0:01:02</RelTime><Title a=\"1\"> A & B </Title>"));
+        assert_eq!(xml_tag(xml, "Missing"), None);
+    }
+
+    #[test]
+    fn parses_renderer_times() {
+        assert_eq!(parse_time("0:23:41.250"), Some(1421.25));
+        assert_eq!(parse_time("NOT_IMPLEMENTED"), None);
+        assert_eq!(http_header("HTTP/1.1 200 OK\r\nLocation: http://tv/desc.xml\r\n", "LOCATION").as_deref(), Some("http://tv/desc.xml"));
+    }
+}
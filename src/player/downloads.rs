@@ -0,0 +1,476 @@
+use super::AnimePlayer;
+use super::providers::Stream;
+use crate::app::Event;
+use crate::config::Config;
+use crate::mal::models::anime::{Anime, AnimeId};
+use crate::screens::BackgroundUpdate;
+use crate::screens::screens::DOWNLOADS;
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use ureq::Agent;
+use url::Url;
+
+const CHUNK_SIZE: usize = 64 * 1024;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+const EXTENSIONS: [&str; 2] = ["mp4", "ts"];
+
+// no global timeout here, an episode can take a while to download
+static AGENT: OnceLock<Agent> = OnceLock::new();
+pub(super) fn get_agent() -> &'static Agent {
+    AGENT.get_or_init(|| {
+        Agent::config_builder()
+            .timeout_connect(Some(Duration::from_secs(10)))
+            .timeout_recv_response(Some(Duration::from_secs(30)))
+            .build()
+            .into()
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DownloadState {
+    Queued,
+    Downloading,
+    Done,
+    Cancelled,
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct DownloadJob {
+    pub id: usize,
+    pub anime_id: AnimeId,
+    pub title: String,
+    pub episode: String,
+    pub state: DownloadState,
+    pub downloaded_bytes: u64,
+    // 0.0 - 1.0, unknown until the size (or segment count) is known
+    pub progress: Option<f32>,
+}
+
+impl DownloadJob {
+    pub fn is_active(&self) -> bool {
+        matches!(self.state, DownloadState::Queued | DownloadState::Downloading)
+    }
+}
+
+enum Outcome {
+    Finished,
+    Cancelled,
+}
+
+#[derive(Debug, Default)]
+struct Queue {
+    jobs: Vec<DownloadJob>,
+    pending: VecDeque<(usize, Anime)>,
+    workers: usize,
+    next_id: usize,
+}
+
+// downloads run on their own worker threads so they keep going while browsing
+#[derive(Debug)]
+pub struct DownloadManager {
+    queue: Arc<Mutex<Queue>>,
+    app_sx: Sender<Event>,
+}
+
+// the downloaded file for an episode, if there is one
+pub fn local_episode(anime_id: AnimeId, episode: &str) -> Option<PathBuf> {
+    EXTENSIONS
+        .iter()
+        .map(|ext| episode_path(anime_id, episode, ext))
+        .find(|path| path.is_file())
+}
+
+// <download dir>/<mal id>/episode_<episode>.<ext>
+fn episode_path(anime_id: AnimeId, episode: &str, extension: &str) -> PathBuf {
+    Config::download_dir()
+        .join(anime_id.to_string())
+        .join(format!("episode_{}.{}", episode, extension))
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+impl DownloadManager {
+    pub fn new(app_sx: Sender<Event>) -> Self {
+        Self {
+            queue: Arc::new(Mutex::new(Queue::default())),
+            app_sx,
+        }
+    }
+
+    pub fn jobs(&self) -> Vec<DownloadJob> {
+        self.queue.lock().unwrap().jobs.clone()
+    }
+
+    // queues an episode, partial files from earlier attempts are resumed
+    pub fn enqueue(&self, anime: Anime, episode: String) {
+        {
+            let mut queue = self.queue.lock().unwrap();
+            if queue
+                .jobs
+                .iter()
+                .any(|j| j.anime_id == anime.id && j.episode == episode && j.is_active())
+            {
+                return;
+            }
+            queue
+                .jobs
+                .retain(|j| !(j.anime_id == anime.id && j.episode == episode));
+
+            let id = queue.next_id;
+            queue.next_id += 1;
+
+            let already_downloaded = local_episode(anime.id, &episode).is_some();
+            queue.jobs.push(DownloadJob {
+                id,
+                anime_id: anime.id,
+                title: anime.title.clone(),
+                episode,
+                state: if already_downloaded {
+                    DownloadState::Done
+                } else {
+                    DownloadState::Queued
+                },
+                downloaded_bytes: 0,
+                progress: already_downloaded.then_some(1.0),
+            });
+
+            if !already_downloaded {
+                queue.pending.push_back((id, anime));
+            }
+        }
+
+        self.notify();
+        self.spawn_workers();
+    }
+
+    // the worker stops at the next chunk, the partial file is kept for later
+    pub fn cancel(&self, id: usize) {
+        if let Some(job) = self.queue.lock().unwrap().jobs.iter_mut().find(|j| j.id == id)
+            && job.is_active()
+        {
+            job.state = DownloadState::Cancelled;
+        }
+        self.notify();
+    }
+
+    fn notify(&self) {
+        send_jobs(&self.queue, &self.app_sx);
+    }
+
+    fn spawn_workers(&self) {
+        let max_workers = Config::global().download.max_concurrent.max(1);
+        let mut queue = self.queue.lock().unwrap();
+
+        while queue.workers < max_workers && queue.workers < queue.pending.len() {
+            queue.workers += 1;
+            let shared = self.queue.clone();
+            let app_sx = self.app_sx.clone();
+            std::thread::spawn(move || run_worker(shared, app_sx));
+        }
+    }
+}
+
+fn send_jobs(queue: &Mutex<Queue>, app_sx: &Sender<Event>) {
+    let jobs = queue.lock().unwrap().jobs.clone();
+    let update = BackgroundUpdate::new(DOWNLOADS).set("downloads", jobs);
+    app_sx.send(Event::BackgroundNotice(update)).ok();
+}
+
+fn update_job(queue: &Mutex<Queue>, id: usize, updater: impl FnOnce(&mut DownloadJob)) {
+    if let Some(job) = queue.lock().unwrap().jobs.iter_mut().find(|j| j.id == id) {
+        updater(job);
+    }
+}
+
+fn is_cancelled(queue: &Mutex<Queue>, id: usize) -> bool {
+    queue
+        .lock()
+        .unwrap()
+        .jobs
+        .iter()
+        .find(|j| j.id == id)
+        .is_none_or(|j| j.state == DownloadState::Cancelled)
+}
+
+fn run_worker(queue: Arc<Mutex<Queue>>, app_sx: Sender<Event>) {
+    let player = AnimePlayer::new();
+
+    loop {
+        let next = {
+            let mut locked = queue.lock().unwrap();
+            match locked.pending.pop_front() {
+                Some(next) => next,
+                None => {
+                    locked.workers -= 1;
+                    return;
+                }
+            }
+        };
+
+        let (id, anime) = next;
+        if is_cancelled(&queue, id) {
+            continue;
+        }
+
+        let mut episode = String::new();
+        update_job(&queue, id, |job| {
+            job.state = DownloadState::Downloading;
+            episode = job.episode.clone();
+        });
+        send_jobs(&queue, &app_sx);
+
+        let mut last_notice = Instant::now();
+        let result = download_episode(&player, &anime, &episode, |bytes, progress| {
+            update_job(&queue, id, |job| {
+                job.downloaded_bytes = bytes;
+                job.progress = progress;
+            });
+            if last_notice.elapsed() >= PROGRESS_INTERVAL {
+                last_notice = Instant::now();
+                send_jobs(&queue, &app_sx);
+            }
+            !is_cancelled(&queue, id)
+        });
+
+        update_job(&queue, id, |job| {
+            job.state = match result {
+                Ok(Outcome::Finished) => {
+                    job.progress = Some(1.0);
+                    DownloadState::Done
+                }
+                Ok(Outcome::Cancelled) => DownloadState::Cancelled,
+                Err(e) => DownloadState::Failed(e),
+            };
+        });
+        send_jobs(&queue, &app_sx);
+    }
+}
+
+// progress gets the bytes written so far and returns false when the download should stop
+fn download_episode(
+    player: &AnimePlayer,
+    anime: &Anime,
+    episode: &str,
+    progress: impl FnMut(u64, Option<f32>) -> bool,
+) -> Result<Outcome, String> {
+    let (_, stream) = player
+        .resolve_stream(anime, episode)
+        .map_err(|e| e.to_string())?;
+
+    let is_hls = Url::parse(&stream.url)
+        .map(|u| u.path().ends_with(".m3u8"))
+        .unwrap_or(false);
+    let extension = if is_hls { "ts" } else { "mp4" };
+    let target = episode_path(anime.id, episode, extension);
+
+    if let Some(dir) = target.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create download directory: {}", e))?;
+    }
+
+    let part = with_suffix(&target, ".part");
+    let outcome = if is_hls {
+        download_hls(&stream, &part, progress)?
+    } else {
+        download_file(&stream, &part, progress)?
+    };
+
+    if let Outcome::Finished = outcome {
+        std::fs::rename(&part, &target)
+            .map_err(|e| format!("Failed to move finished download: {}", e))?;
+        download_subtitles(&stream, &target);
+    }
+    Ok(outcome)
+}
+
+// saves the stream's subtitles next to the episode as "episode_<ep>.<lang>.<ext>"
+// missing subtitles shouldn't fail a finished download so errors are ignored
+fn download_subtitles(stream: &Stream, target: &Path) {
+    for (index, subtitle) in stream.subtitles.iter().enumerate() {
+        let extension = Url::parse(&subtitle.url)
+            .ok()
+            .and_then(|u| {
+                Path::new(u.path())
+                    .extension()
+                    .map(|ext| ext.to_string_lossy().to_lowercase())
+            })
+            .filter(|ext| ["ass", "srt", "vtt"].contains(&ext.as_str()))
+            .unwrap_or_else(|| "vtt".to_string());
+
+        let language = subtitle
+            .language
+            .clone()
+            .unwrap_or_else(|| index.to_string())
+            .replace(['/', '\\', '.'], "_");
+
+        let path = target.with_extension(format!("{}.{}", language, extension));
+        if let Ok(text) = get(&subtitle.url, stream, 0)
+            .and_then(|mut response| response.body_mut().read_to_string())
+        {
+            let _ = std::fs::write(path, text);
+        }
+    }
+}
+
+// a request with the headers the stream's provider expects
+fn get(url: &str, stream: &Stream, range_start: u64) -> Result<ureq::http::Response<ureq::Body>, ureq::Error> {
+    let mut request = get_agent().get(url);
+    for (key, value) in &stream.headers {
+        request = request.header(key, value);
+    }
+    if range_start > 0 {
+        request = request.header("Range", format!("bytes={}-", range_start));
+    }
+    request.call()
+}
+
+// a plain video file, resumed with a range request
+fn download_file(
+    stream: &Stream,
+    part: &Path,
+    mut progress: impl FnMut(u64, Option<f32>) -> bool,
+) -> Result<Outcome, String> {
+    let mut offset = std::fs::metadata(part).map(|m| m.len()).unwrap_or(0);
+
+    let mut response = match get(&stream.url, stream, offset) {
+        Ok(response) => response,
+        // the part file already holds the whole video
+        Err(ureq::Error::StatusCode(416)) if offset > 0 => return Ok(Outcome::Finished),
+        Err(e) => return Err(format!("Download failed: {}", e)),
+    };
+
+    // the server ignored the range, start over
+    if response.status() != 206 {
+        offset = 0;
+    }
+
+    let total = response
+        .headers()
+        .get("content-length")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(|length| length + offset);
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(offset > 0)
+        .truncate(offset == 0)
+        .open(part)
+        .map_err(|e| format!("Failed to open {}: {}", part.display(), e))?;
+
+    let mut reader = response.body_mut().as_reader();
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut written = offset;
+
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .map_err(|e| format!("Download interrupted: {}", e))?;
+        if read == 0 {
+            break;
+        }
+
+        file.write_all(&buffer[..read])
+            .map_err(|e| format!("Failed to write {}: {}", part.display(), e))?;
+        written += read as u64;
+
+        let fraction = total.map(|total| written as f32 / total.max(1) as f32);
+        if !progress(written, fraction) {
+            return Ok(Outcome::Cancelled);
+        }
+    }
+
+    if total.is_some_and(|total| written < total) {
+        return Err("Download ended early, try again to resume".to_string());
+    }
+
+    Ok(Outcome::Finished)
+}
+
+// an hls media playlist, the segments are joined into a single .ts file
+// <part>.segments keeps "<segments done> <bytes>" so it can be resumed
+fn download_hls(
+    stream: &Stream,
+    part: &Path,
+    mut progress: impl FnMut(u64, Option<f32>) -> bool,
+) -> Result<Outcome, String> {
+    let playlist = get(&stream.url, stream, 0)
+        .and_then(|mut response| response.body_mut().read_to_string())
+        .map_err(|e| format!("Failed to fetch playlist: {}", e))?;
+
+    if playlist
+        .lines()
+        .any(|l| l.starts_with("#EXT-X-KEY") && !l.contains("METHOD=NONE"))
+    {
+        return Err("Encrypted streams can't be downloaded".to_string());
+    }
+
+    let base = Url::parse(&stream.url).map_err(|e| format!("Invalid playlist url: {}", e))?;
+    let segments: Vec<String> = playlist
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .filter_map(|l| base.join(l).ok())
+        .map(|u| u.to_string())
+        .collect();
+
+    if segments.is_empty() {
+        return Err("The playlist has no segments".to_string());
+    }
+
+    let state_file = with_suffix(part, ".segments");
+    let (done, mut written) = std::fs::read_to_string(&state_file)
+        .ok()
+        .and_then(|state| {
+            let (done, bytes) = state.trim().split_once(' ')?;
+            Some((done.parse::<usize>().ok()?, bytes.parse::<u64>().ok()?))
+        })
+        .unwrap_or((0, 0));
+
+    // drop anything written after the last finished segment
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(false)
+        .open(part)
+        .map_err(|e| format!("Failed to open {}: {}", part.display(), e))?;
+    file.set_len(written)
+        .and_then(|_| file.seek(SeekFrom::End(0)))
+        .map_err(|e| format!("Failed to resume {}: {}", part.display(), e))?;
+
+    for (index, segment) in segments.iter().enumerate().skip(done) {
+        let mut response =
+            get(segment, stream, 0).map_err(|e| format!("Download failed: {}", e))?;
+        written += copy_segment(&mut response, &mut file)
+            .map_err(|e| format!("Download interrupted: {}", e))?;
+
+        std::fs::write(&state_file, format!("{} {}", index + 1, written))
+            .map_err(|e| format!("Failed to save progress: {}", e))?;
+
+        let fraction = (index + 1) as f32 / segments.len() as f32;
+        if !progress(written, Some(fraction)) {
+            return Ok(Outcome::Cancelled);
+        }
+    }
+
+    std::fs::remove_file(&state_file).ok();
+    Ok(Outcome::Finished)
+}
+
+fn copy_segment(
+    response: &mut ureq::http::Response<ureq::Body>,
+    file: &mut File,
+) -> std::io::Result<u64> {
+    std::io::copy(&mut response.body_mut().as_reader(), file)
+}
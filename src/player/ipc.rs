@@ -0,0 +1,45 @@
+use serde_json::Value;
+use std::path::PathBuf;
+
+// the socket mpv listens on while mal-cli plays something, one per mal-cli process
+pub fn socket_path() -> PathBuf {
+    std::env::temp_dir().join(format!("mal-cli-mpv-{}.sock", std::process::id()))
+}
+
+// mpv argument opening the socket
+pub fn server_arg() -> String {
+    format!("--input-ipc-server={}", socket_path().display())
+}
+
+// sends one command to the running mpv and returns its data, none when mpv isn't there or refused
+#[cfg(unix)]
+pub fn request(command: Value) -> Option<Value> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+    use std::time::Duration;
+
+    const REQUEST_ID: u64 = 1;
+
+    let mut stream = UnixStream::connect(socket_path()).ok()?;
+    stream.set_read_timeout(Some(Duration::from_secs(1))).ok()?;
+    let message = serde_json::json!({ "command": command, "request_id": REQUEST_ID });
+    writeln!(stream, "{}", message).ok()?;
+
+    // events can come before the reply
+    for line in BufReader::new(stream).lines() {
+        let reply: Value = serde_json::from_str(&line.ok()?).ok()?;
+        if reply.get("request_id").and_then(Value::as_u64) != Some(REQUEST_ID) {
+            continue;
+        }
+        if reply.get("error").and_then(Value::as_str) != Some("success") {
+            return None;
+        }
+        return Some(reply.get("data").cloned().unwrap_or(Value::Null));
+    }
+    None
+}
+
+#[cfg(not(unix))]
+pub fn request(_command: Value) -> Option<Value> {
+    None
+}
@@ -0,0 +1,297 @@
+use super::{AnimePlayer, MATCH_CONFIDENCE, MATCH_MARGIN};
+use crate::config::Config;
+use crate::mal::models::anime::Anime;
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+
+const VIDEO_EXTENSIONS: [&str; 7] = ["mkv", "mp4", "avi", "webm", "m4v", "ts", "mov"];
+
+// filled by the library scanner when the app starts
+static LIBRARY: RwLock<Vec<LibraryFile>> = RwLock::new(Vec::new());
+
+// a video file on disk parsed from its release style name
+// eg. "[Group] Some Title - 05 [1080p].mkv" or "Some.Title.S02E05.720p.mkv"
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct LibraryFile {
+    pub path: PathBuf,
+    pub group: Option<String>,
+    pub title: String,
+    pub episode: String,
+    pub resolution: Option<u32>,
+}
+
+struct Patterns {
+    group: Regex,
+    resolution: Regex,
+    tags: Regex,
+    season_episode: Regex,
+    dash_episode: Regex,
+    named_episode: Regex,
+    trailing_episode: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| Patterns {
+        group: Regex::new(r"^\[([^\]]+)\]").unwrap(),
+        resolution: Regex::new(r"(?i)\b(?:\d{3,4}x(\d{3,4})|(\d{3,4})p)\b").unwrap(),
+        tags: Regex::new(r"\[[^\]]*\]|\([^)]*\)|\{[^}]*\}").unwrap(),
+        season_episode: Regex::new(r"(?i)\bS(\d{1,2})\s?E(\d{1,4}(?:\.\d)?)").unwrap(),
+        dash_episode: Regex::new(r"\s-\s(\d{1,4}(?:\.\d)?)(?:v\d)?(?:\s|$)").unwrap(),
+        named_episode: Regex::new(r"(?i)\b(?:EP?|Episode)\s?(\d{1,4}(?:\.\d)?)(?:v\d)?\b")
+            .unwrap(),
+        trailing_episode: Regex::new(r"\s(\d{1,4})(?:v\d)?$").unwrap(),
+    })
+}
+
+impl LibraryFile {
+    pub fn parse(path: &Path) -> Option<Self> {
+        let name = path.file_stem()?.to_string_lossy().to_string();
+        Self::from_name(&name, path)
+    }
+
+    // a file name without its extension, or a media title shown by a player
+    pub fn from_name(name: &str, path: &Path) -> Option<Self> {
+        let patterns = patterns();
+
+        let group = patterns
+            .group
+            .captures(name)
+            .map(|caps| caps[1].trim().to_string());
+
+        // resolutions usually live in the tags, so read them before those are stripped
+        let resolution = patterns.resolution.captures(name).and_then(|caps| {
+            caps.get(1)
+                .or_else(|| caps.get(2))
+                .and_then(|m| m.as_str().parse::<u32>().ok())
+        });
+
+        // dots only separate words when the name has no spaces, "Dr. Stone" keeps its dot
+        let cleaned = patterns.tags.replace_all(name, " ").replace('_', " ");
+        let cleaned = if cleaned.trim().contains(' ') {
+            cleaned
+        } else {
+            cleaned.replace('.', " ")
+        };
+        let cleaned = patterns.resolution.replace_all(&cleaned, " ");
+        let cleaned = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        let (title, season, episode) = if let Some(caps) = patterns.season_episode.captures(&cleaned) {
+            let start = caps.get(0)?.start();
+            (&cleaned[..start], caps[1].parse::<u32>().ok(), caps[2].to_string())
+        } else {
+            let caps = patterns
+                .dash_episode
+                .captures(&cleaned)
+                .or_else(|| patterns.named_episode.captures(&cleaned))
+                .or_else(|| patterns.trailing_episode.captures(&cleaned))?;
+            let start = caps.get(0)?.start();
+            (&cleaned[..start], None, caps[1].to_string())
+        };
+
+        let title = title.trim().trim_end_matches('-').trim();
+        if title.is_empty() {
+            return None;
+        }
+
+        // "S02E05" turns into "<title> Season 2" so it can match the mal entry of that season
+        let title = match season {
+            Some(season) if season > 1 => format!("{} Season {}", title, season),
+            _ => title.to_string(),
+        };
+
+        Some(Self {
+            path: path.to_path_buf(),
+            group,
+            title,
+            episode: normalize_episode(&episode),
+            resolution,
+        })
+    }
+}
+
+// "05" -> "5", "12.5" stays
+fn normalize_episode(episode: &str) -> String {
+    match episode.split_once('.') {
+        Some((whole, part)) => format!("{}.{}", whole.parse::<u32>().unwrap_or(0), part),
+        None => episode.parse::<u32>().unwrap_or(0).to_string(),
+    }
+}
+
+pub fn is_video(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| VIDEO_EXTENSIONS.contains(&ext.as_str()))
+}
+
+// symlinked folders are followed, but each folder only once so links back up the tree dont loop
+fn walk(dir: &Path, files: &mut Vec<LibraryFile>, visited: &mut HashSet<PathBuf>) {
+    let Ok(real) = dir.canonicalize() else {
+        return;
+    };
+    if !visited.insert(real) {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            walk(&path, files, visited);
+            continue;
+        }
+
+        if is_video(&path) && let Some(file) = LibraryFile::parse(&path) {
+            files.push(file);
+        }
+    }
+}
+
+// walks the configured directories and replaces the library, returns the number of episodes found
+pub fn scan() -> usize {
+    let mut files = Vec::new();
+    let mut visited = HashSet::new();
+    for dir in &Config::global().library.directories {
+        walk(&Config::expand_path(dir), &mut files, &mut visited);
+    }
+
+    let found = files.len();
+    *LIBRARY.write().unwrap() = files;
+    found
+}
+
+// the list entry a parsed title belongs to, none when it isn't a confident match
+pub fn match_title<'a>(animes: impl IntoIterator<Item = &'a Anime>, title: &str) -> Option<&'a Anime> {
+    let mut scored: Vec<(&Anime, f32)> = animes
+        .into_iter()
+        .map(|anime| (anime, AnimePlayer::title_score(anime, &[title])))
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let (best, score) = *scored.first()?;
+    let runner_up = scored.get(1).map(|(_, score)| *score).unwrap_or(0.0);
+    (score >= MATCH_CONFIDENCE && score - runner_up >= MATCH_MARGIN).then_some(best)
+}
+
+// the files matching the anime, scored the same way as the source search
+pub fn episodes_for(anime: &Anime) -> Vec<LibraryFile> {
+    let library = LIBRARY.read().unwrap();
+
+    let mut titles: Vec<&str> = library.iter().map(|f| f.title.as_str()).collect();
+    titles.sort_unstable();
+    titles.dedup();
+
+    let scored: Vec<(&str, f32)> = titles
+        .into_iter()
+        .map(|title| (title, AnimePlayer::title_score(anime, &[title])))
+        .filter(|(_, score)| *score >= MATCH_CONFIDENCE)
+        .collect();
+
+    let best = scored.iter().map(|(_, score)| *score).fold(0.0, f32::max);
+    let matching: Vec<&str> = scored
+        .into_iter()
+        .filter(|(_, score)| best - score < MATCH_MARGIN)
+        .map(|(title, _)| title)
+        .collect();
+
+    let mut files: Vec<LibraryFile> = library
+        .iter()
+        .filter(|f| matching.contains(&f.title.as_str()))
+        .cloned()
+        .collect();
+
+    // best resolution first so it wins when an episode exists more than once
+    files.sort_by(|a, b| {
+        let number = |f: &LibraryFile| f.episode.parse::<f32>().unwrap_or(0.0);
+        number(a)
+            .partial_cmp(&number(b))
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(b.resolution.cmp(&a.resolution))
+    });
+    files.dedup_by(|a, b| a.episode == b.episode);
+    files
+}
+
+pub fn find_episode(anime: &Anime, episode: &str) -> Option<PathBuf> {
+    let episode = normalize_episode(episode);
+    episodes_for(anime)
+        .into_iter()
+        .find(|f| f.episode == episode)
+        .map(|f| f.path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // group, title, episode and resolution of a file name
+    fn parsed(name: &str) -> Option<(Option<String>, String, String, Option<u32>)> {
+        let file = LibraryFile::parse(Path::new(name))?;
+        Some((file.group, file.title, file.episode, file.resolution))
+    }
+
+    #[test]
+    fn reads_fansub_names() {
+        assert_eq!(
+            parsed("[SubsPlease] Sousou no Frieren - 05 (1080p) [ABCD1234].mkv"),
+            Some((Some("SubsPlease".to_string()), "Sousou no Frieren".to_string(), "5".to_string(), Some(1080)))
+        );
+        assert_eq!(
+            parsed("[Group] Dr. Stone - 12.5v2 [720p].mp4").map(|(_, title, episode, _)| (title, episode)),
+            Some(("Dr. Stone".to_string(), "12.5".to_string()))
+        );
+    }
+
+    #[test]
+    fn reads_scene_names() {
+        assert_eq!(
+            parsed("Shingeki.no.Kyojin.S02E05.1920x1080.mkv"),
+            Some((None, "Shingeki no Kyojin Season 2".to_string(), "5".to_string(), Some(1080)))
+        );
+        assert_eq!(
+            parsed("Mob_Psycho_100_S01E03_720p.mkv").map(|(_, title, episode, _)| (title, episode)),
+            Some(("Mob Psycho 100".to_string(), "3".to_string()))
+        );
+    }
+
+    #[test]
+    fn reads_episode_words_and_trailing_numbers() {
+        assert_eq!(
+            parsed("One Piece Episode 1071.mkv").map(|(_, title, episode, _)| (title, episode)),
+            Some(("One Piece".to_string(), "1071".to_string()))
+        );
+        assert_eq!(
+            parsed("Bocchi the Rock 07.mkv").map(|(_, title, episode, _)| (title, episode)),
+            Some(("Bocchi the Rock".to_string(), "7".to_string()))
+        );
+    }
+
+    #[test]
+    fn skips_names_without_an_episode_or_title() {
+        assert_eq!(parsed("Spirited Away.mkv"), None);
+        assert_eq!(parsed("[Group] 05 [1080p].mkv"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stops_at_symlink_loops() {
+        let dir = std::env::temp_dir().join(format!("mal-cli-library-{}", std::process::id()));
+        let show = dir.join("Frieren");
+        std::fs::create_dir_all(&show).unwrap();
+        std::fs::write(show.join("[SubsPlease] Sousou no Frieren - 05 (1080p).mkv"), "").unwrap();
+        // points back up to the library root
+        std::os::unix::fs::symlink(&dir, show.join("back")).ok();
+
+        let mut files = Vec::new();
+        walk(&dir, &mut files, &mut HashSet::new());
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].episode, "5");
+    }
+}
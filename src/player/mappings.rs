@@ -0,0 +1,59 @@
+use crate::config::Config;
+use crate::mal::models::anime::AnimeId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const MAPPINGS_FILE: &str = "source_mappings.toml";
+
+// mappings saved before there were multiple providers
+fn def_provider() -> String {
+    "allanime".to_string()
+}
+
+// a show picked manually for a mal entry
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SourceMapping {
+    #[serde(default = "def_provider")]
+    pub provider: String,
+    pub show_id: String,
+    pub name: String,
+}
+
+// mal id -> provider show id, stored in the data dir so later plays skip the search
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SourceMappings {
+    #[serde(default)]
+    mappings: HashMap<String, SourceMapping>,
+}
+
+impl SourceMappings {
+    pub fn load() -> Self {
+        let path = Config::data_dir().join(MAPPINGS_FILE);
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| toml::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let app_dir = Config::data_dir();
+        if !app_dir.exists() {
+            std::fs::create_dir_all(&app_dir)
+                .map_err(|e| format!("Failed to create data directory: {}", e))?;
+        }
+
+        let toml = toml::to_string(self)
+            .map_err(|e| format!("Failed to serialize source mappings: {}", e))?;
+
+        std::fs::write(app_dir.join(MAPPINGS_FILE), toml)
+            .map_err(|e| format!("Failed to write source mappings: {}", e))
+    }
+
+    pub fn get(&self, anime_id: AnimeId) -> Option<&SourceMapping> {
+        self.mappings.get(&anime_id.to_string())
+    }
+
+    pub fn set(&mut self, anime_id: AnimeId, mapping: SourceMapping) {
+        self.mappings.insert(anime_id.to_string(), mapping);
+    }
+}
@@ -1,27 +1,38 @@
-mod allanime;
-use allanime::EpisodeSearch;
-use allanime::LinksSearch;
-use allanime::ShowEdge;
-use allanime::ShowSearch;
-use allanime::SourceUrl;
+pub mod cache;
+pub mod cast;
+pub mod downloads;
+mod ipc;
+pub mod library;
+pub mod mappings;
+pub mod mpris;
+mod playlist;
+pub mod providers;
+pub mod skip;
+use mappings::SourceMapping;
+use providers::SourceShow;
+use providers::Stream;
+use providers::Subtitle;
+use providers::StreamProvider;
 use regex::Regex;
-use url::Url;
 
 use crate::config::Config;
 use crate::mal::models::anime::Anime;
-use crate::mal::network::send_request;
-use crate::mal::network::send_request_expect_text;
-use crate::params;
-use crate::utils::stringManipulation::levenshtein_distance;
-use serde_json::json;
+use mappings::SourceMappings;
+use crate::utils::stringManipulation::title_similarity;
 use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use shell_escape::escape;
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::utils::hooks::{HookPayload, run_hook};
+use serde::Serialize;
 
-const BASE: &str = "https://allanime.day";
-const API: &str = "https://api.allanime.day/api";
-const UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:109.0) Gecko/20100101 Firefox/121.0";
-const REF: &str = "https://allmanga.to";
+// a match below this score (or too close to the runner up) is left for the user to pick
+const MATCH_CONFIDENCE: f32 = 0.85;
+const MATCH_MARGIN: f32 = 0.05;
+const SUBTITLE_EXTENSIONS: [&str; 3] = ["ass", "srt", "vtt"];
+
+// set while mpv started by mal-cli is open
+static PLAYING: AtomicBool = AtomicBool::new(false);
 
 #[derive(Debug, Clone)]
 pub enum PlayError {
@@ -33,13 +44,59 @@ pub enum PlayError {
     },
     NotFound(String),
     NoResults(String),
+    AmbiguousMatch(Vec<ShowCandidate>),
     Other(String),
 }
 
-#[derive(Debug)]
+// a show from the source ranked against a mal entry
+#[derive(Debug, Clone)]
+pub struct ShowCandidate {
+    pub provider: String,
+    pub id: String,
+    pub name: String,
+    pub english_name: Option<String>,
+    pub year: Option<u16>,
+    pub sub_episodes: u32,
+    pub dub_episodes: u32,
+    pub score: f32,
+}
+
+// an episode available on the source, the number can be a special like "12.5"
+#[derive(Debug, Clone)]
+pub struct EpisodeEntry {
+    pub episode: String,
+    pub sub: bool,
+    pub dub: bool,
+}
+
+impl EpisodeEntry {
+    pub fn number(&self) -> f32 {
+        self.episode.parse().unwrap_or(0.0)
+    }
+}
+
+impl std::fmt::Display for ShowCandidate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.provider, self.name)?;
+        if let Some(english_name) = self.english_name.as_ref().filter(|n| **n != self.name) {
+            write!(f, " / {}", english_name)?;
+        }
+        if let Some(year) = self.year {
+            write!(f, " ({})", year)?;
+        }
+        write!(
+            f,
+            " - {} eps - {:.0}%",
+            self.sub_episodes.max(self.dub_episodes),
+            self.score * 100.0
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 #[allow(dead_code)]
 pub struct PlayResult {
-    pub episode: u32,
+    pub episode: String,
     pub current_time: String,
     pub total_time: String,
     pub percentage: u8,
@@ -54,8 +111,11 @@ pub struct AnimePlayer {
     av_regex: Regex,
     exit_regex: Regex,
 
-    // url Regex:
-    wixmp_regex: Regex,
+    // tried in order until one of them works
+    providers: Vec<Box<dyn StreamProvider>>,
+
+    // the cli keeps stdout for its own output, so hooks dont print there
+    quiet_hooks: bool,
 }
 
 impl std::fmt::Display for PlayError {
@@ -87,11 +147,140 @@ impl std::fmt::Display for PlayError {
                 "ani-cli replied:\nError: {}\nthe anime might not be available yet",
                 msg
             ),
+            PlayError::AmbiguousMatch(candidates) => write!(
+                f,
+                "Could not confidently match a source ({} candidates)",
+                candidates.len()
+            ),
             PlayError::Other(msg) => write!(f, "Error running ani-cli: \n{}", msg),
         }
     }
 }
 
+// if mal-cli has its own mpv open right now
+pub fn is_playing() -> bool {
+    PLAYING.load(Ordering::SeqCst)
+}
+
+// marks mpv as open until it is dropped
+struct Playing;
+
+impl Playing {
+    fn start() -> Self {
+        PLAYING.store(true, Ordering::SeqCst);
+        Playing
+    }
+}
+
+impl Drop for Playing {
+    fn drop(&mut self) {
+        PLAYING.store(false, Ordering::SeqCst);
+    }
+}
+
+// "01:02:03" -> 3723
+fn seconds(time: &str) -> u64 {
+    time.split(':')
+        .fold(0, |total, part| total * 60 + part.parse::<u64>().unwrap_or(0))
+}
+
+// if an episode stopped at this point counts as watched
+fn is_completed(percentage: u8, seconds_left: u64) -> bool {
+    let config = &Config::global().player;
+    percentage >= config.completion_threshold
+        || config
+            .completion_seconds_left
+            .is_some_and(|limit| seconds_left <= limit)
+}
+
+// how far a player got in the current file, in seconds
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PlaybackPosition {
+    pub time: f64,
+    pub duration: f64,
+}
+
+impl PlaybackPosition {
+    pub fn result(&self, episode: &str, reached_end: bool) -> PlayResult {
+        let percentage = if self.duration > 0.0 {
+            (self.time / self.duration * 100.0).clamp(0.0, 100.0) as u8
+        } else {
+            0
+        };
+        let seconds_left = (self.duration - self.time).max(0.0) as u64;
+
+        PlayResult {
+            episode: episode.to_string(),
+            current_time: format_time(self.time),
+            total_time: format_time(self.duration),
+            percentage: if reached_end { 100 } else { percentage },
+            fully_watched: reached_end,
+            completed: reached_end || (self.duration > 0.0 && is_completed(percentage, seconds_left)),
+        }
+    }
+}
+
+// 3723.4 -> "01:02:03"
+fn format_time(seconds: f64) -> String {
+    let seconds = seconds.max(0.0) as u64;
+    format!("{:02}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+// a downloaded episode or one from the local library
+pub fn local_episode(anime: &Anime, episode: &str) -> Option<PathBuf> {
+    downloads::local_episode(anime.id, episode).or_else(|| library::find_episode(anime, episode))
+}
+
+// subtitle files next to a video, "episode_5.srt" or "episode_5.en.ass" for "episode_5.mp4"
+pub fn local_subtitles(video: &Path) -> Vec<Subtitle> {
+    let (Some(dir), Some(stem)) = (video.parent(), video.file_stem()) else {
+        return Vec::new();
+    };
+    let stem = stem.to_string_lossy().to_string();
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut subtitles: Vec<Subtitle> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase())
+                .is_some_and(|ext| SUBTITLE_EXTENSIONS.contains(&ext.as_str()))
+        })
+        .filter_map(|path| {
+            // whatever is between the video name and the extension is the language
+            // "episode_12.5.en.vtt" is another episode, not "5.en" subtitles of "episode_12"
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            let language = match name.strip_prefix(&stem)? {
+                "" => None,
+                rest => Some(rest.strip_prefix('.').filter(|tag| is_language_tag(tag))?),
+            };
+
+            Some(Subtitle {
+                url: path.to_string_lossy().to_string(),
+                language: language.map(str::to_string),
+            })
+        })
+        .collect();
+
+    subtitles.sort_by(|a, b| a.url.cmp(&b.url));
+    subtitles
+}
+
+// "en", "eng" or "pt-BR"
+fn is_language_tag(text: &str) -> bool {
+    let (language, region) = match text.split_once('-') {
+        Some((language, region)) => (language, Some(region)),
+        None => (text, None),
+    };
+    (2..=3).contains(&language.len())
+        && language.chars().all(|c| c.is_ascii_alphabetic())
+        && region.is_none_or(|region| !region.is_empty() && region.chars().all(|c| c.is_ascii_alphabetic()))
+}
+
 impl AnimePlayer {
     pub fn new() -> Self {
         AnimePlayer {
@@ -99,14 +288,17 @@ impl AnimePlayer {
             av_regex: Regex::new(r"AV: (\d{2}:\d{2}:\d{2}) / (\d{2}:\d{2}:\d{2}) \((\d+)%\)")
                 .unwrap(),
             exit_regex: Regex::new(r"Exiting\.\.\. \((.*?)\)").unwrap(),
-            wixmp_regex: Regex::new(
-                r#"^video\.wixstatic\.com/video/([^/]+)/,([^/]+),/mp4/file\.mp4$"#,
-            )
-            .unwrap(),
+            providers: providers::configured_providers(),
+            quiet_hooks: false,
         }
     }
 
-    pub fn extract_play_info(&self, stdout: &str, episode: u32) -> Option<PlayResult> {
+    pub fn quiet_hooks(mut self) -> Self {
+        self.quiet_hooks = true;
+        self
+    }
+
+    pub fn extract_play_info(&self, stdout: &str, episode: &str) -> Option<PlayResult> {
         // return default if no output
         if stdout.is_empty() {
             return Some(PlayResult {
@@ -115,7 +307,7 @@ impl AnimePlayer {
                 completed: false,
                 fully_watched: false,
                 percentage: 0,
-                episode,
+                episode: episode.to_string(),
             })
         }
 
@@ -134,545 +326,542 @@ impl AnimePlayer {
 
         let percentage = last_av[3].parse().unwrap_or(0);
 
+        let seconds_left = seconds(&last_av[2]).saturating_sub(seconds(&last_av[1]));
+        let completed = is_completed(percentage, seconds_left);
+
         Some(PlayResult {
             current_time: last_av[1].to_string(),
             total_time: last_av[2].to_string(),
-            completed: percentage >= 90,
+            completed,
             fully_watched: exit_reason == Some("End of file"),
             percentage,
-            episode,
+            episode: episode.to_string(),
         })
     }
 
+
     pub fn play_episode_manually(
         &self,
         anime: &Anime,
-        episode: u32,
+        episode: &str,
     ) -> Result<PlayResult, PlayError> {
         if anime.status == "upcoming" {
             return Err(PlayError::NotReleased(Box::new(anime.clone())));
         }
 
-        ratatui::restore();
+        let stream = self.episode_stream(anime, episode)?;
+        Self::prefetch_next(anime, episode);
 
         // hook
-        if let Some(hook) = Config::global().player.pre_playback_hook.clone() {
-            if let Err(e) = self.run_command(&hook, anime, episode, None) {
+        if let Some(hook) = &Config::global().player.pre_playback_hook {
+            let payload = HookPayload::new("pre_playback").anime(anime).episode(episode);
+            if let Err(e) = run_hook(hook, &payload, self.quiet_hooks) {
                 eprintln!("Failed to run pre-playback hook: {}", e);
             }
         };
 
-
-        // get available shows for the given anime title
-        let shows = self.get_shows(anime.title.clone())?;
-
-        // extract the correct show id from the list of shows
-        let id = self.extract_correct_id(&shows, anime)?;
-
-        // get the available episodes for the show
-        let available_episodes = self.get_episode_providers(&id, episode)?;
-
-        // extract the correct (the one with highest priority) episode from the list of available episodes
-        let candidate = self.extract_best_candidate(&available_episodes)?;
-
         let result = if Config::global().player.disable_default_player {
             String::new()
         } else {
-            self.play_video_in_mpv(&candidate)?
-        };
-
-
-        // hook
-        if let Some(hook) = Config::global().player.post_playback_hook.clone() {
-            if let Err(e) = self.run_command(&hook, anime, episode, Some(&candidate)) {
-                eprintln!("Failed to run pre-playback hook: {}", e);
-            }
+            let _session = mpris::publish(anime, episode);
+            let _playing = Playing::start();
+            self.play_video_in_mpv(&stream, &Self::skip_args(anime, episode))?
         };
 
         // mark as completed
-        if Config::global().player.always_complete_episode {
-            return Ok(PlayResult {
+        let play_result = if Config::global().player.always_complete_episode {
+            Some(PlayResult {
                 current_time: "00:00:00".to_string(),
                 total_time: "00:00:00".to_string(),
                 completed: true,
                 fully_watched: true,
                 percentage: 100,
-                episode,
-            });
-        }
+                episode: episode.to_string(),
+            })
+        } else {
+            self.extract_play_info(&result, episode)
+        };
+
+        // hook
+        if let Some(hook) = &Config::global().player.post_playback_hook {
+            let mut payload = HookPayload::new("post_playback")
+                .anime(anime)
+                .episode(episode)
+                .stream(&stream);
+            if let Some(play_result) = &play_result {
+                payload = payload.play_result(play_result);
+            }
+            if let Err(e) = run_hook(hook, &payload, self.quiet_hooks) {
+                eprintln!("Failed to run post-playback hook: {}", e);
+            }
+        };
 
-        self.extract_play_info(&result, episode).ok_or_else(|| {
+        play_result.ok_or_else(|| {
             PlayError::Other("player did not return any play information".to_string())
         })
     }
 
-    // searches for shows with the given name and returns a list of ShowEdge
-    fn get_shows(&self, show: String) -> Result<Vec<ShowEdge>, PlayError> {
-        let gql = r#"
-      query( $search: SearchInput, $limit: Int, $page: Int,
-             $translationType: VaildTranslationTypeEnumType,
-             $countryOrigin: VaildCountryOriginEnumType ) {
-        shows(
-          search: $search, limit: $limit, page: $page,
-          translationType: $translationType, countryOrigin: $countryOrigin
-        ) {
-          edges { _id name availableEpisodes }
-        }
-      }"#;
-
-        let variables = json!({
-            "search": {"allowAdult": false, "allowUnknown": false, "query": show},
-            "limit": 40,
-            "page": 1,
-            "translationType": "sub",
-            "countryOrigin": "ALL"
-        })
-        .to_string();
-
-        let headers = params![
-            "User-Agent" => UA,
-            "Referer" => REF,
-        ];
-
-        let params = params![
-            "query" => gql,
-            "variables" => variables,
-        ];
-
-        let result = send_request::<ShowSearch>("GET", API.to_string(), params, headers, None);
-        match result {
-            Ok(response) => {
-                if response.data.shows.edges.is_empty() {
-                    return Err(PlayError::NoResults("No shows found".to_string()));
-                }
-                Ok(response.data.shows.edges)
+    // an episode on disk is played instead of streaming it, the mapping is none for those
+    fn locate_stream(
+        &self,
+        anime: &Anime,
+        episode: &str,
+    ) -> Result<(Stream, Option<SourceMapping>), PlayError> {
+        match local_episode(anime, episode) {
+            Some(path) => {
+                let mut stream = Stream {
+                    url: path.to_string_lossy().to_string(),
+                    headers: Vec::new(),
+                    subtitles: local_subtitles(&path),
+                };
+                stream.sort_subtitles();
+                Ok((stream, None))
             }
-            Err(e) => {
-                Err(PlayError::Other(format!("Error fetching shows: {}", e)))
+            None => {
+                let (show, stream) = self.resolve_stream(anime, episode)?;
+                Ok((stream, Some(show)))
             }
         }
     }
 
-    // finds the correct show id from the list of shows and returns its id
-    fn extract_correct_id(&self, shows: &[ShowEdge], anime: &Anime) -> Result<String, PlayError> {
+    fn episode_stream(&self, anime: &Anime, episode: &str) -> Result<Stream, PlayError> {
+        let (stream, show) = self.locate_stream(anime, episode)?;
+        match show {
+            Some(show) => eprintln!(
+                "Playing \"{}\" ({} on {}) episode: {}",
+                show.name, show.show_id, show.provider, episode
+            ),
+            None => eprintln!(
+                "Playing \"{}\" episode: {} from {}",
+                anime.title, episode, stream.url
+            ),
+        }
+        Ok(stream)
+    }
 
-        // Try to match name exactly first:
-        let show = shows.iter()
-            .find(|s| s.name.eq_ignore_ascii_case(&anime.title))
-            .or_else(|| {
-                // If no exact match, find the one(s) with lowest distance
-                if shows.is_empty() {
-                    return None;
-                }
+    // plays the episode on a renderer, on_progress gets the position while it plays
+    pub fn cast_episode(
+        &self,
+        anime: &Anime,
+        episode: &str,
+        renderer: &cast::Renderer,
+        stop: &AtomicBool,
+        on_progress: impl FnMut(PlaybackPosition),
+    ) -> Result<PlayResult, PlayError> {
+        if anime.status == "upcoming" {
+            return Err(PlayError::NotReleased(Box::new(anime.clone())));
+        }
 
-                // Calculate distances for all shows
-                let distances: Vec<(usize, &ShowEdge)> = shows.iter()
-                    .map(|s| (levenshtein_distance(&s.name.to_lowercase(), &anime.title.to_lowercase()), s))
-                    .collect();
-
-                // Find the minimum distance
-                let min_distance = distances.iter()
-                    .map(|(dist, _)| *dist)
-                    .min()
-                    .unwrap();
-
-                // Get all the smallest distance matches 
-                let best_matches = distances.into_iter()
-                    .filter(|(dist, _)| *dist == min_distance)
-                    .map(|(_, show)| show)
-                    .collect::<Vec<&ShowEdge>>();
-
-                // select the one with the most episodes:
-                best_matches.into_iter()
-                    .max_by_key(|show| show.available_episodes.sub + show.available_episodes.dub)
-            })
-            .ok_or(PlayError::NoResults(
-                "No shows found".to_string(),
-            ))?;
+        let (stream, _) = self.locate_stream(anime, episode)?;
+        Self::prefetch_next(anime, episode);
 
-        println!("Playing \"{}\" ({}) episode: {}", show.name, show.id, anime.my_list_status.num_episodes_watched + 1);
+        // the proxy has to live until the renderer is done
+        let (url, _proxy) = cast::playable_url(&stream, renderer).map_err(PlayError::Other)?;
+        let title = format!("{} - Episode {}", anime.title, episode);
+        let (position, reached_end) =
+            cast::play(renderer, &url, &title, stop, on_progress).map_err(PlayError::Other)?;
 
-        Ok(show.id.clone())
+        Ok(position.result(episode, reached_end))
     }
 
-    fn get_episode_providers(
+    // plays the episodes in one mpv, on_finished gets each episode as mpv moves past it
+    pub fn play_playlist(
         &self,
-        show_id: &str,
-        episode: u32,
-    ) -> Result<Vec<SourceUrl>, PlayError> {
-        let gql = r#"
-        query($showId: String!, $translationType: VaildTranslationTypeEnumType!, $episodeString: String!) {
-            episode(showId: $showId, translationType: $translationType, episodeString: $episodeString) {
-                episodeString sourceUrls
-            }
+        anime: &Anime,
+        episodes: &[String],
+        on_finished: impl FnMut(PlayResult),
+    ) -> Result<(), PlayError> {
+        if anime.status == "upcoming" {
+            return Err(PlayError::NotReleased(Box::new(anime.clone())));
+        }
+        if Config::global().player.disable_default_player {
+            return Err(PlayError::Other(
+                "Playing multiple episodes needs the default player".to_string(),
+            ));
         }
-      "#;
 
-        let variables = json!({
-            "showId": show_id,
-            "translationType": "sub",
-            "episodeString": episode.to_string(),
-        })
-        .to_string();
+        // everything is resolved before mpv starts, the list stops at the first episode that can't be found
+        let mut entries: Vec<(String, Stream)> = Vec::new();
+        for episode in episodes {
+            match self.episode_stream(anime, episode) {
+                Ok(stream) => entries.push((episode.clone(), stream)),
+                Err(e) if entries.is_empty() => return Err(e),
+                Err(e) => {
+                    eprintln!("Stopping the playlist before episode {}: {}", episode, e);
+                    break;
+                }
+            }
+        }
 
-        let headers = params![
-            "User-Agent" => UA,
-            "Referer" => REF,
-        ];
+        // hook
+        if let Some(hook) = &Config::global().player.pre_playback_hook {
+            let payload = HookPayload::new("pre_playback").anime(anime).episode(&entries[0].0);
+            if let Err(e) = run_hook(hook, &payload, self.quiet_hooks) {
+                eprintln!("Failed to run pre-playback hook: {}", e);
+            }
+        };
 
-        let params = params![
-            "query" => gql,
-            "variables" => variables,
-        ];
+        let mut args = Vec::new();
+        let mut has_chapters = false;
+        for (episode, stream) in &entries {
+            let chapters = Self::chapter_args(anime, episode);
+            has_chapters |= !chapters.is_empty();
+
+            args.push("--{".to_string());
+            args.extend(Self::stream_args(stream));
+            args.extend(chapters);
+            args.push(stream.url.clone());
+            args.push("--}".to_string());
+        }
+        if has_chapters && skip::SkipPreferences::load().auto_skip(anime.id) {
+            args.extend(skip::autoskip_args());
+        }
 
-        let result = send_request::<EpisodeSearch>("GET", API.to_string(), params, headers, None);
+        let episodes: Vec<String> = entries.iter().map(|(episode, _)| episode.clone()).collect();
+        let session = mpris::publish(anime, &episodes[0]);
+        let playing = Playing::start();
+        playlist::play(&args, &episodes, session.as_ref(), on_finished)?;
+        drop(playing);
 
-        match result {
-            Ok(mut response) => {
-                if response.data.episode.source_urls.is_empty() {
-                    return Err(PlayError::NoResults("No episodes found".to_string()));
-                }
+        // hook
+        if let Some(hook) = &Config::global().player.post_playback_hook
+            && let Some((episode, stream)) = entries.last()
+        {
+            let payload = HookPayload::new("post_playback")
+                .anime(anime)
+                .episode(episode)
+                .stream(stream);
+            if let Err(e) = run_hook(hook, &payload, self.quiet_hooks) {
+                eprintln!("Failed to run post-playback hook: {}", e);
+            }
+        };
 
-                for source in response.data.episode.source_urls.iter_mut() {
-                    if source.source_url.starts_with("http") {
-                        continue;
-                    }
+        Ok(())
+    }
 
-                    let (source_url, http_appended) = AnimePlayer::decode_clock(&source.source_url)
-                        .unwrap_or_else(|_| (source.source_url.clone(), false));
+    // opening/ending chapters for mpv, playback goes on without them when they can't be found
+    fn skip_args(anime: &Anime, episode: &str) -> Vec<String> {
+        let mut args = Self::chapter_args(anime, episode);
+        if !args.is_empty() && skip::SkipPreferences::load().auto_skip(anime.id) {
+            args.extend(skip::autoskip_args());
+        }
+        args
+    }
 
-                    source.source_url = source_url;
+    fn chapter_args(anime: &Anime, episode: &str) -> Vec<String> {
+        if !Config::global().skip.enabled {
+            return Vec::new();
+        }
 
-                    if !http_appended {
-                        //this means the url already had https (its
-                        //already its full path), nothing more to do (i think)
-                        continue;
-                    }
+        match skip::fetch_skip_times(anime.id, episode) {
+            Ok(times) => skip::chapter_args(anime.id, episode, &times),
+            Err(e) => {
+                eprintln!("{}", e);
+                Vec::new()
+            }
+        }
+    }
 
-                    let headers = params![
-                        "User-Agent" => UA,
-                        "Referer" => REF,
-                    ];
+    // the providers in the order they should be tried, the one the user mapped the anime on goes first
+    fn ordered_providers(&self, anime: &Anime) -> Vec<&dyn StreamProvider> {
+        let mapped = SourceMappings::load()
+            .get(anime.id)
+            .map(|mapping| mapping.provider.clone());
 
-                    let url = source.source_url.clone();
+        let mut providers: Vec<&dyn StreamProvider> =
+            self.providers.iter().map(|p| p.as_ref()).collect();
+        providers.sort_by_key(|p| Some(p.name()) != mapped.as_deref());
+        providers
+    }
 
-                    if let Ok(link_details) =
-                        send_request::<LinksSearch>("GET", url, params![], headers, None)
-                    {
-                        source.extra_values = link_details.links.into_iter().next();
-                    }
+    // runs the attempt on each provider until one works
+    // an unsure match is only returned when none of them found the episode
+    fn try_providers<T>(
+        &self,
+        anime: &Anime,
+        mut attempt: impl FnMut(&dyn StreamProvider) -> Result<T, PlayError>,
+    ) -> Result<T, PlayError> {
+        let mut ambiguous = None;
+        let mut last_error = PlayError::Other("No stream providers configured".to_string());
+
+        for provider in self.ordered_providers(anime) {
+            match attempt(provider) {
+                Ok(value) => return Ok(value),
+                Err(PlayError::AmbiguousMatch(candidates)) => {
+                    ambiguous.get_or_insert(candidates);
                 }
-
-                Ok(response.data.episode.source_urls)
-            }
-            Err(e) => {
-                Err(PlayError::Other(format!("Error fetching episodes: {}", e)))
+                Err(e) => last_error = e,
             }
         }
+
+        Err(ambiguous.map(PlayError::AmbiguousMatch).unwrap_or(last_error))
     }
 
-    fn decode_clock(enc: &str) -> Result<(String, bool), String> {
-        let bytes = enc.trim_start_matches("--");
-        if bytes.len() % 2 != 0 {
-            return Err("odd-length clock encoding".into());
-        }
-
-        let mut out = String::with_capacity(bytes.len() / 2);
-        for i in (0..bytes.len()).step_by(2) {
-            let key = &bytes[i..i + 2].to_ascii_lowercase();
-            let ch = match key.as_str() {
-                "79" => "A",
-                "7a" => "B",
-                "7b" => "C",
-                "7c" => "D",
-                "7d" => "E",
-                "7e" => "F",
-                "7f" => "G",
-                "70" => "H",
-                "71" => "I",
-                "72" => "J",
-                "73" => "K",
-                "74" => "L",
-                "75" => "M",
-                "76" => "N",
-                "77" => "O",
-                "68" => "P",
-                "69" => "Q",
-                "6a" => "R",
-                "6b" => "S",
-                "6c" => "T",
-                "6d" => "U",
-                "6e" => "V",
-                "6f" => "W",
-                "60" => "X",
-                "61" => "Y",
-                "62" => "Z",
-                "59" => "a",
-                "5a" => "b",
-                "5b" => "c",
-                "5c" => "d",
-                "5d" => "e",
-                "5e" => "f",
-                "5f" => "g",
-                "50" => "h",
-                "51" => "i",
-                "52" => "j",
-                "53" => "k",
-                "54" => "l",
-                "55" => "m",
-                "56" => "n",
-                "57" => "o",
-                "48" => "p",
-                "49" => "q",
-                "4a" => "r",
-                "4b" => "s",
-                "4c" => "t",
-                "4d" => "u",
-                "4e" => "v",
-                "4f" => "w",
-                "40" => "x",
-                "41" => "y",
-                "42" => "z",
-                "08" => "0",
-                "09" => "1",
-                "0a" => "2",
-                "0b" => "3",
-                "0c" => "4",
-                "0d" => "5",
-                "0e" => "6",
-                "0f" => "7",
-                "00" => "8",
-                "01" => "9",
-                "15" => "-",
-                "16" => ".",
-                "67" => "_",
-                "46" => "~",
-                "02" => ":",
-                "17" => "/",
-                "07" => "?",
-                "1b" => "#",
-                "63" => "[",
-                "65" => "]",
-                "78" => "@",
-                "19" => "!",
-                "1c" => "$",
-                "1e" => "&",
-                "10" => "(",
-                "11" => ")",
-                "12" => "*",
-                "13" => "+",
-                "14" => ",",
-                "03" => ";",
-                "05" => "=",
-                "1d" => "%",
-                _ => return Err(format!("unknown code {key}")),
-            };
-            out.push_str(ch);
+    // the best stream for an episode and the show it was found on
+    pub fn resolve_stream(
+        &self,
+        anime: &Anime,
+        episode: &str,
+    ) -> Result<(SourceMapping, Stream), PlayError> {
+        self.try_providers(anime, |provider| {
+            let show = self.resolve_show(provider, anime)?;
+            let translation = provider.translation();
+            if let Some(stream) = cache::get_stream(anime.id, provider.name(), &show.show_id, episode, translation) {
+                return Ok((show, stream));
+            }
+
+            let stream = provider.stream(&show.show_id, episode)?;
+            cache::set_stream(anime.id, provider.name(), &show.show_id, episode, translation, &stream);
+            Ok((show, stream))
+        })
+    }
+
+    // resolves the episode after this one in the background so playing it starts right away
+    pub fn prefetch_next(anime: &Anime, episode: &str) {
+        if !Config::global().player.prefetch_next {
+            return;
+        }
+
+        // specials dont have a next episode
+        let Ok(next) = episode.parse::<u32>().map(|e| e + 1) else {
+            return;
+        };
+        let last = anime.num_released_episodes.unwrap_or(anime.num_episodes);
+        if last != 0 && next > last {
+            return;
         }
 
-        if out.ends_with("/clock") {
-            out.push_str(".json");
+        let next = next.to_string();
+        if local_episode(anime, &next).is_some() {
+            return;
         }
-        // replace all occurrences
-        else if !out.ends_with("/clock.json") {
-            out = out.replace("/clock", "/clock.json");
+
+        let anime = anime.clone();
+        std::thread::spawn(move || {
+            AnimePlayer::new().resolve_stream(&anime, &next).ok();
+        });
+    }
+
+    // the show on the provider, the mapping picked by the user or otherwise the best search result
+    fn resolve_show(
+        &self,
+        provider: &dyn StreamProvider,
+        anime: &Anime,
+    ) -> Result<SourceMapping, PlayError> {
+        if let Some(mapping) = SourceMappings::load().get(anime.id)
+            && mapping.provider == provider.name()
+        {
+            return Ok(mapping.clone());
         }
 
-        // return the url if it already includes the host
-        if out.starts_with("https://") || out.starts_with("http://") {
-            return Ok((out, false));
+        if let Some(show) = cache::get_show(provider.name(), anime.id) {
+            return Ok(show);
         }
 
-        Ok((BASE.to_string() + &out, true))
+        let shows = self.search_shows(provider, anime)?;
+        let show = self.extract_correct_id(provider, &shows, anime)?;
+        cache::set_show(anime.id, &show);
+        Ok(show)
     }
 
-    fn extract_best_candidate(
+    // searches by the main title first and falls back to the english one
+    fn search_shows(
         &self,
-        sources: &[SourceUrl],
-    ) -> Result<(String, Option<String>), PlayError> {
-        let mut variants: Vec<(i32, String, Option<String>, i32)> = Vec::new();
-
-        for source in sources {
-            let Some(link) = source
-                .extra_values
-                .as_ref()
-                .map(|l| l.link.as_str())
-                .filter(|s| !s.is_empty())
-            else {
-                variants.push((0, source.source_url.clone(), None, 0));
-                continue;
-            };
+        provider: &dyn StreamProvider,
+        anime: &Anime,
+    ) -> Result<Vec<SourceShow>, PlayError> {
+        let shows = match provider.search(&anime.title) {
+            Ok(shows) if !shows.is_empty() => return Ok(shows),
+            Ok(_) => Err(PlayError::NoResults("No shows found".to_string())),
+            Err(e) => Err(e),
+        };
 
-            if let Some(values) = self.convert_wixmp(link) {
-                for (qlt, url) in values {
-                    variants.push((qlt, url, None, 2))
-                }
-                continue;
+        let en = &anime.alternative_titles.en;
+        match shows {
+            Err(PlayError::NoResults(msg))
+                if en.is_empty() || en == "N/A" || en.eq_ignore_ascii_case(&anime.title) =>
+            {
+                Err(PlayError::NoResults(msg))
             }
-
-            if let Some(values) = self.parse_master_m3u8(link) {
-                for (qlt, url) in values {
-                    variants.push((qlt, url, Some(REF.to_string()), 3))
+            Err(PlayError::NoResults(_)) => match provider.search(en) {
+                Ok(shows) if shows.is_empty() => {
+                    Err(PlayError::NoResults("No shows found".to_string()))
                 }
-                continue;
-            }
-
-            //anything else (like sharepoint?)
-            variants.push((1, link.to_string(), None, 0));
+                result => result,
+            },
+            result => result,
         }
+    }
+
+    // all shows the providers have for the anime, best match first
+    pub fn search_candidates(&self, anime: &Anime) -> Result<Vec<ShowCandidate>, PlayError> {
+        let mut candidates = Vec::new();
+        let mut last_error = PlayError::NoResults("No shows found".to_string());
 
-        if variants.is_empty() {
-            return Err(PlayError::NoResults("No playable sources".to_string()));
+        for provider in self.ordered_providers(anime) {
+            match self.search_shows(provider, anime) {
+                Ok(shows) => candidates.extend(Self::rank_shows(provider, &shows, anime)),
+                Err(e) => last_error = e,
+            }
         }
 
-        // sort: by height desc then by kind weight (HLS > MP4 > Other)
-        variants.sort_by(|a, b| (b.0, b.3).cmp(&(a.0, a.3)));
+        if candidates.is_empty() {
+            return Err(last_error);
+        }
 
-        let (_, url, referer, _k) = variants.remove(0);
-        Ok((url, referer))
+        candidates.sort_by(Self::compare_candidates);
+        Ok(candidates)
     }
 
-    /// https://repackager.wixmp.com/video.wixstatic.com/video/<id>/,1080p,720p,480p,/mp4/file.mp4.urlset/master.m3u8
-    fn convert_wixmp(&self, url: &str) -> Option<Vec<(i32, String)>> {
-        if !url.contains("repackager.wixmp.com") {
-            return None;
-        }
-
-        let base = url
-            .trim_start_matches("https://repackager.wixmp.com/")
-            .trim_end_matches(".urlset/master.m3u8");
+    // finds the correct show from the list of shows
+    fn extract_correct_id(
+        &self,
+        provider: &dyn StreamProvider,
+        shows: &[SourceShow],
+        anime: &Anime,
+    ) -> Result<SourceMapping, PlayError> {
+        let candidates = Self::rank_shows(provider, shows, anime);
 
-        // capture the comma quality list between ".../<id>/" and "/mp4/"
-        let caps = self.wixmp_regex.captures(base)?;
-        let id = caps.get(1)?.as_str();
-        let quality_list = caps.get(2)?.as_str();
+        let best = candidates.first().ok_or(PlayError::NoResults(
+            "No shows found".to_string(),
+        ))?;
 
-        let qualities: Vec<&str> = quality_list.split(',').collect();
-        if qualities.is_empty() {
-            return None;
+        let runner_up = candidates.get(1).map(|c| c.score).unwrap_or(0.0);
+        if best.score < MATCH_CONFIDENCE || best.score - runner_up < MATCH_MARGIN {
+            return Err(PlayError::AmbiguousMatch(candidates));
         }
 
-        // replace each ",<something>" segment with the chosen quality
-        let mut out = Vec::new();
-        for q in qualities {
-            let h = q.trim_end_matches('p').parse::<i32>().unwrap_or(0);
-            let u = format!(
-                "https://video.wixstatic.com/video/{}/{}/mp4/file.mp4",
-                id, q
-            );
-            out.push((h, u));
-        }
+        Ok(SourceMapping {
+            provider: best.provider.clone(),
+            show_id: best.id.clone(),
+            name: best.name.clone(),
+        })
+    }
 
-        out.sort_by(|a, b| b.0.cmp(&a.0));
-        Some(out)
+    fn rank_shows(
+        provider: &dyn StreamProvider,
+        shows: &[SourceShow],
+        anime: &Anime,
+    ) -> Vec<ShowCandidate> {
+        let mut candidates: Vec<ShowCandidate> = shows
+            .iter()
+            .map(|show| ShowCandidate {
+                provider: provider.name().to_string(),
+                id: show.id.clone(),
+                name: show.name.clone(),
+                english_name: show.english_name.clone(),
+                year: show.year,
+                sub_episodes: show.sub_episodes,
+                dub_episodes: show.dub_episodes,
+                score: Self::score_show(show, anime),
+            })
+            .collect();
+
+        candidates.sort_by(Self::compare_candidates);
+        candidates
     }
 
-    fn parse_master_m3u8(&self, url: &str) -> Option<Vec<(i32, String)>> {
-        if !url.ends_with("master.m3u8") {
-            return None;
-        }
+    // highest score first, the one with the most episodes on ties
+    fn compare_candidates(a: &ShowCandidate, b: &ShowCandidate) -> std::cmp::Ordering {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then((b.sub_episodes + b.dub_episodes).cmp(&(a.sub_episodes + a.dub_episodes)))
+    }
 
-        let parameters = params![];
-        let headers = params![
-            "User-Agent" => UA,
-            "Referer" => REF,
-        ];
-        let body: Option<&str> = None;
-        let text =
-            send_request_expect_text("GET", url.to_string(), parameters, headers, body).ok()?;
+    // 0.0 - 1.0, mostly decided by the titles, the year and episode count settle seasons and recaps
+    fn score_show(show: &SourceShow, anime: &Anime) -> f32 {
+        let show_titles: Vec<&str> = std::iter::once(show.name.as_str())
+            .chain(show.english_name.as_deref())
+            .collect();
+
+        let title_score = Self::title_score(anime, &show_titles);
+
+        let year_score = match (show.year, anime.start_season.year) {
+            (Some(year), mal_year) if mal_year != 0 => match year.abs_diff(mal_year) {
+                0 => 1.0,
+                1 => 0.5,
+                _ => 0.0,
+            },
+            // unknown either way, dont punish it
+            _ => 0.5,
+        };
 
-        if !text.contains("#EXTM3U") {
-            return None;
-        }
+        let show_episodes = show
+            .episode_count
+            .filter(|e| *e > 0)
+            .unwrap_or(show.sub_episodes.max(show.dub_episodes));
+        let episode_score = if anime.num_episodes == 0 || show_episodes == 0 {
+            0.5
+        } else {
+            let diff = show_episodes.abs_diff(anime.num_episodes) as f32;
+            1.0 - diff / show_episodes.max(anime.num_episodes) as f32
+        };
 
-        let base = Url::parse(url).ok()?;
-        let mut out: Vec<(i32, String)> = Vec::new();
-        let mut pending_height = 0i32;
-        let mut want_url_next = false;
+        0.7 * title_score + 0.15 * year_score + 0.15 * episode_score
+    }
 
-        for raw in text.lines() {
-            let line = raw.trim();
+    // the best similarity between any of the mal titles and any of the given titles
+    fn title_score(anime: &Anime, titles: &[&str]) -> f32 {
+        std::iter::once(&anime.title)
+            .chain(std::iter::once(&anime.alternative_titles.en))
+            .chain(anime.alternative_titles.synonyms.iter())
+            .filter(|t| !t.is_empty() && t.as_str() != "N/A")
+            .flat_map(|mal| titles.iter().map(move |t| title_similarity(mal, t)))
+            .fold(0.0, f32::max)
+    }
 
-            if line.starts_with("#EXT-X-I-FRAME-STREAM-INF") {
-                want_url_next = false;
-                pending_height = 0;
-                continue;
-            }
+    // every episode the first working provider has for the anime, in order
+    pub fn get_episode_list(&self, anime: &Anime) -> Result<Vec<EpisodeEntry>, PlayError> {
+        self.try_providers(anime, |provider| {
+            let show = self.resolve_show(provider, anime)?;
+            let mut episodes = provider.episodes(&show.show_id)?;
 
-            if line.starts_with("#EXT-X-STREAM-INF") {
-                pending_height = self.parse_height_from_inf(line);
-                want_url_next = true;
-                continue;
+            if episodes.is_empty() {
+                return Err(PlayError::NoResults("No episodes found".to_string()));
             }
 
-            if want_url_next && !line.is_empty() && !line.starts_with('#') {
-                // resolve relative → absolute
-                let abs = if let Ok(u) = Url::parse(line) {
-                    u
-                } else if let Ok(u) = base.join(line) {
-                    u
-                } else {
-                    want_url_next = false;
-                    pending_height = 0;
-                    continue;
-                };
-                out.push((pending_height, abs.to_string()));
-                want_url_next = false;
-                pending_height = 0;
+            episodes.sort_by(|a, b| {
+                a.number()
+                    .partial_cmp(&b.number())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            Ok(episodes)
+        })
+    }
+
+    // the headers and subtitles of a stream as mpv options
+    fn stream_args(stream: &Stream) -> Vec<String> {
+        let mut args = Vec::new();
+        for (key, value) in &stream.headers {
+            if key.eq_ignore_ascii_case("Referer") {
+                args.push(format!("--referrer={}", value));
+            } else if key.eq_ignore_ascii_case("User-Agent") {
+                args.push(format!("--user-agent={}", value));
+            } else {
+                args.push(format!("--http-header-fields-append={}: {}", key, value));
             }
         }
 
-        if out.is_empty() {
-            return None;
+        for subtitle in &stream.subtitles {
+            args.push(format!("--sub-file={}", subtitle.url));
         }
 
-        // sort highest first
-        out.sort_by(|a, b| b.0.cmp(&a.0));
-        Some(out)
-    }
-
-    fn parse_height_from_inf(&self, inf_line: &str) -> i32 {
-        // parse RESOLUTION=WxH (case-insensitive) and return H
-        let lower = inf_line.to_ascii_lowercase();
-        if let Some(pos) = lower.find("resolution=") {
-            let after = &lower[pos + "resolution=".len()..];
-            let mut w = String::new();
-            let mut h = String::new();
-            let mut seen_x = false;
-            for ch in after.chars() {
-                if ch == ',' || ch == ' ' {
-                    break;
-                }
-                if ch == 'x' {
-                    seen_x = true;
-                    continue;
-                }
-                if ch.is_ascii_digit() {
-                    if !seen_x {
-                        w.push(ch);
-                    } else {
-                        h.push(ch);
-                    }
-                } else {
-                    break;
-                }
-            }
-            if let Ok(n) = h.parse::<i32>() {
-                return n;
-            }
+        if let Some(language) = &Config::global().player.subtitle_language {
+            args.push(format!("--slang={}", language));
         }
-        0
+        args
     }
 
-    fn play_video_in_mpv(&self, info: &(String, Option<String>)) -> Result<String, PlayError> {
+    fn play_video_in_mpv(&self, stream: &Stream, extra_args: &[String]) -> Result<String, PlayError> {
         let mut cmd = Command::new("mpv");
+        cmd.arg(ipc::server_arg());
+        cmd.args(extra_args);
 
-        if let Some(referer) = &info.1 {
-            cmd.arg(format!("--referrer={}", referer));
-        }
+        cmd.args(Self::stream_args(stream));
 
         let output = cmd
-            .arg(&info.0)
+            .arg(&stream.url)
             .output()
             .map_err(|e| {
                 if e.kind() == ErrorKind::NotFound {
@@ -701,39 +890,119 @@ impl AnimePlayer {
 
         Ok(stdout)
     }
+}
 
-    fn run_command(
-        &self,
-        command: &str,
-        anime: &Anime,
-        episode: u32,
-        url: Option<&(String, Option<String>)>,
-    ) -> Result<(), String> {
-        let cmd = command 
-            .replace("{title}", &escape(anime.title.clone().into()))
-            .replace("{episode}", &escape(episode.to_string().into()))
-            .replace( "{url}", &escape(url.map(|u| u.0.as_str()).unwrap_or_default().into()))
-            .replace( "{referer}", &escape(url.and_then(|u| u.1.as_deref()).unwrap_or("").into()))
-            .replace( "{referrer}", &escape(url.and_then(|u| u.1.as_deref()).unwrap_or("").into()));
-
-        #[cfg(unix)]
-        let status = Command::new("sh")
-            .arg("-c")
-            .arg(&cmd)
-            .status()
-            .map_err(|e| format!("Failed to run hook: {}", e))?;
-
-        #[cfg(windows)]
-        let status = Command::new("cmd")
-            .arg("/C")
-            .arg(&cmd)
-            .status()
-            .map_err(|e| format!("Failed to run hook: {}", e))?;
-
-        if !status.success() {
-            return Err(format!("Hook exited with status: {:?}", status.code()));
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_subtitles_of_the_video_only() {
+        let dir = std::env::temp_dir().join(format!("mal-cli-subtitles-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for file in [
+            "episode_12.mp4",
+            "episode_12.srt",
+            "episode_12.en.ass",
+            "episode_12.pt-BR.vtt",
+            "episode_12.5.en.vtt",
+            "episode_12.notes.srt",
+            "episode_120.srt",
+        ] {
+            std::fs::write(dir.join(file), "").unwrap();
         }
 
-        Ok(())
+        let subtitles = local_subtitles(&dir.join("episode_12.mp4"));
+        std::fs::remove_dir_all(&dir).ok();
+
+        let found: Vec<(String, Option<String>)> = subtitles
+            .into_iter()
+            .map(|subtitle| {
+                let name = Path::new(&subtitle.url).file_name().unwrap().to_string_lossy().to_string();
+                (name, subtitle.language)
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("episode_12.en.ass".to_string(), Some("en".to_string())),
+                ("episode_12.pt-BR.vtt".to_string(), Some("pt-BR".to_string())),
+                ("episode_12.srt".to_string(), None),
+            ]
+        );
+    }
+
+    // only named, the shows are handed to rank_shows directly
+    struct NamedProvider;
+
+    impl StreamProvider for NamedProvider {
+        fn name(&self) -> &'static str {
+            "named"
+        }
+
+        fn search(&self, _query: &str) -> Result<Vec<SourceShow>, PlayError> {
+            Err(PlayError::NoResults("not searched".to_string()))
+        }
+
+        fn episodes(&self, _show_id: &str) -> Result<Vec<EpisodeEntry>, PlayError> {
+            Err(PlayError::NoResults("not searched".to_string()))
+        }
+
+        fn stream(&self, _show_id: &str, _episode: &str) -> Result<Stream, PlayError> {
+            Err(PlayError::NoResults("not searched".to_string()))
+        }
+    }
+
+    fn anime() -> Anime {
+        let mut anime = Anime::empty();
+        anime.title = "Shingeki no Kyojin".to_string();
+        anime.alternative_titles.en = "Attack on Titan".to_string();
+        anime.start_season.year = 2013;
+        anime.num_episodes = 25;
+        anime
+    }
+
+    fn show(id: &str, name: &str, english_name: Option<&str>, year: u16, episodes: u32) -> SourceShow {
+        SourceShow {
+            id: id.to_string(),
+            name: name.to_string(),
+            english_name: english_name.map(str::to_string),
+            year: Some(year),
+            sub_episodes: episodes,
+            dub_episodes: 0,
+            episode_count: None,
+        }
+    }
+
+    #[test]
+    fn scores_the_same_show_fully() {
+        let score = AnimePlayer::score_show(&show("1", "Shingeki no Kyojin", None, 2013, 25), &anime());
+        assert!((score - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn year_and_episodes_tell_seasons_apart() {
+        let first = AnimePlayer::score_show(&show("1", "Shingeki no Kyojin", None, 2013, 25), &anime());
+        let later = AnimePlayer::score_show(&show("2", "Shingeki no Kyojin", None, 2017, 12), &anime());
+        assert!(later < first);
+        // the title alone still decides most of it
+        assert!(later >= 0.7);
+    }
+
+    #[test]
+    fn ranks_by_score_then_episode_count() {
+        let mut shows = vec![
+            show("movie", "Shingeki no Kyojin Movie", None, 2015, 1),
+            show("english", "Something Else", Some("Attack on Titan"), 2013, 25),
+            show("sub", "Shingeki no Kyojin", None, 2013, 24),
+            show("dub", "Shingeki no Kyojin", None, 2013, 24),
+        ];
+        // scored the same as "sub", more episodes in total
+        shows[3].dub_episodes = 24;
+
+        let ranked = AnimePlayer::rank_shows(&NamedProvider, &shows, &anime());
+        let ids: Vec<&str> = ranked.iter().map(|candidate| candidate.id.as_str()).collect();
+        assert_eq!(ids, ["english", "dub", "sub", "movie"]);
+        assert!(ranked.iter().all(|candidate| candidate.provider == "named"));
     }
 }
@@ -0,0 +1,347 @@
+use super::ipc;
+use crate::config::Config;
+use crate::mal::models::anime::{Anime, AnimeId};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use zbus::blocking::Connection;
+use zbus::blocking::connection::Builder;
+use zbus::interface;
+use zbus::zvariant::{ObjectPath, OwnedValue, Value};
+
+const MPRIS_PATH: &str = "/org/mpris/MediaPlayer2";
+const PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+
+// set when "next" was pressed, the app plays the next episode once mpv closes
+static NEXT_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+// true once after "next" was pressed during the last playback
+pub fn take_next_requested() -> bool {
+    NEXT_REQUESTED.swap(false, Ordering::SeqCst)
+}
+
+// the same without resetting it, for a playlist that still has to report its last entry
+pub fn next_requested() -> bool {
+    NEXT_REQUESTED.load(Ordering::SeqCst)
+}
+
+// the episode being played
+#[derive(Debug, Clone)]
+struct Track {
+    anime_id: AnimeId,
+    title: String,
+    episode: String,
+    art_url: Option<String>,
+}
+
+impl Track {
+    fn metadata(&self) -> HashMap<String, OwnedValue> {
+        let mut metadata = HashMap::new();
+        let mut insert = |key: &str, value: Value| {
+            if let Ok(value) = OwnedValue::try_from(value) {
+                metadata.insert(key.to_string(), value);
+            }
+        };
+
+        // object paths only allow [A-Za-z0-9_], "12.5" turns into "12_5"
+        let track_id = format!(
+            "/org/mal_cli/track/{}_{}",
+            self.anime_id,
+            self.episode.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+        );
+        if let Ok(path) = ObjectPath::try_from(track_id) {
+            insert("mpris:trackid", Value::from(path));
+        }
+        insert("xesam:title", Value::from(format!("Episode {}", self.episode)));
+        insert("xesam:album", Value::from(self.title.clone()));
+        insert("xesam:artist", Value::from(vec![self.title.clone()]));
+        if let Ok(number) = self.episode.parse::<i32>() {
+            insert("xesam:trackNumber", Value::from(number));
+        }
+        if let Some(art_url) = &self.art_url {
+            insert("mpris:artUrl", Value::from(art_url.clone()));
+        }
+        if let Some(duration) = mpv_seconds("duration") {
+            insert("mpris:length", Value::from((duration * 1_000_000.0) as i64));
+        }
+        metadata
+    }
+}
+
+// a number property of the running mpv
+fn mpv_seconds(property: &str) -> Option<f64> {
+    ipc::request(json!(["get_property", property]))?.as_f64()
+}
+
+fn mpv_command(command: serde_json::Value) {
+    ipc::request(command);
+}
+
+// org.mpris.MediaPlayer2, mal-cli can't be raised or closed from outside
+struct Root;
+
+#[interface(name = "org.mpris.MediaPlayer2")]
+impl Root {
+    fn raise(&self) {}
+
+    fn quit(&self) {}
+
+    #[zbus(property)]
+    fn can_quit(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn can_raise(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn has_track_list(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn identity(&self) -> String {
+        "mal-cli".to_string()
+    }
+
+    #[zbus(property)]
+    fn supported_uri_schemes(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    #[zbus(property)]
+    fn supported_mime_types(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+// org.mpris.MediaPlayer2.Player, controls are forwarded to mpv
+struct Player {
+    track: Arc<Mutex<Track>>,
+}
+
+#[interface(name = "org.mpris.MediaPlayer2.Player")]
+impl Player {
+    fn next(&self) {
+        NEXT_REQUESTED.store(true, Ordering::SeqCst);
+
+        // inside a playlist mpv moves on by itself, otherwise the app starts the next episode
+        let position = mpv_seconds("playlist-pos").unwrap_or(0.0);
+        let count = mpv_seconds("playlist-count").unwrap_or(1.0);
+        if position + 1.0 < count {
+            mpv_command(json!(["playlist-next"]));
+        } else {
+            mpv_command(json!(["quit"]));
+        }
+    }
+
+    fn previous(&self) {}
+
+    fn pause(&self) {
+        mpv_command(json!(["set_property", "pause", true]));
+    }
+
+    fn play_pause(&self) {
+        mpv_command(json!(["cycle", "pause"]));
+    }
+
+    fn stop(&self) {
+        mpv_command(json!(["quit"]));
+    }
+
+    fn play(&self) {
+        mpv_command(json!(["set_property", "pause", false]));
+    }
+
+    // offset in microseconds
+    fn seek(&self, offset: i64) {
+        mpv_command(json!(["seek", offset as f64 / 1_000_000.0, "relative"]));
+    }
+
+    fn set_position(&self, _track_id: ObjectPath<'_>, position: i64) {
+        mpv_command(json!(["seek", position as f64 / 1_000_000.0, "absolute"]));
+    }
+
+    fn open_uri(&self, _uri: String) {}
+
+    #[zbus(property)]
+    fn playback_status(&self) -> String {
+        match ipc::request(json!(["get_property", "pause"])) {
+            Some(serde_json::Value::Bool(true)) => "Paused",
+            Some(_) => "Playing",
+            None => "Stopped",
+        }
+        .to_string()
+    }
+
+    #[zbus(property)]
+    fn metadata(&self) -> HashMap<String, OwnedValue> {
+        self.track.lock().unwrap().metadata()
+    }
+
+    // in microseconds
+    #[zbus(property)]
+    fn position(&self) -> i64 {
+        mpv_seconds("time-pos").map_or(0, |time| (time * 1_000_000.0) as i64)
+    }
+
+    #[zbus(property)]
+    fn rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn minimum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn maximum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn can_go_next(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_go_previous(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn can_play(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_pause(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_seek(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_control(&self) -> bool {
+        true
+    }
+}
+
+// the published player, it disappears from the bus when dropped
+pub struct Session {
+    connection: Connection,
+    track: Arc<Mutex<Track>>,
+}
+
+impl Session {
+    // moves on to another episode of the same anime, eg. in a playlist
+    pub fn set_episode(&self, episode: &str) {
+        let metadata = {
+            let mut track = self.track.lock().unwrap();
+            if track.episode == episode {
+                return;
+            }
+            track.episode = episode.to_string();
+            track.metadata()
+        };
+
+        let mut changed: HashMap<&str, Value> = HashMap::new();
+        changed.insert("Metadata", Value::from(metadata));
+        self.connection
+            .emit_signal(
+                None::<&str>,
+                MPRIS_PATH,
+                "org.freedesktop.DBus.Properties",
+                "PropertiesChanged",
+                &(PLAYER_INTERFACE, changed, Vec::<&str>::new()),
+            )
+            .ok();
+    }
+}
+
+// publishes the episode on the session bus, none when publishing is off or there is no bus
+pub fn publish(anime: &Anime, episode: &str) -> Option<Session> {
+    if !Config::global().mpris.publish {
+        return None;
+    }
+    NEXT_REQUESTED.store(false, Ordering::SeqCst);
+
+    let art_url = [&anime.main_picture.large, &anime.main_picture.medium]
+        .into_iter()
+        .find(|url| url.starts_with("http"))
+        .cloned();
+    let track = Arc::new(Mutex::new(Track {
+        anime_id: anime.id,
+        title: anime.title.clone(),
+        episode: episode.to_string(),
+        art_url,
+    }));
+
+    // the instance suffix keeps several mal-clis apart
+    let name = format!("org.mpris.MediaPlayer2.mal_cli.instance{}", std::process::id());
+    let connection = Builder::session()
+        .ok()?
+        .name(name)
+        .ok()?
+        .serve_at(MPRIS_PATH, Root)
+        .ok()?
+        .serve_at(
+            MPRIS_PATH,
+            Player {
+                track: track.clone(),
+            },
+        )
+        .ok()?
+        .build()
+        .ok()?;
+
+    Some(Session { connection, track })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zbus::blocking::Proxy;
+
+    // what another app on the bus sees of the published player
+    fn published_metadata(connection: &Connection) -> HashMap<String, OwnedValue> {
+        let name = format!("org.mpris.MediaPlayer2.mal_cli.instance{}", std::process::id());
+        Proxy::new(connection, name, MPRIS_PATH, PLAYER_INTERFACE)
+            .unwrap()
+            .get_property("Metadata")
+            .unwrap()
+    }
+
+    fn text(metadata: &HashMap<String, OwnedValue>, key: &str) -> String {
+        String::try_from(metadata[key].try_clone().unwrap()).unwrap()
+    }
+
+    #[test]
+    #[ignore = "needs a session bus, run with dbus-run-session -- cargo test -- --ignored"]
+    fn publishes_the_playing_episode() {
+        Config::init_defaults();
+        let anime: Anime = serde_json::from_value(json!({ "id": 21, "title": "Some Show" })).unwrap();
+        let session = publish(&anime, "12.5").unwrap();
+        let connection = Connection::session().unwrap();
+
+        let metadata = published_metadata(&connection);
+        assert_eq!(text(&metadata, "xesam:title"), "Episode 12.5");
+        assert_eq!(text(&metadata, "xesam:album"), "Some Show");
+        assert_eq!(
+            ObjectPath::try_from(metadata["mpris:trackid"].try_clone().unwrap()).unwrap().as_str(),
+            "/org/mal_cli/track/21_12_5"
+        );
+
+        session.set_episode("13");
+        let metadata = published_metadata(&connection);
+        assert_eq!(text(&metadata, "xesam:title"), "Episode 13");
+        assert_eq!(i32::try_from(metadata["xesam:trackNumber"].try_clone().unwrap()).unwrap(), 13);
+    }
+}
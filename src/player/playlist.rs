@@ -0,0 +1,159 @@
+use super::mpris::{self, Session};
+use super::{PlayError, PlayResult, PlaybackPosition, ipc};
+use serde::Deserialize;
+use std::io::ErrorKind;
+use std::process::{Child, Command};
+use std::time::Duration;
+
+// how long mpv gets to open its ipc socket
+const CONNECT_ATTEMPTS: u32 = 50;
+const CONNECT_INTERVAL: Duration = Duration::from_millis(100);
+
+// the properties watched over ipc, the number is the observe id
+const OBSERVED: [(u64, &str); 3] = [(1, "time-pos"), (2, "duration"), (3, "playlist-pos")];
+
+// { event: "property-change", id, name, data } or { event: "end-file", reason, playlist_entry_id }
+#[derive(Debug, Deserialize)]
+struct IpcMessage {
+    event: Option<String>,
+    name: Option<String>,
+    data: Option<serde_json::Value>,
+    reason: Option<String>,
+    // starts at 1 in the order the files were given
+    playlist_entry_id: Option<usize>,
+}
+
+// runs mpv with the playlist args and reports every entry it finishes or skips past
+pub fn play(
+    args: &[String],
+    episodes: &[String],
+    session: Option<&Session>,
+    mut on_finished: impl FnMut(PlayResult),
+) -> Result<(), PlayError> {
+    let socket = ipc::socket_path();
+    std::fs::remove_file(&socket).ok();
+
+    let mut child = Command::new("mpv")
+        .arg(ipc::server_arg())
+        .args(args)
+        .spawn()
+        .map_err(|e| {
+            if e.kind() == ErrorKind::NotFound {
+                PlayError::NotFound("mpv is not installed or not found in PATH".to_string())
+            } else {
+                PlayError::Other(format!("Error running mpv: \n{}", e))
+            }
+        })?;
+
+    // mpv exits with an error when some file failed, the ones that played are already reported
+    let tracked = track(&mut child, &socket, episodes, session, &mut on_finished);
+    child.wait().ok();
+    std::fs::remove_file(&socket).ok();
+
+    tracked
+}
+
+#[cfg(unix)]
+fn track(
+    child: &mut Child,
+    socket: &std::path::Path,
+    episodes: &[String],
+    session: Option<&Session>,
+    on_finished: &mut impl FnMut(PlayResult),
+) -> Result<(), PlayError> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = None;
+    for _ in 0..CONNECT_ATTEMPTS {
+        if let Ok(connected) = UnixStream::connect(socket) {
+            stream = Some(connected);
+            break;
+        }
+        // mpv already closed, eg. none of the urls could be opened
+        if child.try_wait().ok().flatten().is_some() {
+            return Ok(());
+        }
+        std::thread::sleep(CONNECT_INTERVAL);
+    }
+    let mut stream = stream.ok_or_else(|| {
+        PlayError::Other("Could not connect to mpv to follow the playlist".to_string())
+    })?;
+
+    for (id, property) in OBSERVED {
+        let command = serde_json::json!({ "command": ["observe_property", id, property] });
+        writeln!(stream, "{}", command)
+            .map_err(|e| PlayError::Other(format!("Could not talk to mpv: {}", e)))?;
+    }
+
+    let mut position = PlaybackPosition::default();
+    // the socket closes when mpv exits
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        let Ok(message) = serde_json::from_str::<IpcMessage>(&line) else {
+            continue;
+        };
+
+        match (message.event.as_deref(), message.name.as_deref()) {
+            // unset while switching files, keep the last known value
+            (Some("property-change"), Some("time-pos")) => {
+                if let Some(time) = message.data.and_then(|d| d.as_f64()) {
+                    position.time = time;
+                }
+            }
+            (Some("property-change"), Some("duration")) => {
+                if let Some(duration) = message.data.and_then(|d| d.as_f64()) {
+                    position.duration = duration;
+                }
+            }
+            // starts at 0, the published episode follows the playlist
+            (Some("property-change"), Some("playlist-pos")) => {
+                if let Some(session) = session
+                    && let Some(episode) = message
+                        .data
+                        .and_then(|d| d.as_u64())
+                        .and_then(|pos| episodes.get(pos as usize))
+                {
+                    session.set_episode(episode);
+                }
+            }
+            (Some("end-file"), _) => {
+                let reason = message.reason.as_deref().unwrap_or_default();
+                if let Some(episode) = message
+                    .playlist_entry_id
+                    .and_then(|id| episodes.get(id.checked_sub(1)?))
+                    && reason != "error"
+                {
+                    // skipping ahead with "next" counts as watching the episode, on the last entry it
+                    // closes mpv and the flag is left for the app to play the episode after it
+                    let skipped = match reason {
+                        "stop" => mpris::take_next_requested(),
+                        "quit" => mpris::next_requested(),
+                        _ => false,
+                    };
+                    on_finished(position.result(episode, reason == "eof" || skipped));
+                }
+                position.time = 0.0;
+                position.duration = 0.0;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn track(
+    _child: &mut Child,
+    _socket: &std::path::Path,
+    _episodes: &[String],
+    _session: Option<&Session>,
+    _on_finished: &mut impl FnMut(PlayResult),
+) -> Result<(), PlayError> {
+    Err(PlayError::Other(
+        "Following a playlist is only supported on unix".to_string(),
+    ))
+}
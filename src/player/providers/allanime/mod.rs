@@ -0,0 +1,574 @@
+mod models;
+
+use super::{SourceShow, Stream, StreamProvider, Subtitle};
+use crate::mal::network::{send_request, send_request_expect_text};
+use crate::params;
+use crate::player::{EpisodeEntry, PlayError};
+use models::{
+    AvailableEpisodesDetail, EpisodeSearch, InfoSearch, LinksSearch, ShowEdge, ShowSearch,
+    SourceUrl,
+};
+use regex::Regex;
+use serde_json::json;
+use url::Url;
+
+const BASE: &str = "https://allanime.day";
+const API: &str = "https://api.allanime.day/api";
+const UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:109.0) Gecko/20100101 Firefox/121.0";
+const REF: &str = "https://allmanga.to";
+// streams are fetched subbed, episodes that only have a dub fall back to it
+const TRANSLATION: &str = "sub";
+const DUB: &str = "dub";
+
+// one playable quality of a source
+struct Variant {
+    height: i32,
+    url: String,
+    referer: Option<String>,
+    // HLS > MP4 > Other
+    kind: i32,
+    subtitles: Vec<Subtitle>,
+}
+
+impl Variant {
+    fn new(height: i32, url: String, referer: Option<String>, kind: i32, subtitles: Vec<Subtitle>) -> Self {
+        Self {
+            height,
+            url,
+            referer,
+            kind,
+            subtitles,
+        }
+    }
+}
+
+pub struct AllAnime {
+    // url Regex:
+    wixmp_regex: Regex,
+}
+
+impl StreamProvider for AllAnime {
+    fn name(&self) -> &'static str {
+        "allanime"
+    }
+
+    fn search(&self, query: &str) -> Result<Vec<SourceShow>, PlayError> {
+        let shows = self.get_shows(query)?;
+        Ok(shows
+            .into_iter()
+            .map(|show| SourceShow {
+                episode_count: show.episode_count(),
+                year: show.season.as_ref().and_then(|s| s.year),
+                sub_episodes: show.available_episodes.sub,
+                dub_episodes: show.available_episodes.dub,
+                id: show.id,
+                name: show.name,
+                english_name: show.english_name,
+            })
+            .collect())
+    }
+
+    fn episodes(&self, show_id: &str) -> Result<Vec<EpisodeEntry>, PlayError> {
+        let details = self.get_episode_details(show_id)?;
+
+        let mut episodes: Vec<EpisodeEntry> = Vec::new();
+        for episode in details.sub.iter().chain(details.dub.iter()) {
+            if episodes.iter().any(|e| &e.episode == episode) {
+                continue;
+            }
+            episodes.push(EpisodeEntry {
+                episode: episode.clone(),
+                sub: details.sub.contains(episode),
+                dub: details.dub.contains(episode),
+            });
+        }
+        Ok(episodes)
+    }
+
+    fn stream(&self, show_id: &str, episode: &str) -> Result<Stream, PlayError> {
+        // get the available episodes for the show, the episode list also shows dub only episodes
+        let available_episodes = match self.get_episode_providers(show_id, episode, TRANSLATION) {
+            Ok(sources) => sources,
+            Err(e) => self
+                .get_episode_providers(show_id, episode, DUB)
+                .map_err(|_| e)?,
+        };
+
+        // extract the correct (the one with highest priority) episode from the list of available episodes
+        self.extract_best_candidate(&available_episodes)
+    }
+
+    fn translation(&self) -> &'static str {
+        TRANSLATION
+    }
+}
+
+impl AllAnime {
+    pub fn new() -> Self {
+        Self {
+            wixmp_regex: Regex::new(
+                r#"^video\.wixstatic\.com/video/([^/]+)/,([^/]+),/mp4/file\.mp4$"#,
+            )
+            .unwrap(),
+        }
+    }
+
+    // searches for shows with the given name and returns a list of ShowEdge
+    fn get_shows(&self, show: &str) -> Result<Vec<ShowEdge>, PlayError> {
+        let gql = r#"
+      query( $search: SearchInput, $limit: Int, $page: Int,
+             $translationType: VaildTranslationTypeEnumType,
+             $countryOrigin: VaildCountryOriginEnumType ) {
+        shows(
+          search: $search, limit: $limit, page: $page,
+          translationType: $translationType, countryOrigin: $countryOrigin
+        ) {
+          edges { _id name englishName availableEpisodes season episodeCount }
+        }
+      }"#;
+
+        let variables = json!({
+            "search": {"allowAdult": false, "allowUnknown": false, "query": show},
+            "limit": 40,
+            "page": 1,
+            "translationType": "sub",
+            "countryOrigin": "ALL"
+        })
+        .to_string();
+
+        let headers = params![
+            "User-Agent" => UA,
+            "Referer" => REF,
+        ];
+
+        let params = params![
+            "query" => gql,
+            "variables" => variables,
+        ];
+
+        let result = send_request::<ShowSearch>("GET", API.to_string(), params, headers, None);
+        match result {
+            Ok(response) => {
+                if response.data.shows.edges.is_empty() {
+                    return Err(PlayError::NoResults("No shows found".to_string()));
+                }
+                Ok(response.data.shows.edges)
+            }
+            Err(e) => {
+                Err(PlayError::Other(format!("Error fetching shows: {}", e)))
+            }
+        }
+    }
+
+    // the sub and dub episodes of a show
+    fn get_episode_details(&self, show_id: &str) -> Result<AvailableEpisodesDetail, PlayError> {
+        let gql = r#"
+        query($showId: String!) {
+            show(_id: $showId) {
+                _id availableEpisodesDetail
+            }
+        }
+      "#;
+
+        let variables = json!({ "showId": show_id }).to_string();
+
+        let headers = params![
+            "User-Agent" => UA,
+            "Referer" => REF,
+        ];
+
+        let params = params![
+            "query" => gql,
+            "variables" => variables,
+        ];
+
+        send_request::<InfoSearch>("GET", API.to_string(), params, headers, None)
+            .map(|response| response.data.show.available_episodes_detail)
+            .map_err(|e| PlayError::Other(format!("Error fetching episode list: {}", e)))
+    }
+
+    fn get_episode_providers(
+        &self,
+        show_id: &str,
+        episode: &str,
+        translation: &str,
+    ) -> Result<Vec<SourceUrl>, PlayError> {
+        let gql = r#"
+        query($showId: String!, $translationType: VaildTranslationTypeEnumType!, $episodeString: String!) {
+            episode(showId: $showId, translationType: $translationType, episodeString: $episodeString) {
+                episodeString sourceUrls
+            }
+        }
+      "#;
+
+        let variables = json!({
+            "showId": show_id,
+            "translationType": translation,
+            "episodeString": episode.to_string(),
+        })
+        .to_string();
+
+        let headers = params![
+            "User-Agent" => UA,
+            "Referer" => REF,
+        ];
+
+        let params = params![
+            "query" => gql,
+            "variables" => variables,
+        ];
+
+        let result = send_request::<EpisodeSearch>("GET", API.to_string(), params, headers, None);
+
+        match result {
+            Ok(mut response) => {
+                if response.data.episode.source_urls.is_empty() {
+                    return Err(PlayError::NoResults("No episodes found".to_string()));
+                }
+
+                for source in response.data.episode.source_urls.iter_mut() {
+                    if source.source_url.starts_with("http") {
+                        continue;
+                    }
+
+                    let (source_url, http_appended) = AllAnime::decode_clock(&source.source_url)
+                        .unwrap_or_else(|_| (source.source_url.clone(), false));
+
+                    source.source_url = source_url;
+
+                    if !http_appended {
+                        //this means the url already had https (its
+                        //already its full path), nothing more to do (i think)
+                        continue;
+                    }
+
+                    let headers = params![
+                        "User-Agent" => UA,
+                        "Referer" => REF,
+                    ];
+
+                    let url = source.source_url.clone();
+
+                    if let Ok(link_details) =
+                        send_request::<LinksSearch>("GET", url, params![], headers, None)
+                    {
+                        source.extra_values = link_details.links.into_iter().next();
+                    }
+                }
+
+                Ok(response.data.episode.source_urls)
+            }
+            Err(e) => {
+                Err(PlayError::Other(format!("Error fetching episodes: {}", e)))
+            }
+        }
+    }
+
+    fn decode_clock(enc: &str) -> Result<(String, bool), String> {
+        let bytes = enc.trim_start_matches("--");
+        if bytes.len() % 2 != 0 {
+            return Err("odd-length clock encoding".into());
+        }
+
+        let mut out = String::with_capacity(bytes.len() / 2);
+        for i in (0..bytes.len()).step_by(2) {
+            let key = &bytes[i..i + 2].to_ascii_lowercase();
+            let ch = match key.as_str() {
+                "79" => "A",
+                "7a" => "B",
+                "7b" => "C",
+                "7c" => "D",
+                "7d" => "E",
+                "7e" => "F",
+                "7f" => "G",
+                "70" => "H",
+                "71" => "I",
+                "72" => "J",
+                "73" => "K",
+                "74" => "L",
+                "75" => "M",
+                "76" => "N",
+                "77" => "O",
+                "68" => "P",
+                "69" => "Q",
+                "6a" => "R",
+                "6b" => "S",
+                "6c" => "T",
+                "6d" => "U",
+                "6e" => "V",
+                "6f" => "W",
+                "60" => "X",
+                "61" => "Y",
+                "62" => "Z",
+                "59" => "a",
+                "5a" => "b",
+                "5b" => "c",
+                "5c" => "d",
+                "5d" => "e",
+                "5e" => "f",
+                "5f" => "g",
+                "50" => "h",
+                "51" => "i",
+                "52" => "j",
+                "53" => "k",
+                "54" => "l",
+                "55" => "m",
+                "56" => "n",
+                "57" => "o",
+                "48" => "p",
+                "49" => "q",
+                "4a" => "r",
+                "4b" => "s",
+                "4c" => "t",
+                "4d" => "u",
+                "4e" => "v",
+                "4f" => "w",
+                "40" => "x",
+                "41" => "y",
+                "42" => "z",
+                "08" => "0",
+                "09" => "1",
+                "0a" => "2",
+                "0b" => "3",
+                "0c" => "4",
+                "0d" => "5",
+                "0e" => "6",
+                "0f" => "7",
+                "00" => "8",
+                "01" => "9",
+                "15" => "-",
+                "16" => ".",
+                "67" => "_",
+                "46" => "~",
+                "02" => ":",
+                "17" => "/",
+                "07" => "?",
+                "1b" => "#",
+                "63" => "[",
+                "65" => "]",
+                "78" => "@",
+                "19" => "!",
+                "1c" => "$",
+                "1e" => "&",
+                "10" => "(",
+                "11" => ")",
+                "12" => "*",
+                "13" => "+",
+                "14" => ",",
+                "03" => ";",
+                "05" => "=",
+                "1d" => "%",
+                _ => return Err(format!("unknown code {key}")),
+            };
+            out.push_str(ch);
+        }
+
+        if out.ends_with("/clock") {
+            out.push_str(".json");
+        }
+        // replace all occurrences
+        else if !out.ends_with("/clock.json") {
+            out = out.replace("/clock", "/clock.json");
+        }
+
+        // return the url if it already includes the host
+        if out.starts_with("https://") || out.starts_with("http://") {
+            return Ok((out, false));
+        }
+
+        Ok((BASE.to_string() + &out, true))
+    }
+
+    fn extract_best_candidate(&self, sources: &[SourceUrl]) -> Result<Stream, PlayError> {
+        let mut variants: Vec<Variant> = Vec::new();
+
+        for source in sources {
+            let Some(link) = source.extra_values.as_ref().filter(|l| !l.link.is_empty()) else {
+                variants.push(Variant::new(0, source.source_url.clone(), None, 0, Vec::new()));
+                continue;
+            };
+
+            let subtitles: Vec<Subtitle> = link
+                .subtitles
+                .iter()
+                .filter(|sub| sub.src.starts_with("http"))
+                .map(|sub| Subtitle {
+                    url: sub.src.clone(),
+                    language: Some(sub.lang.clone())
+                        .filter(|lang| !lang.is_empty())
+                        .or_else(|| sub.label.clone()),
+                })
+                .collect();
+
+            if let Some(values) = self.convert_wixmp(&link.link) {
+                for (qlt, url) in values {
+                    variants.push(Variant::new(qlt, url, None, 2, subtitles.clone()))
+                }
+                continue;
+            }
+
+            if let Some(values) = self.parse_master_m3u8(&link.link) {
+                for (qlt, url) in values {
+                    variants.push(Variant::new(qlt, url, Some(REF.to_string()), 3, subtitles.clone()))
+                }
+                continue;
+            }
+
+            //anything else (like sharepoint?)
+            variants.push(Variant::new(1, link.link.clone(), None, 0, subtitles));
+        }
+
+        if variants.is_empty() {
+            return Err(PlayError::NoResults("No playable sources".to_string()));
+        }
+
+        // sort: by height desc then by kind weight (HLS > MP4 > Other)
+        variants.sort_by(|a, b| (b.height, b.kind).cmp(&(a.height, a.kind)));
+
+        let best = variants.remove(0);
+        let mut headers = params!["User-Agent" => UA];
+        if let Some(referer) = best.referer {
+            headers.push(("Referer".to_string(), referer));
+        }
+
+        let mut stream = Stream {
+            url: best.url,
+            headers,
+            subtitles: best.subtitles,
+        };
+        stream.sort_subtitles();
+        Ok(stream)
+    }
+
+    /// https://repackager.wixmp.com/video.wixstatic.com/video/<id>/,1080p,720p,480p,/mp4/file.mp4.urlset/master.m3u8
+    fn convert_wixmp(&self, url: &str) -> Option<Vec<(i32, String)>> {
+        if !url.contains("repackager.wixmp.com") {
+            return None;
+        }
+
+        let base = url
+            .trim_start_matches("https://repackager.wixmp.com/")
+            .trim_end_matches(".urlset/master.m3u8");
+
+        // capture the comma quality list between ".../<id>/" and "/mp4/"
+        let caps = self.wixmp_regex.captures(base)?;
+        let id = caps.get(1)?.as_str();
+        let quality_list = caps.get(2)?.as_str();
+
+        let qualities: Vec<&str> = quality_list.split(',').collect();
+        if qualities.is_empty() {
+            return None;
+        }
+
+        // replace each ",<something>" segment with the chosen quality
+        let mut out = Vec::new();
+        for q in qualities {
+            let h = q.trim_end_matches('p').parse::<i32>().unwrap_or(0);
+            let u = format!(
+                "https://video.wixstatic.com/video/{}/{}/mp4/file.mp4",
+                id, q
+            );
+            out.push((h, u));
+        }
+
+        out.sort_by(|a, b| b.0.cmp(&a.0));
+        Some(out)
+    }
+
+    fn parse_master_m3u8(&self, url: &str) -> Option<Vec<(i32, String)>> {
+        if !url.ends_with("master.m3u8") {
+            return None;
+        }
+
+        let parameters = params![];
+        let headers = params![
+            "User-Agent" => UA,
+            "Referer" => REF,
+        ];
+        let body: Option<&str> = None;
+        let text =
+            send_request_expect_text("GET", url.to_string(), parameters, headers, body).ok()?;
+
+        if !text.contains("#EXTM3U") {
+            return None;
+        }
+
+        let base = Url::parse(url).ok()?;
+        let mut out: Vec<(i32, String)> = Vec::new();
+        let mut pending_height = 0i32;
+        let mut want_url_next = false;
+
+        for raw in text.lines() {
+            let line = raw.trim();
+
+            if line.starts_with("#EXT-X-I-FRAME-STREAM-INF") {
+                want_url_next = false;
+                pending_height = 0;
+                continue;
+            }
+
+            if line.starts_with("#EXT-X-STREAM-INF") {
+                pending_height = self.parse_height_from_inf(line);
+                want_url_next = true;
+                continue;
+            }
+
+            if want_url_next && !line.is_empty() && !line.starts_with('#') {
+                // resolve relative → absolute
+                let abs = if let Ok(u) = Url::parse(line) {
+                    u
+                } else if let Ok(u) = base.join(line) {
+                    u
+                } else {
+                    want_url_next = false;
+                    pending_height = 0;
+                    continue;
+                };
+                out.push((pending_height, abs.to_string()));
+                want_url_next = false;
+                pending_height = 0;
+            }
+        }
+
+        if out.is_empty() {
+            return None;
+        }
+
+        // sort highest first
+        out.sort_by(|a, b| b.0.cmp(&a.0));
+        Some(out)
+    }
+
+    fn parse_height_from_inf(&self, inf_line: &str) -> i32 {
+        // parse RESOLUTION=WxH (case-insensitive) and return H
+        let lower = inf_line.to_ascii_lowercase();
+        if let Some(pos) = lower.find("resolution=") {
+            let after = &lower[pos + "resolution=".len()..];
+            let mut w = String::new();
+            let mut h = String::new();
+            let mut seen_x = false;
+            for ch in after.chars() {
+                if ch == ',' || ch == ' ' {
+                    break;
+                }
+                if ch == 'x' {
+                    seen_x = true;
+                    continue;
+                }
+                if ch.is_ascii_digit() {
+                    if !seen_x {
+                        w.push(ch);
+                    } else {
+                        h.push(ch);
+                    }
+                } else {
+                    break;
+                }
+            }
+            if let Ok(n) = h.parse::<i32>() {
+                return n;
+            }
+        }
+        0
+    }
+}
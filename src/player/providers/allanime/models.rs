@@ -2,7 +2,7 @@
 use serde::Deserialize;
 
 //
-// 1) Search shows: { data: { shows: { edges: [ { _id, name, availableEpisodes } ] } } }
+// 1) Search shows: { data: { shows: { edges: [ { _id, name, englishName, availableEpisodes, season, episodeCount } ] } } }
 //
 #[derive(Debug, Deserialize)]
 pub struct ShowSearch {
@@ -24,8 +24,30 @@ pub struct ShowEdge {
     #[serde(rename = "_id")]
     pub id: String,
     pub name: String,
+    #[serde(rename = "englishName")]
+    pub english_name: Option<String>,
     #[serde(rename = "availableEpisodes")]
     pub available_episodes: AvailableEpisodes,
+    pub season: Option<AiredSeason>,
+    // allanime returns this as either a string or a number
+    #[serde(rename = "episodeCount")]
+    pub episode_count: Option<serde_json::Value>,
+}
+
+impl ShowEdge {
+    pub fn episode_count(&self) -> Option<u32> {
+        match self.episode_count.as_ref()? {
+            serde_json::Value::Number(n) => n.as_u64().map(|n| n as u32),
+            serde_json::Value::String(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AiredSeason {
+    pub quarter: Option<String>,
+    pub year: Option<u16>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -60,8 +82,11 @@ pub struct SingleShow {
 
 #[derive(Debug, Deserialize)]
 pub struct AvailableEpisodesDetail {
+    #[serde(default)]
     pub sub: Vec<String>,
+    #[serde(default)]
     pub dub: Vec<String>,
+    #[serde(default)]
     pub raw: Vec<String>,
 }
 
@@ -137,6 +162,17 @@ pub struct Link {
     pub resolution_str: Option<String>,
     #[serde(rename = "fromCache")]
     pub from_cache: Option<String>,
+    #[serde(default)]
+    pub subtitles: Vec<LinkSubtitle>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LinkSubtitle {
+    #[serde(default)]
+    pub lang: String,
+    pub label: Option<String>,
+    #[serde(default)]
+    pub src: String,
 }
 
 
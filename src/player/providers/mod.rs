@@ -0,0 +1,104 @@
+mod allanime;
+
+use super::{EpisodeEntry, PlayError};
+use crate::config::Config;
+
+// a show as listed by a provider, scored against the mal entry by the player
+#[derive(Debug, Clone)]
+pub struct SourceShow {
+    pub id: String,
+    pub name: String,
+    pub english_name: Option<String>,
+    pub year: Option<u16>,
+    pub sub_episodes: u32,
+    pub dub_episodes: u32,
+    // the total the show will have, when the provider knows it
+    pub episode_count: Option<u32>,
+}
+
+// an external subtitle track, a url or a file on disk
+#[derive(Debug, Clone)]
+pub struct Subtitle {
+    pub url: String,
+    // "en", "English", ... whatever the source calls it
+    pub language: Option<String>,
+}
+
+impl Subtitle {
+    fn is_language(&self, language: &str) -> bool {
+        self.language.as_deref().is_some_and(|lang| {
+            lang.eq_ignore_ascii_case(language)
+                || lang
+                    .to_lowercase()
+                    .starts_with(&language.to_lowercase())
+        })
+    }
+}
+
+// something mpv (or a download) can open
+#[derive(Debug, Clone)]
+pub struct Stream {
+    pub url: String,
+    // headers the source expects, like Referer or User-Agent
+    pub headers: Vec<(String, String)>,
+    pub subtitles: Vec<Subtitle>,
+}
+
+impl Stream {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn referer(&self) -> Option<&str> {
+        self.header("Referer")
+    }
+
+    // puts the subtitles in the preferred language first so the player picks them
+    pub fn sort_subtitles(&mut self) {
+        if let Some(language) = &Config::global().player.subtitle_language {
+            self.subtitles
+                .sort_by_key(|subtitle| !subtitle.is_language(language));
+        }
+    }
+}
+
+// a source to search shows on and stream episodes from
+// to add one: implement this and add it to provider_by_name
+pub trait StreamProvider: Send + Sync {
+    // the name used in the config and in source mappings
+    fn name(&self) -> &'static str;
+
+    // shows matching the query
+    fn search(&self, query: &str) -> Result<Vec<SourceShow>, PlayError>;
+
+    // every episode the show has, in any order
+    fn episodes(&self, show_id: &str) -> Result<Vec<EpisodeEntry>, PlayError>;
+
+    // the best playable stream for an episode
+    fn stream(&self, show_id: &str, episode: &str) -> Result<Stream, PlayError>;
+
+    // which version of the episodes stream() returns, part of the stream cache key
+    fn translation(&self) -> &'static str {
+        "sub"
+    }
+}
+
+pub fn provider_by_name(name: &str) -> Option<Box<dyn StreamProvider>> {
+    match name.to_lowercase().as_str() {
+        "allanime" => Some(Box::new(allanime::AllAnime::new())),
+        _ => None,
+    }
+}
+
+// the providers from the config in the order they are tried, unknown names are skipped
+pub fn configured_providers() -> Vec<Box<dyn StreamProvider>> {
+    Config::global()
+        .player
+        .providers
+        .iter()
+        .filter_map(|name| provider_by_name(name))
+        .collect()
+}
@@ -0,0 +1,275 @@
+use crate::config::Config;
+use crate::mal::models::anime::AnimeId;
+use crate::mal::network::send_request;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+const PREFERENCES_FILE: &str = "skip_preferences.toml";
+const SCRIPT_FILE: &str = "mal-cli-autoskip.lua";
+
+// jumps to the next chapter whenever one mal-cli marked as skippable starts
+const AUTOSKIP_SCRIPT: &str = r#"
+local skip = { Opening = true, Ending = true }
+
+mp.observe_property("chapter", "number", function(_, chapter)
+    if chapter == nil or chapter < 0 then
+        return
+    end
+
+    local chapters = mp.get_property_native("chapter-list")
+    local current = chapters[chapter + 1]
+    local following = chapters[chapter + 2]
+    if current ~= nil and following ~= nil and skip[current.title] then
+        mp.osd_message("Skipped " .. current.title)
+        mp.set_property_number("time-pos", following.time)
+    end
+end)
+"#;
+
+//
+// aniskip v2: { found, results: [ { interval: { startTime, endTime }, skipType, episodeLength } ] }
+//
+#[derive(Debug, Deserialize)]
+struct SkipResponse {
+    #[serde(default)]
+    found: bool,
+    #[serde(default)]
+    results: Vec<SkipResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SkipResult {
+    interval: SkipInterval,
+    #[serde(rename = "skipType")]
+    skip_type: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SkipInterval {
+    #[serde(rename = "startTime")]
+    start_time: f64,
+    #[serde(rename = "endTime")]
+    end_time: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkipTime {
+    // "Opening" or "Ending", also the chapter title
+    pub title: &'static str,
+    pub start: f64,
+    pub end: f64,
+}
+
+// the opening and ending of an episode, empty when the service doesn't know them
+pub fn fetch_skip_times(anime_id: AnimeId, episode: &str) -> Result<Vec<SkipTime>, String> {
+    // aniskip only knows whole episodes
+    let episode = episode
+        .parse::<f32>()
+        .ok()
+        .filter(|e| e.fract() == 0.0 && *e > 0.0)
+        .ok_or_else(|| format!("No skip times for episode {}", episode))?;
+
+    let url = format!(
+        "{}/v2/skip-times/{}/{}",
+        Config::global().skip.api_url.trim_end_matches('/'),
+        anime_id,
+        episode as u32
+    );
+    let params = vec![
+        ("types[]".to_string(), "op".to_string()),
+        ("types[]".to_string(), "ed".to_string()),
+        ("episodeLength".to_string(), "0".to_string()),
+    ];
+
+    let response = send_request::<SkipResponse>("GET", url, params, Vec::new(), None)
+        .map_err(|e| format!("Failed to fetch skip times: {}", e))?;
+
+    if !response.found {
+        return Ok(Vec::new());
+    }
+
+    let mut times: Vec<SkipTime> = response
+        .results
+        .into_iter()
+        .filter(|r| r.interval.end_time > r.interval.start_time)
+        .filter_map(|r| {
+            let title = match r.skip_type.as_str() {
+                "op" => "Opening",
+                "ed" => "Ending",
+                _ => return None,
+            };
+            Some(SkipTime {
+                title,
+                start: r.interval.start_time,
+                end: r.interval.end_time,
+            })
+        })
+        .collect();
+
+    times.sort_by(|a, b| a.start.total_cmp(&b.start));
+    Ok(times)
+}
+
+// ffmetadata chapters, the parts between the skip times are called "Episode"
+fn chapters(times: &[SkipTime]) -> String {
+    let mut marks: Vec<(f64, &str)> = Vec::new();
+    if times.first().is_none_or(|t| t.start > 0.0) {
+        marks.push((0.0, "Episode"));
+    }
+    for (index, time) in times.iter().enumerate() {
+        marks.push((time.start, time.title));
+        if times.get(index + 1).is_none_or(|next| next.start > time.end) {
+            marks.push((time.end, "Episode"));
+        }
+    }
+
+    let mut out = String::from(";FFMETADATA1\n");
+    for (index, (start, title)) in marks.iter().enumerate() {
+        // mpv only uses the start, the last chapter gets a long end
+        let end = marks.get(index + 1).map(|m| m.0).unwrap_or(start + 86400.0);
+        let _ = write!(
+            out,
+            "[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+            (start * 1000.0) as u64,
+            (end * 1000.0) as u64,
+            title
+        );
+    }
+    out
+}
+
+// mpv arguments adding the skip times of an episode as chapters
+pub fn chapter_args(anime_id: AnimeId, episode: &str, times: &[SkipTime]) -> Vec<String> {
+    if times.is_empty() {
+        return Vec::new();
+    }
+
+    let cache_dir = Config::cache_dir();
+    let chapters_path = cache_dir.join(format!("chapters-{}-{}.txt", anime_id, episode));
+    if std::fs::create_dir_all(&cache_dir).is_err()
+        || std::fs::write(&chapters_path, chapters(times)).is_err()
+    {
+        return Vec::new();
+    }
+
+    vec![format!("--chapters-file={}", chapters_path.display())]
+}
+
+// mpv arguments loading the script that jumps over the chapters
+pub fn autoskip_args() -> Vec<String> {
+    let cache_dir = Config::cache_dir();
+    let script_path = cache_dir.join(SCRIPT_FILE);
+    if std::fs::create_dir_all(&cache_dir).is_err()
+        || std::fs::write(&script_path, AUTOSKIP_SCRIPT).is_err()
+    {
+        return Vec::new();
+    }
+
+    vec![format!("--script={}", script_path.display())]
+}
+
+// per anime overrides of the auto_skip config, toggled from the popup
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SkipPreferences {
+    #[serde(default)]
+    auto_skip: HashMap<String, bool>,
+}
+
+impl SkipPreferences {
+    fn path() -> PathBuf {
+        Config::data_dir().join(PREFERENCES_FILE)
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|contents| toml::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let app_dir = Config::data_dir();
+        if !app_dir.exists() {
+            std::fs::create_dir_all(&app_dir)
+                .map_err(|e| format!("Failed to create data directory: {}", e))?;
+        }
+
+        let toml = toml::to_string(self)
+            .map_err(|e| format!("Failed to serialize skip preferences: {}", e))?;
+
+        std::fs::write(Self::path(), toml)
+            .map_err(|e| format!("Failed to write skip preferences: {}", e))
+    }
+
+    pub fn auto_skip(&self, anime_id: AnimeId) -> bool {
+        self.auto_skip
+            .get(&anime_id.to_string())
+            .copied()
+            .unwrap_or(Config::global().skip.auto_skip)
+    }
+
+    // flips auto skip for the anime and returns the new value
+    pub fn toggle(&mut self, anime_id: AnimeId) -> bool {
+        let value = !self.auto_skip(anime_id);
+        self.auto_skip.insert(anime_id.to_string(), value);
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(title: &'static str, start: f64, end: f64) -> SkipTime {
+        SkipTime { title, start, end }
+    }
+
+    // (start in ms, title) of every chapter
+    fn marks(chapters: &str) -> Vec<(u64, String)> {
+        let starts = chapters.lines().filter_map(|line| line.strip_prefix("START="));
+        let titles = chapters.lines().filter_map(|line| line.strip_prefix("title="));
+        starts
+            .zip(titles)
+            .map(|(start, title)| (start.parse().unwrap(), title.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn puts_the_episode_around_the_skip_times() {
+        let out = chapters(&[time("Opening", 90.0, 180.5), time("Ending", 1300.0, 1390.0)]);
+        assert!(out.starts_with(";FFMETADATA1\n[CHAPTER]\nTIMEBASE=1/1000\n"));
+        assert_eq!(
+            marks(&out),
+            vec![
+                (0, "Episode".to_string()),
+                (90000, "Opening".to_string()),
+                (180500, "Episode".to_string()),
+                (1300000, "Ending".to_string()),
+                (1390000, "Episode".to_string()),
+            ]
+        );
+        // each chapter ends where the next one starts
+        assert!(out.contains("START=90000\nEND=180500\n"));
+    }
+
+    #[test]
+    fn skip_times_at_the_start_or_touching_get_no_episode_between() {
+        let out = chapters(&[time("Opening", 0.0, 90.0), time("Ending", 90.0, 180.0)]);
+        assert_eq!(
+            marks(&out),
+            vec![
+                (0, "Opening".to_string()),
+                (90000, "Ending".to_string()),
+                (180000, "Episode".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn the_last_chapter_runs_a_day() {
+        let out = chapters(&[time("Opening", 10.0, 20.0)]);
+        assert!(out.ends_with("START=20000\nEND=86420000\ntitle=Episode\n"));
+    }
+}
@@ -0,0 +1,231 @@
+use crate::add_screen_caching;
+use crate::app::Action;
+use crate::config::keymap::{KeyAction, KeyContext, KeyInput};
+use crate::config::Config;
+use crate::config::navigation::NavDirection;
+use crate::player::downloads::{DownloadJob, DownloadState};
+
+use super::BackgroundUpdate;
+use super::ExtraInfo;
+use super::Screen;
+use super::widgets::navigatable::Navigatable;
+
+use crossterm::event::{MouseEvent, MouseEventKind};
+use ratatui::Frame;
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Margin};
+use ratatui::style::{Color, Style};
+use ratatui::symbols;
+use ratatui::widgets::{Block, Borders, Clear, Gauge, Paragraph};
+
+const ROW_HEIGHT: u16 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    NavBar,
+    Content,
+}
+
+#[derive(Clone)]
+pub struct DownloadsScreen {
+    jobs: Vec<DownloadJob>,
+    focus: Focus,
+    navigatable: Navigatable,
+    app_info: ExtraInfo,
+}
+
+impl DownloadsScreen {
+    pub fn new(info: ExtraInfo) -> Self {
+        Self {
+            jobs: info.downloads.jobs(),
+            focus: Focus::NavBar,
+            navigatable: Navigatable::new((5, 1)),
+            app_info: info,
+        }
+    }
+
+    // cancels a running download, retries a stopped one or plays a finished one
+    fn activate(&mut self) -> Option<Action> {
+        let job = self.navigatable.get_selected_item(&self.jobs)?;
+
+        match job.state {
+            DownloadState::Queued | DownloadState::Downloading => {
+                self.app_info.downloads.cancel(job.id);
+                None
+            }
+            DownloadState::Failed(_) | DownloadState::Cancelled => {
+                Some(Action::DownloadEpisode(job.anime_id, job.episode.clone()))
+            }
+            DownloadState::Done => Some(Action::PlayEpisode(job.anime_id, job.episode.clone())),
+        }
+    }
+
+    fn state_text(job: &DownloadJob) -> (String, Color) {
+        let theme = &Config::global().theme;
+        match &job.state {
+            DownloadState::Queued => ("Queued".to_string(), theme.primary),
+            DownloadState::Downloading => (
+                format!(
+                    "{:.1} MB{}",
+                    job.downloaded_bytes as f64 / 1_000_000.0,
+                    job.progress
+                        .map(|p| format!(" - {:.0}%", p * 100.0))
+                        .unwrap_or_default()
+                ),
+                theme.highlight,
+            ),
+            DownloadState::Done => ("Done".to_string(), theme.status_color("completed")),
+            DownloadState::Cancelled => ("Cancelled".to_string(), theme.status_color("on_hold")),
+            DownloadState::Failed(e) => (format!("Failed: {}", e), theme.error),
+        }
+    }
+}
+
+impl Screen for DownloadsScreen {
+    add_screen_caching!();
+
+    fn key_context(&self) -> Option<KeyContext> {
+        Some(KeyContext::Downloads)
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(Clear, area);
+
+        // navbar on top
+        let [_, bottom] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Fill(1)])
+            .areas(area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols::border::ROUNDED)
+            .title("Downloads")
+            .title_bottom(format!(
+                " [{}] cancel / retry / play ",
+                Config::global().keymap.hint(
+                    &Config::global().navigation,
+                    Some(KeyContext::Downloads),
+                    KeyAction::Select
+                )
+            ))
+            .border_style(Style::default().fg(Config::global().theme.primary));
+        frame.render_widget(block, bottom);
+
+        let list_area = bottom.inner(Margin::new(2, 1));
+
+        if self.jobs.is_empty() {
+            let text = Paragraph::new(format!(
+                "Nothing downloaded yet, queue episodes from the episode list with {}\n\nEpisodes are saved to {}",
+                Config::global().keymap.hint(
+                    &Config::global().navigation,
+                    Some(KeyContext::EpisodePopup),
+                    KeyAction::Download
+                ),
+                Config::download_dir().display()
+            ))
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Config::global().theme.text));
+            frame.render_widget(text, list_area.inner(Margin::new(0, list_area.height / 3)));
+            return;
+        }
+
+        self.navigatable
+            .change_size(((list_area.height / ROW_HEIGHT).max(1), 1));
+
+        let focused = self.focus == Focus::Content;
+        self.navigatable
+            .construct(&self.jobs, list_area, |job, area, selected| {
+                let theme = &Config::global().theme;
+                let border_color = if selected && focused {
+                    theme.highlight
+                } else {
+                    theme.primary
+                };
+
+                let block = Block::default()
+                    .borders(Borders::ALL)
+                    .border_set(symbols::border::ROUNDED)
+                    .border_style(Style::default().fg(border_color));
+                frame.render_widget(block, area);
+
+                let [text_area, gauge_area] = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(1), Constraint::Length(1)])
+                    .areas(area.inner(Margin::new(1, 1)));
+
+                let (state, state_color) = Self::state_text(job);
+                frame.render_widget(
+                    Paragraph::new(format!("{} - EP {}", job.title, job.episode))
+                        .style(Style::default().fg(theme.text)),
+                    text_area,
+                );
+                frame.render_widget(
+                    Paragraph::new(state)
+                        .alignment(Alignment::Right)
+                        .style(Style::default().fg(state_color)),
+                    text_area,
+                );
+
+                let ratio = job.progress.unwrap_or(0.0).clamp(0.0, 1.0) as f64;
+                frame.render_widget(
+                    Gauge::default()
+                        .gauge_style(Style::new().fg(state_color).bg(Color::Black))
+                        .ratio(ratio)
+                        .label(""),
+                    gauge_area,
+                );
+            });
+    }
+
+    fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
+        match self.focus {
+            Focus::NavBar => {
+                self.focus = Focus::Content;
+            }
+            Focus::Content => {
+                if key.focus_direction() == NavDirection::Up {
+                    self.focus = Focus::NavBar;
+                    return Some(Action::NavbarSelect(true));
+                }
+
+                match key.direction() {
+                    NavDirection::Up => self.navigatable.move_up(),
+                    NavDirection::Down => self.navigatable.move_down(),
+                    _ => {}
+                }
+
+                if key.is(KeyAction::Select) {
+                    return self.activate();
+                }
+            }
+        }
+
+        None
+    }
+
+    fn handle_mouse(&mut self, mouse_event: MouseEvent) -> Option<Action> {
+        if mouse_event.row < 3 {
+            self.focus = Focus::NavBar;
+            return Some(Action::NavbarSelect(true));
+        }
+
+        self.navigatable.handle_scroll(mouse_event);
+
+        if self.navigatable.get_hovered_index(mouse_event).is_some() {
+            self.focus = Focus::Content;
+
+            if let MouseEventKind::Down(_) = mouse_event.kind {
+                return self.activate();
+            }
+        }
+
+        None
+    }
+
+    fn apply_update(&mut self, mut update: BackgroundUpdate) {
+        if let Some(jobs) = update.take::<Vec<DownloadJob>>("downloads") {
+            self.jobs = jobs;
+        }
+    }
+}
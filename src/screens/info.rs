@@ -5,8 +5,8 @@ use super::widgets::button::Button;
 use super::ExtraInfo;
 use super::Screen;
 
-use crossterm::event::KeyCode;
-use crossterm::event::KeyEvent;
+use crate::config::keymap::{KeyAction, KeyContext, KeyInput};
+use crate::config::navigation::NavDirection;
 use ratatui::Frame;
 use ratatui::widgets;
 use ratatui::style;
@@ -30,6 +30,10 @@ impl InfoScreen {
 
 impl Screen for InfoScreen {
 
+    fn key_context(&self) -> Option<KeyContext> {
+        Some(KeyContext::Info)
+    }
+
     fn draw(&mut self, frame: &mut Frame) {
         let size = frame.area();
         let block = widgets::Block::default()
@@ -53,19 +57,21 @@ impl Screen for InfoScreen {
 
     }
 
-    fn handle_keyboard(&mut self, key_event: KeyEvent) -> Option<Action> {
-        match key_event.code {
-            KeyCode::Up | KeyCode::Char('j') => {
+    fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
+        if key.is(KeyAction::Select) {
+            return Some(Action::SwitchScreen(OVERVIEW));
+        }
+        match key.direction() {
+            NavDirection::Up => {
                 if self.selected_button > 0 {
                     self.selected_button -= 1;
                 }
             }
-            KeyCode::Down | KeyCode::Char('k') => {
+            NavDirection::Down => {
                 if self.selected_button < self.buttons.len() - 1 {
                     self.selected_button += 1;
                 }
             }
-            KeyCode::Enter => return Some(Action::SwitchScreen(OVERVIEW)),
             _ => {},
         }
         None
@@ -3,9 +3,10 @@ use super::{
     screens::*,
     widgets::{button::Button, navigatable::Navigatable},
 };
-use crate::{app::Action, config::{navigation::NavDirection, Config}};
+use crate::{app::Action, config::navigation::NavDirection};
 use crate::mal::MalClient;
-use crossterm::event::{KeyEvent, MouseEvent};
+use crate::config::keymap::{KeyAction, KeyContext, KeyInput};
+use crossterm::event::MouseEvent;
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout},
@@ -45,8 +46,7 @@ impl LaunchScreen {
             }
             1 => {
                 if MalClient::user_is_logged_in() {
-                    MalClient::log_out();
-                    Some(Action::SwitchScreen(LAUNCH))
+                    Some(Action::LogOut)
                 } else {
                     Some(Action::SwitchScreen(LOGIN))
                 }
@@ -58,6 +58,10 @@ impl LaunchScreen {
 }
 
 impl Screen for LaunchScreen {
+    fn key_context(&self) -> Option<KeyContext> {
+        Some(KeyContext::Launch)
+    }
+
     fn draw(&mut self, frame: &mut Frame) {
         let area = frame.area();
 
@@ -112,10 +116,8 @@ impl Screen for LaunchScreen {
             });
     }
 
-    fn handle_keyboard(&mut self, key_event: KeyEvent) -> Option<Action> {
-        let nav = &Config::global().navigation;
-
-        match nav.get_direction(&key_event.code) {
+    fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
+        match key.direction() {
             NavDirection::Up => {
                 self.navigatable.move_up();
             }
@@ -125,7 +127,7 @@ impl Screen for LaunchScreen {
             _ => {}
         };
 
-        if nav.is_select(&key_event.code) {
+        if key.is(KeyAction::Select) {
             return self.activate_button(self.navigatable.get_selected_index());
         }
 
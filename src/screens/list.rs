@@ -4,6 +4,7 @@ use std::thread::JoinHandle;
 
 use crate::add_screen_caching;
 use crate::app::Event;
+use crate::config::keymap::{KeyAction, KeyContext, KeyInput};
 use crate::config::navigation::NavDirection;
 use crate::config::Config;
 use crate::mal::models::anime::{Anime, AnimeId};
@@ -12,8 +13,6 @@ use crate::utils::imageManager::ImageManager;
 use crate::utils::input::Input;
 use crate::{app::Action, screens::Screen};
 
-use crossterm::event::KeyCode;
-use crossterm::event::KeyEvent;
 use ratatui::Frame;
 use ratatui::layout::Layout;
 use ratatui::layout::{Alignment, Constraint, Margin, Rect};
@@ -27,8 +26,8 @@ use ratatui::widgets::Paragraph;
 
 use super::widgets::animebox::AnimeBox;
 use super::widgets::navigatable::Navigatable;
-use super::widgets::popup::{Arrows, SelectionPopup};
-use super::{BackgroundUpdate, ExtraInfo};
+use super::widgets::popups::{Arrows, SelectionPopup};
+use super::{BackgroundUpdate, ExtraInfo, anime_key};
 
 #[derive(Debug, Clone)]
 struct Statistics {
@@ -117,6 +116,26 @@ pub struct ListScreen {
 }
 
 impl ListScreen {
+    // refilters the list in the background after a dropdown changed
+    fn apply_filter(&mut self, index: usize, selection: String) {
+        self.filters.update(index, selection);
+
+        let animes = self.app_info.anime_store.get_bulk(self.all_animes.clone());
+        if let Some(sx) = &self.bg_sx {
+            sx.send(LocalEvent::Dropdown(
+                animes.iter().map(|rc| (**rc).clone()).collect(),
+                self.filters.clone(),
+            ))
+            .ok();
+        }
+    }
+
+    fn close_dropdowns(&mut self) {
+        for dropdown in self.dropdowns.iter_mut() {
+            dropdown.close();
+        }
+    }
+
     pub fn new(info: ExtraInfo) -> Self {
         Self {
             image_manager: Arc::new(Mutex::new(ImageManager::new())),
@@ -253,6 +272,28 @@ impl ListScreen {
 impl Screen for ListScreen {
     add_screen_caching!();
 
+    fn key_context(&self) -> Option<KeyContext> {
+        Some(KeyContext::List)
+    }
+
+    fn takes_text(&self) -> bool {
+        self.focus == Focus::Search
+    }
+
+    fn key_actions(&self) -> Vec<KeyAction> {
+        use KeyAction::*;
+        match self.focus {
+            Focus::NavBar | Focus::Search => vec![FocusUp, FocusDown, FocusRight, NextFilter],
+            Focus::Content => vec![
+                Up, Down, Left, Right, Select, Play, PickEpisode, OpenInBrowser,
+                FocusUp, FocusRight, FocusSearch, NextFilter,
+            ],
+            Focus::Dropdown => vec![
+                Up, Down, Select, Close, FocusUp, FocusDown, FocusLeft, FocusSearch, NextFilter,
+            ],
+        }
+    }
+
     // draws the screen
     fn draw(&mut self, frame: &mut Frame) {
         let area = frame.area();
@@ -386,14 +427,24 @@ impl Screen for ListScreen {
         );
     }
 
-    fn handle_keyboard(&mut self, key_event: KeyEvent) -> Option<Action> {
-        let modifier = key_event.modifiers.contains(crossterm::event::KeyModifiers::CONTROL);
-        let nav = &Config::global().navigation;
+    fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
+        if key.is(KeyAction::FocusSearch) {
+            self.close_dropdowns();
+            self.focus = Focus::Search;
+            return None;
+        }
+        // cycles the list status, the first dropdown
+        if key.is(KeyAction::NextFilter) {
+            if let Some(selection) = self.dropdowns.first_mut().and_then(|d| d.select_next()) {
+                self.apply_filter(0, selection);
+            }
+            return None;
+        }
 
         match self.focus {
             Focus::Search => {
-                if modifier {
-                    match nav.get_direction(&key_event.code) {
+                if key.focus_direction() != NavDirection::None {
+                    match key.focus_direction() {
                         NavDirection::Up => {
                             self.focus = Focus::NavBar;
                             return Some(Action::NavbarSelect(true));
@@ -410,7 +461,7 @@ impl Screen for ListScreen {
                     }
                 }
 
-                if let Some(text) = self.search_input.handle_event(key_event, true) {
+                if let Some(text) = self.search_input.handle_event(key.event, true) {
                     if let Some(sx) = &self.bg_sx {
                         let animes = self.app_info.anime_store.get_bulk(self.all_animes.clone());
                         sx.send(LocalEvent::Search(
@@ -427,8 +478,8 @@ impl Screen for ListScreen {
             }
 
             Focus::Content => {
-                if modifier {
-                    match nav.get_direction(&key_event.code) {
+                if key.focus_direction() != NavDirection::None {
+                    match key.focus_direction() {
                         NavDirection::Up => {
                             self.focus = Focus::Search;
                             return None;
@@ -442,7 +493,7 @@ impl Screen for ListScreen {
                     return None;
                 }
 
-                match nav.get_direction(&key_event.code) {
+                match key.direction() {
                     NavDirection::Up => {
                         self.navigatable.move_up();
                     }
@@ -458,18 +509,17 @@ impl Screen for ListScreen {
                     _ => {}
                 }
 
-                if nav.is_select(&key_event.code) {
-                    if let Some(anime_id) =
-                        self.navigatable.get_selected_item(&self.filtered_animes)
-                    {
+                if let Some(anime_id) = self.navigatable.get_selected_item(&self.filtered_animes) {
+                    if key.is(KeyAction::Select) {
                         return Some(Action::ShowOverlay(*anime_id));
                     }
+                    return anime_key(&key, *anime_id);
                 }
             }
 
             Focus::Dropdown => {
-                if modifier {
-                    match nav.get_direction(&key_event.code) {
+                if key.focus_direction() != NavDirection::None {
+                    match key.focus_direction() {
                         NavDirection::Down | NavDirection::Left => {
                             self.focus = Focus::Content;
                             if let Some(dropdown) =
@@ -495,7 +545,7 @@ impl Screen for ListScreen {
                 if let Some(dropdown) = self.dropdown_nav.get_selected_item_mut(&mut self.dropdowns)
                 {
                     if !dropdown.is_open() {
-                        match nav.get_direction(&key_event.code) {
+                        match key.direction() {
                             NavDirection::Up => {
                                 self.dropdown_nav.move_up();
                                 return None;
@@ -508,19 +558,9 @@ impl Screen for ListScreen {
                         }
                     }
 
-                    if let Some(selection) = dropdown.handle_input(key_event) {
+                    if let Some(selection) = dropdown.handle_input(key) {
                         let index = self.dropdown_nav.get_selected_index();
-                        self.filters.update(index, selection);
-
-                        let animes = self.app_info.anime_store.get_bulk(self.all_animes.clone());
-
-                        if let Some(sx) = &self.bg_sx {
-                            sx.send(LocalEvent::Dropdown(
-                                animes.iter().map(|rc| (**rc).clone()).collect(),
-                                self.filters.clone(),
-                            ))
-                            .ok();
-                        }
+                        self.apply_filter(index, selection);
                     }
                 }
             }
@@ -549,16 +589,7 @@ impl Screen for ListScreen {
 
             let selection = dropdown.handle_mouse(mouse_event)?;
             let index = self.dropdown_nav.get_selected_index();
-            self.filters.update(index, selection);
-
-            let animes = self.app_info.anime_store.get_bulk(self.all_animes.clone());
-            if let Some(sx) = &self.bg_sx {
-                sx.send(LocalEvent::Dropdown(
-                    animes.iter().map(|rc| (**rc).clone()).collect(),
-                    self.filters.clone(),
-                ))
-                .ok();
-            }
+            self.apply_filter(index, selection);
 
             return None;
         }
@@ -700,6 +731,17 @@ impl Screen for ListScreen {
 
         if let Some(startup) = update.take::<bool>("startup") {
             self.bg_startup = startup;
+            // a status picked in the command palette before the list was loaded
+            if !startup && self.filters.list_type != "all" {
+                let status = self.filters.list_type.clone();
+                self.apply_filter(0, status);
+            }
+        }
+
+        if let Some(status) = update.take::<String>("list_status")
+            && let Some(status) = self.dropdowns[0].select_option(&status)
+        {
+            self.apply_filter(0, status);
         }
 
         if let Some(filtered_animes) = update.take::<Vec<AnimeId>>("filtered_animes") {
@@ -1,9 +1,10 @@
 use std::cmp::{max, min};
 use crate::{add_screen_caching, app::Event, config::{navigation::NavDirection, Config}, mal::MalClient, screens::widgets::button::Button};
-use crossterm::event::KeyEvent;
+use crate::config::keymap::{KeyAction, KeyContext, KeyInput};
 use super::{screens::*, widgets::navigatable::Navigatable, BackgroundUpdate, ExtraInfo, Screen};
 use std::thread::JoinHandle;
 use crate::app::Action;
+use crate::utils::hooks::{self, HookPayload};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect}, 
     widgets::{ Block, Borders, Clear, Paragraph}, 
@@ -65,6 +66,10 @@ impl LoginScreen {
 impl Screen for LoginScreen {
     add_screen_caching!();
 
+    fn key_context(&self) -> Option<KeyContext> {
+        Some(KeyContext::Login)
+    }
+
     fn draw(&mut self, frame: &mut Frame) {
         let area = frame.area();
 
@@ -153,10 +158,8 @@ impl Screen for LoginScreen {
         });
     }
 
-    fn handle_keyboard(&mut self, key_event: KeyEvent) -> Option<Action> {
-        let nav = &Config::global().navigation;
-
-        match nav.get_direction(&key_event.code) {
+    fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
+        match key.direction() {
             NavDirection::Up => {
                 self.navigatable.move_up();
             }
@@ -166,7 +169,7 @@ impl Screen for LoginScreen {
             _ => {} 
         };
 
-        if nav.is_select(&key_event.code) {
+        if key.is(KeyAction::Select) {
             return self.activate_button(self.navigatable.get_selected_index());
         }
         None
@@ -219,6 +222,7 @@ impl Screen for LoginScreen {
 
             joinable.join().unwrap();  
             mal_client.update_user_login();
+            hooks::fire(&Config::global().hooks.on_login, HookPayload::new("login"));
             let new_url = "Login successful".to_string();
             let update = BackgroundUpdate::new(id.clone())
                 .set("login_url", new_url);
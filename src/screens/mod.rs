@@ -1,10 +1,13 @@
-use crate::app::{Action, Event, ExtraInfo};
-use crate::mal::models::anime::AnimeId;
+use crate::app::{Action, Event, ExtraInfo, Resume};
+use crate::config::Config;
+use crate::config::keymap::{KeyAction, KeyContext, KeyInput, KeySequence};
+use crate::mal::models::anime::{Anime, AnimeId, status_is_known};
+use crate::player::{PlayResult, ShowCandidate, library};
 use std::collections::HashMap;
 use ratatui::layout::Layout;
 use std::thread::JoinHandle;
 use widgets::navbar;
-use widgets::popup;
+use widgets::popups;
 use ratatui::Frame;
 use std::any::Any;
 use screens::*;
@@ -21,6 +24,7 @@ mod search;
 mod login;
 mod info;
 mod list;
+mod downloads;
 
 // this is a macro to define screens in a more structured way
 // it allows for screens to be implemented in a single place and work across the app
@@ -106,6 +110,7 @@ define_screens! {
     SEASONS => "Seasons" => seasons::SeasonsScreen,
     SEARCH => "Search" => search::SearchScreen,
     LIST => "List" => list::ListScreen,
+    DOWNLOADS => "Downloads" => downloads::DownloadsScreen,
 
     // To add more::
     // SCREEN1 => "<structName>" => <module>::<structName>Screen,
@@ -117,9 +122,23 @@ define_screens! {
 #[allow(dead_code, unused_variables)]
 pub trait Screen {
     fn draw(&mut self, frame: &mut Frame);
-    fn handle_keyboard(&mut self, key_event: crossterm::event::KeyEvent) -> Option<Action> {
+    fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
         None
     }
+    // which bindings of the keymap apply, screens without one only get the global ones
+    fn key_context(&self) -> Option<KeyContext> {
+        None
+    }
+    // while a text field has focus, plain characters aren't looked up in the keymap
+    fn takes_text(&self) -> bool {
+        false
+    }
+    // the actions the help lists, for the part of the screen that has focus
+    fn key_actions(&self) -> Vec<KeyAction> {
+        self.key_context()
+            .map(|context| context.actions().to_vec())
+            .unwrap_or_default()
+    }
     fn handle_mouse(&mut self, mouse_event: crossterm::event::MouseEvent) -> Option<Action> {
         None
     }
@@ -149,14 +168,36 @@ pub trait Screen {
     fn apply_update(&mut self, update: BackgroundUpdate) {}
 }
 
+// how many places back can go
+const HISTORY_LIMIT: usize = 50;
+
+// somewhere the user has been, a screen and the anime open on it
+#[derive(Debug, Clone, PartialEq)]
+struct Place {
+    screen: String,
+    anime: Option<AnimeId>,
+}
+
 pub struct ScreenManager {
     navbar: navbar::NavBar,
-    overlay: popup::AnimePopup,
-    error_overlay: popup::ErrorPopup,
+    overlay: popups::AnimePopup,
+    error_overlay: popups::ErrorPopup,
+    help_overlay: popups::HelpPopup,
+    palette: popups::CommandPalette,
+    source_overlay: popups::SourcePopup,
+    episode_overlay: popups::EpisodePopup,
+    binge_overlay: popups::BingePopup,
+    completion_overlay: popups::CompletionPopup,
+    cast_overlay: popups::CastPopup,
     current_screen: Box<dyn Screen>,
     screen_storage: HashMap<String, Box<dyn Screen>>,
     backgrounds: Vec<JoinHandle<()>>,
     passable_info: ExtraInfo,
+    // keys of a sequence that isn't finished yet
+    keys: KeySequence,
+    // the places to go back and forward to, the latest is last
+    back: Vec<Place>,
+    forward: Vec<Place>,
 }
 
 #[allow(dead_code)]
@@ -170,13 +211,25 @@ impl ScreenManager {
                 .add_screen(SEASONS)
                 .add_screen(SEARCH)
                 .add_screen(LIST)
-                .add_screen(PROFILE),
-            overlay: popup::AnimePopup::new(passable_info.clone()),
-            error_overlay: popup::ErrorPopup::new(),
+                .add_screen(DOWNLOADS)
+                .add_screen(PROFILE)
+                .add_screen(SETTINGS),
+            overlay: popups::AnimePopup::new(passable_info.clone()),
+            error_overlay: popups::ErrorPopup::new(),
+            help_overlay: popups::HelpPopup::new(),
+            palette: popups::CommandPalette::new(),
+            source_overlay: popups::SourcePopup::new(passable_info.clone()),
+            episode_overlay: popups::EpisodePopup::new(passable_info.clone()),
+            binge_overlay: popups::BingePopup::new(passable_info.clone()),
+            completion_overlay: popups::CompletionPopup::new(),
+            cast_overlay: popups::CastPopup::new(passable_info.clone()),
             current_screen: Box::new(launch::LaunchScreen::new(passable_info.clone())),
             screen_storage: HashMap::new(),
             backgrounds: Vec::new(),
             passable_info,
+            keys: KeySequence::default(),
+            back: Vec::new(),
+            forward: Vec::new(),
         }
     }
 
@@ -193,7 +246,14 @@ impl ScreenManager {
             self.navbar.render(frame, nav_bar_area);
         }
         self.overlay.render(frame);
+        self.episode_overlay.render(frame);
+        self.source_overlay.render(frame);
+        self.cast_overlay.render(frame);
+        self.binge_overlay.render(frame);
+        self.completion_overlay.render(frame);
+        self.palette.render(frame);
         self.error_overlay.render(frame);
+        self.help_overlay.render(frame);
     }
 
     pub fn toggle_navbar(&mut self, select: bool) {
@@ -205,6 +265,9 @@ impl ScreenManager {
     }
 
     pub fn toggle_overlay(&mut self, anime: AnimeId) {
+        if !self.overlay.is_open() || self.overlay.anime_id() != anime {
+            self.remember(self.place());
+        }
         self.overlay.set_anime(anime);
         self.overlay.open();
     }
@@ -218,35 +281,178 @@ impl ScreenManager {
         self.error_overlay.open();
     }
 
+    pub fn show_source_picker(
+        &mut self,
+        anime: &Anime,
+        resume: Resume,
+        candidates: Vec<ShowCandidate>,
+    ) {
+        self.source_overlay
+            .open_with_candidates(anime, resume, candidates);
+    }
+
+    pub fn show_episodes(&mut self, anime_id: AnimeId) {
+        if let Some(anime) = self.passable_info.anime_store.get(&anime_id) {
+            self.episode_overlay.open(&anime);
+        }
+    }
+
+    pub fn rematch_source(&mut self, anime_id: AnimeId) {
+        if let Some(anime) = self.passable_info.anime_store.get(&anime_id) {
+            self.source_overlay.open_and_search(&anime, Resume::Nothing);
+        }
+    }
+
+    pub fn start_binge(&mut self, anime: &Anime, next_episode: u32) {
+        self.binge_overlay.start_countdown(anime, next_episode);
+    }
+
+    pub fn offer_sequel(&mut self, anime: &Anime) {
+        self.binge_overlay.offer_sequel(anime);
+    }
+
+    pub fn show_cast(&mut self, anime_id: AnimeId) {
+        if let Some(anime) = self.passable_info.anime_store.get(&anime_id) {
+            self.cast_overlay.open(&anime);
+        }
+    }
+
+    pub fn confirm_completion(&mut self, anime: &Anime, details: PlayResult) {
+        self.completion_overlay.open(anime, details);
+    }
+
     pub fn handle_input(&mut self, event: crossterm::event::Event) -> Option<Action> {
         match event {
             crossterm::event::Event::Key(key_event) => {
+                let (context, typing) = self.key_target();
+                let config = Config::global();
+                let key = self.keys.press(&config.keymap, &config.navigation, context, key_event, typing)?;
+
+                if key.is(KeyAction::Quit) {
+                    return Some(Action::Quit);
+                }
+
+                if self.help_overlay.is_open() {
+                    return self.help_overlay.handle_keyboard(key);
+                }
+
+                if key.is(KeyAction::Help) {
+                    self.show_help();
+                    return None;
+                }
+
                 if self.error_overlay.is_open() {
-                    return self.error_overlay.handle_keyboard(key_event);
+                    return self.error_overlay.handle_keyboard(key);
+                }
+
+                if self.palette.is_open() {
+                    return self.palette.handle_keyboard(key);
+                }
+
+                if key.is(KeyAction::CommandPalette) {
+                    self.palette.open(&self.passable_info.anime_store.get_list());
+                    return None;
+                }
+
+                if self.completion_overlay.is_open() {
+                    return self.completion_overlay.handle_keyboard(key);
+                }
+
+                if self.binge_overlay.is_open() {
+                    return self.binge_overlay.handle_keyboard(key);
+                }
+
+                if self.source_overlay.is_open() {
+                    return self.source_overlay.handle_keyboard(key);
+                }
+
+                if self.cast_overlay.is_open() {
+                    return self.cast_overlay.handle_keyboard(key);
+                }
+
+                if self.episode_overlay.is_open() {
+                    return self.episode_overlay.handle_keyboard(key);
+                }
+
+                if key.is(KeyAction::Back) {
+                    self.go_back();
+                    return None;
+                }
+
+                if key.is(KeyAction::Forward) {
+                    self.go_forward();
+                    return None;
                 }
 
                 if self.overlay.is_open() {
-                    return self.overlay.handle_keyboard(key_event);
+                    // closing the anime is a place to come back to as well
+                    let place = self.place();
+                    let action = self.overlay.handle_keyboard(key);
+                    if !self.overlay.is_open() {
+                        self.remember(place);
+                    }
+                    return action;
                 }
 
                 if self.navbar.is_selected() {
-                    return self.navbar.handle_keyboard(key_event)
+                    return self.navbar.handle_keyboard(key)
                         .and_then(|action| match action {
-                        Action::NavbarSelect(_) => self.current_screen.handle_keyboard(key_event),
+                        Action::NavbarSelect(_) => self.current_screen.handle_keyboard(key),
                         other => Some(other),
                     });
                 }
 
-                self.current_screen.handle_keyboard(key_event)
+                // screens that keep what they fetched are made again to fetch it anew
+                if key.is(KeyAction::Refresh) && self.current_screen.should_store() {
+                    self.current_screen = create_screen(&self.current_screen.get_name(), &self.passable_info);
+                    self.cleanup_backgrounds();
+                    self.spawn_background();
+                    return None;
+                }
+
+                self.current_screen.handle_keyboard(key)
             }
 
             crossterm::event::Event::Mouse(mouse_event) => {
+                if self.help_overlay.is_open() {
+                    return self.help_overlay.handle_mouse(mouse_event);
+                }
+
                 if self.error_overlay.is_open() {
                     return self.error_overlay.handle_mouse(mouse_event);
                 }
 
+                if self.palette.is_open() {
+                    return self.palette.handle_mouse(mouse_event);
+                }
+
+                if self.completion_overlay.is_open() {
+                    return self.completion_overlay.handle_mouse(mouse_event);
+                }
+
+                if self.binge_overlay.is_open() {
+                    return self.binge_overlay.handle_mouse(mouse_event);
+                }
+
+                if self.source_overlay.is_open() {
+                    return self.source_overlay.handle_mouse(mouse_event);
+                }
+
+                if self.cast_overlay.is_open() {
+                    return self.cast_overlay.handle_mouse(mouse_event);
+                }
+
+                if self.episode_overlay.is_open() {
+                    return self.episode_overlay.handle_mouse(mouse_event);
+                }
+
                 if self.overlay.is_open() {
-                    return self.overlay.handle_mouse(mouse_event);
+                    let place = self.place();
+                    let action = self.overlay.handle_mouse(mouse_event);
+                    if !self.overlay.is_open() {
+                        self.remember(place);
+                    }
+                    return action;
                 }
 
                 if self.navbar.is_selected() {
@@ -265,10 +471,52 @@ impl ScreenManager {
 
     }
 
-    pub fn update_screen(&mut self, update: BackgroundUpdate) {
+    // returns an action when a background update should trigger one, eg. the binge countdown ending
+    pub fn update_screen(&mut self, mut update: BackgroundUpdate) -> Option<Action> {
         if update.id == "popup" {
             self.overlay.apply_update(update);
-            return;
+            return None;
+        }
+
+        if update.id == "source_popup" {
+            self.source_overlay.apply_update(update);
+            return None;
+        }
+
+        if update.id == "cast_popup" {
+            return self.cast_overlay.apply_update(update);
+        }
+
+        if update.id == "binge_popup" {
+            return self.binge_overlay.apply_update(update);
+        }
+
+        // an episode watched in another player, only entries on the list are updated
+        if update.id == "scrobble" {
+            let title = update.take::<String>("title")?;
+            let result = update.take::<PlayResult>("result")?;
+            let animes = self.passable_info.anime_store.get_list();
+            let anime = library::match_title(
+                animes
+                    .iter()
+                    .map(|anime| anime.as_ref())
+                    .filter(|anime| status_is_known(anime.my_list_status.status.clone())),
+                &title,
+            )?;
+            return Some(Action::ScrobbleEpisode(anime.id, result));
+        }
+
+        if update.id == "episode_popup" {
+            // the show couldnt be matched, pick it before listing episodes
+            if let Some((anime_id, candidates)) =
+                update.take::<(AnimeId, Vec<ShowCandidate>)>("candidates")
+                && let Some(anime) = self.passable_info.anime_store.get(&anime_id)
+            {
+                self.episode_overlay.close();
+                self.show_source_picker(&anime, Resume::EpisodeList, candidates);
+            }
+            self.episode_overlay.apply_update(update);
+            return None;
         }
 
         if self.current_screen.get_name() == update.id {
@@ -276,12 +524,150 @@ impl ScreenManager {
         } else if let Some(screen) = self.screen_storage.get_mut(&update.id) {
             screen.apply_update(update);
         }
+        None
+    }
+
+    // the bindings that apply to the next key, and if it goes into a text field
+    fn key_target(&self) -> (Option<KeyContext>, bool) {
+        let context = if self.help_overlay.is_open() {
+            KeyContext::HelpPopup
+        } else if self.error_overlay.is_open() {
+            KeyContext::ErrorPopup
+        } else if self.palette.is_open() {
+            return (Some(KeyContext::CommandPalette), true);
+        } else if self.completion_overlay.is_open() {
+            KeyContext::CompletionPopup
+        } else if self.binge_overlay.is_open() {
+            KeyContext::BingePopup
+        } else if self.source_overlay.is_open() {
+            KeyContext::SourcePopup
+        } else if self.cast_overlay.is_open() {
+            KeyContext::CastPopup
+        } else if self.episode_overlay.is_open() {
+            KeyContext::EpisodePopup
+        } else if self.overlay.is_open() {
+            KeyContext::AnimePopup
+        } else if self.navbar.is_selected() {
+            KeyContext::Navbar
+        } else {
+            return (self.current_screen.key_context(), self.current_screen.takes_text());
+        };
+        (Some(context), false)
+    }
+
+    // lists the keys that work where the focus is now
+    fn show_help(&mut self) {
+        let (context, typing) = self.key_target();
+        let on_screen = context == self.current_screen.key_context();
+        let mut actions = if on_screen {
+            self.current_screen.key_actions()
+        } else {
+            context.map(|context| context.actions().to_vec()).unwrap_or_default()
+        };
+        if on_screen && self.current_screen.should_store() {
+            actions.push(KeyAction::Refresh);
+        }
+        // the history works on screens and the anime, not in the popups above them
+        if on_screen || matches!(context, Some(KeyContext::Navbar | KeyContext::AnimePopup)) {
+            actions.extend([KeyAction::Back, KeyAction::Forward]);
+        }
+        actions.extend([KeyAction::Help, KeyAction::Quit]);
+
+        let config = Config::global();
+        let mut keys = Vec::new();
+        if typing {
+            keys.push(("letters".to_string(), "typed into the text field"));
+        }
+        keys.extend(config.keymap.help(&config.navigation, context, &actions));
+
+        let title = context.map(|context| context.title()).unwrap_or("Global");
+        let mouse = context.map(|context| context.mouse()).unwrap_or("");
+        self.help_overlay.open(title, keys, mouse);
+    }
+
+    // switches to the screen and hands it the update, eg. a season picked in the command palette
+    // a screen that isn't open yet gets it before its background starts, so it loads the right thing
+    pub fn open_with(&mut self, screen_name: &'static str, update: BackgroundUpdate) {
+        if self.current_screen.get_name() == screen_name {
+            self.current_screen.apply_update(update);
+            return;
+        }
+        let mut screen = self
+            .screen_storage
+            .remove(screen_name)
+            .unwrap_or_else(|| create_screen(screen_name, &self.passable_info));
+        screen.apply_update(update);
+        self.screen_storage.insert(screen_name.to_string(), screen);
+        self.change_screen(screen_name);
+    }
+
+    // where the user is now
+    fn place(&self) -> Place {
+        Place {
+            screen: self.current_screen.get_name(),
+            anime: self.overlay.is_open().then(|| self.overlay.anime_id()),
+        }
+    }
+
+    // adds the place to go back to, going somewhere new drops the way forward
+    // launch and login are only passed through, so they aren't kept
+    fn remember(&mut self, place: Place) {
+        self.forward.clear();
+        if place.screen == LAUNCH || place.screen == LOGIN || self.back.last() == Some(&place) {
+            return;
+        }
+        self.back.push(place);
+        if self.back.len() > HISTORY_LIMIT {
+            self.back.remove(0);
+        }
+    }
+
+    fn go_back(&mut self) {
+        if let Some(place) = self.back.pop() {
+            self.forward.push(self.place());
+            self.visit(place);
+        }
     }
 
-    // change screen stores the previous screen if not specified otherwise
+    fn go_forward(&mut self) {
+        if let Some(place) = self.forward.pop() {
+            self.back.push(self.place());
+            self.visit(place);
+        }
+    }
+
+    // goes to a place from the history without adding to it
+    fn visit(&mut self, place: Place) {
+        if self.current_screen.get_name() != place.screen {
+            self.switch_screen(&place.screen);
+        }
+        match place.anime {
+            Some(anime_id) => {
+                self.overlay.set_anime(anime_id);
+                self.overlay.open();
+            }
+            None => {
+                self.overlay.close();
+            }
+        }
+    }
+
+    // switching screens is remembered so back can return to it
+    // going to launch, eg. after logging out, starts the history over
+    pub fn change_screen(&mut self, screen_name: &str) {
+        if screen_name == LAUNCH {
+            self.back.clear();
+            self.forward.clear();
+        } else if self.current_screen.get_name() != screen_name {
+            self.remember(self.place());
+        }
+        self.switch_screen(screen_name);
+    }
+
+    // switch screen stores the previous screen if not specified otherwise
     // the current screen is removed from the storage if it exists, or created anew
     // this allows for screens to be swapped and their state to be preserved
-    pub fn change_screen(&mut self, screen_name: &str) {
+    fn switch_screen(&mut self, screen_name: &str) {
         if self.current_screen.should_store() {
             self.screen_storage.insert(
                 self.current_screen.get_name(),
@@ -294,6 +680,7 @@ impl ScreenManager {
         } else {
             self.current_screen = create_screen(screen_name, &self.passable_info);
         }
+        self.navbar.set_current(screen_name);
 
         self.cleanup_backgrounds();
         self.spawn_background();
@@ -358,3 +745,20 @@ impl BackgroundUpdate {
             .map(|boxed| *boxed)
     }
 }
+
+// keys that act on the highlighted anime of a list without opening its popup
+pub fn anime_key(key: &KeyInput, anime_id: AnimeId) -> Option<Action> {
+    match key.action? {
+        KeyAction::Play => Some(Action::PlayAnime(anime_id)),
+        KeyAction::PickEpisode => Some(Action::ShowEpisodes(anime_id)),
+        KeyAction::OpenInBrowser => open_anime_page(anime_id),
+        _ => None,
+    }
+}
+
+// opens the myanimelist page of the anime, only returns an action when it fails
+pub fn open_anime_page(anime_id: AnimeId) -> Option<Action> {
+    open::that(format!("https://myanimelist.net/anime/{}", anime_id))
+        .err()
+        .map(|e| Action::ShowError(format!("Failed to open anime page: {}", e)))
+}
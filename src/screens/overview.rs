@@ -5,14 +5,14 @@ use std::thread::{self, JoinHandle};
 
 use super::widgets::animebox::AnimeBox;
 use super::widgets::navigatable::Navigatable;
-use super::{BackgroundUpdate, ExtraInfo, Screen};
+use super::{BackgroundUpdate, ExtraInfo, Screen, anime_key};
 use crate::app::{Action, Event};
+use crate::config::keymap::{KeyAction, KeyContext, KeyInput};
 use crate::config::navigation::NavDirection;
 use crate::config::Config;
 use crate::mal::models::anime::AnimeId;
 use crate::utils::functionStreaming::StreamableRunner;
 use crate::utils::imageManager::ImageManager;
-use crossterm::event::KeyEvent;
 use indexmap::IndexSet;
 use ratatui::layout::{Margin, Rect};
 use ratatui::widgets::{Paragraph, Wrap};
@@ -83,6 +83,10 @@ impl OverviewScreen {
 impl Screen for OverviewScreen {
     // add_screen_caching!();
 
+    fn key_context(&self) -> Option<KeyContext> {
+        Some(KeyContext::Overview)
+    }
+
     fn draw(&mut self, frame: &mut Frame) {
         let area = frame.area();
         frame.render_widget(Clear, area);
@@ -181,22 +185,19 @@ impl Screen for OverviewScreen {
             });
     }
 
-    fn handle_keyboard(&mut self, key_event: KeyEvent) -> Option<Action> {
-        let modifier = key_event.modifiers.contains(crossterm::event::KeyModifiers::CONTROL);
-        let nav = &Config::global().navigation;
-
+    fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
         match self.focus {
             Focus::NavBar => {
                 self.focus = Focus::Content;
             }
 
             Focus::Content => {
-                if modifier && nav.get_direction(&key_event.code) == NavDirection::Up {
+                if key.focus_direction() == NavDirection::Up {
                     self.focus = Focus::NavBar;
                     return Some(Action::NavbarSelect(true));
                 }
 
-                match nav.get_direction(&key_event.code) {
+                match key.direction() {
                     NavDirection::Down => {
                         self.navigation.move_down();
                     }
@@ -216,12 +217,13 @@ impl Screen for OverviewScreen {
                     _ => {}
                 }
 
-                if nav.is_select(&key_event.code) {
-                    if let Some(selected) = self.navigation.get_selected_item_mut(&mut self.lists) {
-                        if let Some(anime_id) = selected.navigatable.get_selected_item(&selected.items) {
-                            return Some(Action::ShowOverlay(*anime_id));
-                        }
+                if let Some(selected) = self.navigation.get_selected_item_mut(&mut self.lists)
+                    && let Some(anime_id) = selected.navigatable.get_selected_item(&selected.items)
+                {
+                    if key.is(KeyAction::Select) {
+                        return Some(Action::ShowOverlay(*anime_id));
                     }
+                    return anime_key(&key, *anime_id);
                 }
             }
         }
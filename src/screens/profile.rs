@@ -5,6 +5,7 @@ use std::thread::JoinHandle;
 use crate::add_screen_caching;
 use crate::app::Action;
 use crate::app::Event;
+use crate::config::keymap::{KeyAction, KeyContext, KeyInput};
 use crate::config::navigation::NavDirection;
 use crate::config::Config;
 use crate::mal::models::anime::Anime;
@@ -20,7 +21,6 @@ use super::ExtraInfo;
 use super::Screen;
 use super::widgets::navigatable::Navigatable;
 
-use crossterm::event::KeyEvent;
 use ratatui::Frame;
 use ratatui::layout::Alignment;
 use ratatui::layout::Constraint;
@@ -71,6 +71,10 @@ impl ProfileScreen {
 impl Screen for ProfileScreen {
     add_screen_caching!();
 
+    fn key_context(&self) -> Option<KeyContext> {
+        Some(KeyContext::Profile)
+    }
+
     fn draw(&mut self, frame: &mut Frame) {
         let area = frame.area();
         frame.render_widget(widgets::Clear, area);
@@ -304,22 +308,18 @@ impl Screen for ProfileScreen {
 
     // handle inut function
     // for this spcific screen bro
-    fn handle_keyboard(&mut self, key_event: KeyEvent) -> Option<Action> {
-        let modifier = key_event.modifiers.contains(crossterm::event::KeyModifiers::CONTROL);
-        let nav = &Config::global().navigation;
-
+    fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
         match self.focus {
             Focus::NavBar => {
                 self.focus = Focus::Content;
             }
             Focus::Content => {
-                if modifier &&
-                nav.get_direction(&key_event.code) == NavDirection::Up {
+                if key.focus_direction() == NavDirection::Up {
                     self.focus = Focus::NavBar;
                     return Some(Action::NavbarSelect(true));
                 }
 
-                match nav.get_direction(&key_event.code) {
+                match key.direction() {
                     NavDirection::Down => {
                         self.navigation_fav.move_down();
                     }
@@ -335,7 +335,7 @@ impl Screen for ProfileScreen {
                     _ => {}
                 }
 
-                if nav.is_select(&key_event.code) {
+                if key.is(KeyAction::Select) {
                     if let Some(anime) = self
                         .navigation_fav
                         .get_selected_item_mut(&mut self.user.favorited_animes)
@@ -6,6 +6,7 @@ use crate::add_screen_caching;
 use crate::{app::Action, screens::Screen};
 use ratatui::layout::Constraint;
 use ratatui::layout::Direction;
+use crate::config::keymap::{KeyAction, KeyInput};
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyCode;
 use ratatui::widgets::Borders;
@@ -65,21 +66,21 @@ impl Screen for TempScreen {
 
 
     // returns an action based on the keyboard event that the app will act upon
-    fn handle_keyboard(&mut self, key_event: KeyEvent) -> Option<Action> {
+    fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
         todo!("Handle input for ListScreen");
         // code to handle inputs from user
         // ...
         // ...
         // ...
         // example:
-        // match key_event.code {
-        //     KeyCode::Up | KeyCode::Char('j') => {}
-        //     KeyCode::Down | KeyCode::Char('k') => {}
-        //     KeyCode::Left | KeyCode::Char('h') => {}
-        //     KeyCode::Right | KeyCode::Char('l') => {}
-        //     KeyCode::Enter => {}
-        //     _ => {} 
+        // match key.direction() {
+        //     NavDirection::Up => {}
+        //     NavDirection::Down => {}
+        //     NavDirection::Left => {}
+        //     NavDirection::Right => {}
+        //     _ => {}
         // };
+        // if key.is(KeyAction::Select) {}
     }
 
     fn background(&mut self) -> Option<std::thread::JoinHandle<()>> {
@@ -1,9 +1,10 @@
-use super::ExtraInfo;
+use super::{ExtraInfo, anime_key};
 use super::widgets::animebox::AnimeBox;
 use super::widgets::navigatable::Navigatable;
-use super::widgets::popup::{Arrows, SelectionPopup};
+use super::widgets::popups::{Arrows, SelectionPopup};
 use crate::add_screen_caching;
 use crate::app::Event;
+use crate::config::keymap::{KeyAction, KeyContext, KeyInput};
 use crate::config::navigation::NavDirection;
 use crate::config::Config;
 use crate::mal::models::anime::Anime;
@@ -12,7 +13,6 @@ use crate::utils::functionStreaming::StreamableRunner;
 use crate::utils::imageManager::ImageManager;
 use crate::utils::input::Input;
 use crate::{app::Action, screens::Screen};
-use crossterm::event::KeyEvent;
 use ratatui::Frame;
 use ratatui::layout::{Alignment, Position};
 use ratatui::layout::Constraint;
@@ -65,6 +65,22 @@ pub struct SearchScreen {
 }
 
 impl SearchScreen {
+    // the ranking type mal calls the filter by
+    fn ranking_type(filter: String) -> String {
+        if filter == "popularity" {
+            return "bypopularity".to_string();
+        }
+        filter
+    }
+
+    // fetches the ranking of the chosen filter
+    fn switch_filter(&mut self, filter_type: String) {
+        self.fetching = true;
+        if let Some(sender) = &self.bg_sender {
+            sender.send(LocalEvent::FilterSwitch(Self::ranking_type(filter_type))).ok();
+        }
+    }
+
     pub fn new(info: ExtraInfo) -> Self {
         Self {
             image_manager: Arc::new(Mutex::new(ImageManager::new())),
@@ -133,6 +149,28 @@ impl SearchScreen {
 impl Screen for SearchScreen {
     add_screen_caching!();
 
+    fn key_context(&self) -> Option<KeyContext> {
+        Some(KeyContext::Search)
+    }
+
+    fn takes_text(&self) -> bool {
+        self.focus == Focus::Search
+    }
+
+    fn key_actions(&self) -> Vec<KeyAction> {
+        use KeyAction::*;
+        match self.focus {
+            Focus::NavBar | Focus::Search => vec![FocusUp, FocusDown, FocusRight, NextFilter],
+            Focus::Filter => vec![
+                Up, Down, Select, Close, FocusUp, FocusDown, FocusLeft, FocusSearch, NextFilter,
+            ],
+            Focus::AnimeList => vec![
+                Up, Down, Left, Right, Select, Play, PickEpisode, OpenInBrowser,
+                FocusUp, FocusSearch, NextFilter,
+            ],
+        }
+    }
+
     fn draw(&mut self, frame: &mut Frame) {
         let area = frame.area();
         frame.render_widget(Clear, area);
@@ -230,14 +268,23 @@ impl Screen for SearchScreen {
             .render(frame, filter_area, self.focus == Focus::Filter);
     }
 
-    fn handle_keyboard(&mut self, key_event: KeyEvent) -> Option<Action> {
-        let modifier = key_event.modifiers.contains(crossterm::event::KeyModifiers::CONTROL);
-        let nav = &Config::global().navigation;
+    fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
+        if key.is(KeyAction::FocusSearch) {
+            self.filter_popup.close();
+            self.focus = Focus::Search;
+            return None;
+        }
+        if key.is(KeyAction::NextFilter) {
+            if let Some(filter_type) = self.filter_popup.select_next() {
+                self.switch_filter(filter_type);
+            }
+            return None;
+        }
 
         match self.focus {
             Focus::Filter => {
-                if modifier {
-                    match nav.get_direction(&key_event.code) {
+                if key.focus_direction() != NavDirection::None {
+                    match key.focus_direction() {
                         NavDirection::Down => {
                             self.focus = Focus::AnimeList;
                             self.filter_popup.close();
@@ -256,20 +303,14 @@ impl Screen for SearchScreen {
                     return None;
                 }
 
-                if let Some(mut filter_type) = self.filter_popup.handle_input(key_event) {
-                    self.fetching = true;
-                    if filter_type == "popularity" {
-                        filter_type = "bypopularity".to_string();
-                    }
-                    if let Some(sender) = &self.bg_sender {
-                        sender.send(LocalEvent::FilterSwitch(filter_type)).ok();
-                    }
+                if let Some(filter_type) = self.filter_popup.handle_input(key) {
+                    self.switch_filter(filter_type);
                 }
             }
 
             Focus::Search => {
-                if modifier {
-                    match nav.get_direction(&key_event.code) {
+                if key.focus_direction() != NavDirection::None {
+                    match key.focus_direction() {
                         NavDirection::Up => {
                             self.focus = Focus::NavBar;
                             return Some(Action::NavbarSelect(true));
@@ -286,7 +327,7 @@ impl Screen for SearchScreen {
                     }
                 }
 
-                if let Some(text) = self.search_input.handle_event(key_event, false) {
+                if let Some(text) = self.search_input.handle_event(key.event, false) {
                     if !text.is_empty() {
                         self.fetching = true;
                         if let Some(sender) = &self.bg_sender {
@@ -297,14 +338,14 @@ impl Screen for SearchScreen {
             }
 
             Focus::AnimeList => {
-                if modifier {
-                    if nav.get_direction(&key_event.code) == NavDirection::Up{
+                if key.focus_direction() != NavDirection::None {
+                    if key.focus_direction() == NavDirection::Up {
                         self.focus = Focus::Search;
                     }
                     return None;
                 }
 
-                match nav.get_direction(&key_event.code) {
+                match key.direction() {
                     NavDirection::Down => {
                         self.navigatable.move_down();
                     }
@@ -320,13 +361,16 @@ impl Screen for SearchScreen {
                     _ => {}
                 }
 
-                if nav.is_select(&key_event.code) {
+                if key.is(KeyAction::Select) {
                     if let Some(anime_id) = self.navigatable.get_selected_item(&self.animes) {
                         if let Some(anime) = self.app_info.anime_store.get(anime_id) {
                             return Some(Action::ShowOverlay(anime.id));
                         }
                     }
                 }
+                if let Some(anime_id) = self.navigatable.get_selected_item(&self.animes) {
+                    return anime_key(&key, *anime_id);
+                }
             }
 
             Focus::NavBar => {
@@ -347,15 +391,8 @@ impl Screen for SearchScreen {
             self.focus = Focus::Filter;
 
             // if a filter is selected:
-            let mut filter= self.filter_popup.handle_mouse(mouse_event)?;
-
-            self.fetching = true;
-            if filter == "popularity" {
-                filter = "bypopularity".to_string();
-            }
-
-            let sender = self.bg_sender.clone()?;
-            sender.send(LocalEvent::FilterSwitch(filter)).ok();
+            let filter = self.filter_popup.handle_mouse(mouse_event)?;
+            self.switch_filter(filter);
 
             return None;
         }
@@ -400,11 +437,15 @@ impl Screen for SearchScreen {
         ImageManager::init_with_threads(&image_manager, info.app_sx.clone());
         let mal_client = info.mal_client.clone();
         let app_sx = info.app_sx.clone();
+        // "all" unless one was picked in the command palette before the screen opened
+        let first_ranking = Self::ranking_type(
+            self.filter_popup.get_selected_option().unwrap_or("all".to_string()),
+        );
 
         let handle = std::thread::spawn(move || {
             if nr_of_animes == 0 {
                 Self::fetch_and_send_animes(&app_sx, id.clone(), |offset, limit| {
-                    mal_client.get_top_anime("all".to_string(), offset, limit)
+                    mal_client.get_top_anime(first_ranking.clone(), offset, limit)
                 });
             }
 
@@ -428,6 +469,12 @@ impl Screen for SearchScreen {
     }
 
     fn apply_update(&mut self, mut update: super::BackgroundUpdate) {
+        // a ranking picked in the command palette
+        if let Some(ranking) = update.take::<String>("ranking")
+            && let Some(ranking) = self.filter_popup.select_option(&ranking)
+        {
+            self.switch_filter(ranking);
+        }
         if let Some(ids) = update.take::<Vec<AnimeId>>("anime_ids") {
             if self.fetching {
                 self.reset();
@@ -1,8 +1,9 @@
-use super::ExtraInfo;
+use super::{ExtraInfo, anime_key};
 use super::widgets::navigatable::Navigatable;
-use super::widgets::popup::SeasonPopup;
+use super::widgets::popups::SeasonPopup;
 use super::{BackgroundUpdate, Screen};
 use crate::add_screen_caching;
+use crate::config::keymap::{KeyAction, KeyContext, KeyInput};
 use crate::config::Config;
 use crate::mal::models::anime::AnimeId;
 use crate::config::navigation::NavDirection;
@@ -15,7 +16,6 @@ use crate::{
         stringManipulation::DisplayString,
     },
 };
-use crossterm::event::KeyEvent;
 use ratatui::layout::{Alignment, Margin, Position, Rect};
 use ratatui::widgets::{Padding, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap};
 use ratatui::{
@@ -138,6 +138,7 @@ impl SeasonsScreen {
             let anime_ids = animes.iter().map(|a| a.id).collect::<Vec<_>>();
 
             let update = BackgroundUpdate::new(id.clone())
+                .set("season_of", (year, season.clone()))
                 .set("animes", animes)
                 .set("anime_ids", anime_ids)
                 .set("fetching", false)
@@ -150,6 +151,10 @@ impl SeasonsScreen {
 impl Screen for SeasonsScreen {
     add_screen_caching!();
 
+    fn key_context(&self) -> Option<KeyContext> {
+        Some(KeyContext::Seasons)
+    }
+
     fn draw(&mut self, frame: &mut Frame) {
         let mut anime = Anime::empty();
         if let Some(selected_anime) = self.navigatable.get_selected_item(&self.animes) {
@@ -463,10 +468,7 @@ impl Screen for SeasonsScreen {
         self.season_popup.render(frame, bl_top);
     }
 
-    fn handle_keyboard(&mut self, key_event: KeyEvent) -> Option<Action> {
-        let modifier = key_event.modifiers.contains(crossterm::event::KeyModifiers::CONTROL);
-        let nav = &Config::global().navigation;
-
+    fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
         match self.focus {
             // this focus is used just to not highligh anything in the screen
             // and when the navbar gets deselcted this handle_input will run once right after
@@ -476,8 +478,8 @@ impl Screen for SeasonsScreen {
             }
 
             Focus::AnimeList => {
-                if modifier { 
-                    match nav.get_direction(&key_event.code) {
+                if key.focus_direction() != NavDirection::None {
+                    match key.focus_direction() {
                         NavDirection::Up => {
                             self.focus = Focus::SeasonSelection;
                         }
@@ -489,7 +491,7 @@ impl Screen for SeasonsScreen {
                     return None; 
                 }
 
-                match nav.get_direction(&key_event.code) {
+                match key.direction() {
                     NavDirection::Up => {
                         self.navigatable.move_up();
                     }
@@ -505,21 +507,26 @@ impl Screen for SeasonsScreen {
                     _ => {}
                 };
 
-                if nav.is_select(&key_event.code) {
+                if key.is(KeyAction::Select) {
                     if let Some(id) = self.navigatable.get_selected_item(&self.animes) {
                         if let Some(anime) = self.app_info.anime_store.get(id) {
                             return Some(Action::ShowOverlay(anime.id));
                         }
                     }
                 }
+                if let Some(id) = self.navigatable.get_selected_item(&self.animes)
+                    && let Some(action) = anime_key(&key, *id)
+                {
+                    return Some(action);
+                }
 
                 self.detail_scroll_y = 0;
                 self.detail_scroll_x = 0;
             }
 
             Focus::AnimeDetails => {
-                if modifier {
-                    match nav.get_direction(&key_event.code) {
+                if key.focus_direction() != NavDirection::None {
+                    match key.focus_direction() {
                         NavDirection::Up => {
                             self.focus = Focus::Navbar;
                             return Some(Action::NavbarSelect(true));
@@ -532,7 +539,7 @@ impl Screen for SeasonsScreen {
                     return None;
                 }
 
-                match nav.get_direction(&key_event.code) {
+                match key.direction() {
                     NavDirection::Down => {
                         self.detail_scroll_y += 1;
                     }
@@ -550,8 +557,8 @@ impl Screen for SeasonsScreen {
             }
 
             Focus::SeasonSelection => {
-                if modifier {
-                    match nav.get_direction(&key_event.code) {
+                if key.focus_direction() != NavDirection::None {
+                    match key.focus_direction() {
                         NavDirection::Up => {
                             self.focus = Focus::Navbar;
                             return Some(Action::NavbarSelect(true));
@@ -569,7 +576,7 @@ impl Screen for SeasonsScreen {
                     return None;
                 }
 
-                if let Some((year, season)) = self.season_popup.handle_keyboard(key_event) {
+                if let Some((year, season)) = self.season_popup.handle_keyboard(key) {
                     if year == self.year && season == self.season {
                         return None;
                     }
@@ -654,12 +661,13 @@ impl Screen for SeasonsScreen {
         let info = self.app_info.clone();
         let nr_of_animes = self.animes.len();
         let manager = self.image_manager.clone();
+        // the current season, unless one was picked in the command palette before the screen opened
+        let (year, season) = (self.year, self.season.clone());
 
         ImageManager::init_with_threads(&manager, info.app_sx.clone());
 
         Some(thread::spawn(move || {
             if nr_of_animes == 0 {
-                let (year, season) = MalClient::current_season();
                 Self::fetch_anime_season(year, season, &info.app_sx, &info.mal_client, id.clone());
             }
 
@@ -680,6 +688,21 @@ impl Screen for SeasonsScreen {
     }
 
     fn apply_update(&mut self, mut update: BackgroundUpdate) {
+        // a season picked in the command palette
+        if let Some((year, season)) = update.take::<(u16, String)>("season") {
+            self.season_popup.select(year, &season);
+            if year != self.year || !season.eq_ignore_ascii_case(&self.season) {
+                self.year = year;
+                self.season = season;
+                self.fetch_season();
+            }
+        }
+        // a season still loading when another was picked sends its animes anyway
+        if let Some((year, season)) = update.take::<(u16, String)>("season_of")
+            && (year != self.year || !season.eq_ignore_ascii_case(&self.season))
+        {
+            return;
+        }
         if let Some(animes) = update.take::<Vec<AnimeId>>("anime_ids") {
             self.animes.extend(animes);
         }
@@ -1,100 +1,539 @@
-use super::{screens::*, ExtraInfo, Screen};
-use crate::screens::widgets::button::Button;
+use super::{ExtraInfo, Screen};
+use crate::add_screen_caching;
 use crate::app::Action;
-use ratatui::{
-    layout::{Constraint, Direction, Layout }, widgets::{ Block, Borders, Clear}, Frame 
-};
-use crossterm::event::{KeyCode, KeyEvent};
+use crate::config::keymap::{KeyAction, KeyContext, KeyInput};
+use crate::config::Config;
+use crate::config::navigation::NavDirection;
+use crate::config::overrides;
+use crate::utils::input::Input;
+use crossterm::event::{KeyCode, MouseEvent, MouseEventKind};
+use ratatui::Frame;
+use ratatui::layout::{Margin, Position, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use toml::Value;
 
+// width of the key column
+const KEY_WIDTH: usize = 26;
+
+// how a setting is edited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Keys,
+    Color,
+    Toggle,
+    Text,
+    OptionalText,
+    Number,
+    OptionalNumber,
+    List,
+}
+
+const FIELDS: [(&str, Kind); 37] = [
+    ("navigation.nav_up", Kind::Keys),
+    ("navigation.nav_down", Kind::Keys),
+    ("navigation.nav_left", Kind::Keys),
+    ("navigation.nav_right", Kind::Keys),
+    ("navigation.select", Kind::Keys),
+    ("navigation.close", Kind::Keys),
+    ("navigation.download", Kind::Keys),
+    ("navigation.playlist", Kind::Keys),
+    ("navigation.enable_mouse_capture", Kind::Toggle),
+    ("network.auth_server", Kind::Text),
+    ("network.callback_port", Kind::Number),
+    ("network.max_port_retries", Kind::Number),
+    ("player.disable_default_player", Kind::Toggle),
+    ("player.always_complete_episode", Kind::Toggle),
+    ("player.completion_threshold", Kind::Number),
+    ("player.completion_seconds_left", Kind::OptionalNumber),
+    ("player.confirm_completion", Kind::Toggle),
+    ("player.binge_mode", Kind::Toggle),
+    ("player.binge_countdown", Kind::Number),
+    ("player.subtitle_language", Kind::OptionalText),
+    ("player.prefetch_next", Kind::Toggle),
+    ("player.stream_cache_minutes", Kind::Number),
+    ("player.providers", Kind::List),
+    ("player.pre_playback_hook", Kind::OptionalText),
+    ("player.post_playback_hook", Kind::OptionalText),
+    ("theme.primary", Kind::Color),
+    ("theme.secondary", Kind::Color),
+    ("theme.highlight", Kind::Color),
+    ("theme.second_highlight", Kind::Color),
+    ("theme.error", Kind::Color),
+    ("theme.text", Kind::Color),
+    ("theme.second_text", Kind::Color),
+    ("theme.watching", Kind::Color),
+    ("theme.completed", Kind::Color),
+    ("theme.on_hold", Kind::Color),
+    ("theme.dropped", Kind::Color),
+    ("theme.plan_to_watch", Kind::Color),
+];
+
+// offered by the color picker, anything else can be typed as #rrggbb
+const COLORS: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    NavBar,
+    Content,
+}
 
 #[derive(Clone)]
-pub struct SettingsScreen { 
-    settings: Vec<&'static str>,
-    buttons: Vec<&'static str>,
-    selected_button: usize,
+enum Editing {
+    None,
+    // the next key pressed is added to the setting, or removed when it is there already
+    Key,
+    // index into COLORS, the one after the last is a typed color
+    Color(usize),
+    Text(Input),
+}
+
+#[derive(Clone)]
+pub struct SettingsScreen {
+    selected: usize,
+    scroll: usize,
+    focus: Focus,
+    editing: Editing,
+    // rows drawn last time, for the mouse
+    row_areas: Vec<(usize, Rect)>,
 }
 
 impl SettingsScreen {
     pub fn new(_info: ExtraInfo) -> Self {
         Self {
-            settings: vec![
-                "settings 1",
-                "settings 2",
-                "settings 3",
-                "settings 4",
-                "settings 5",
-            ],
+            selected: 0,
+            scroll: 0,
+            focus: Focus::NavBar,
+            editing: Editing::None,
+            row_areas: Vec::new(),
+        }
+    }
 
-            buttons: vec![
-                "Back",
-                "Exit"
-            ],
+    // starts editing the selected setting, toggles are changed right away
+    fn activate(&mut self) -> Option<Action> {
+        let (key, kind) = FIELDS[self.selected];
+        if let Some(var) = overrides::env_var_for(key) {
+            return Some(Action::ShowError(format!(
+                "{} is set by ${}, change the variable instead",
+                key, var
+            )));
+        }
+
+        let value = current(key);
+        match kind {
+            Kind::Toggle => {
+                let enabled = value.and_then(|v| v.as_bool()).unwrap_or(false);
+                return save(key, Some(Value::Boolean(!enabled)));
+            }
+            Kind::Keys => self.editing = Editing::Key,
+            Kind::Color => {
+                let index = value
+                    .and_then(|v| v.as_str().map(str::to_string))
+                    .and_then(|name| COLORS.iter().position(|c| c.to_string() == name))
+                    .unwrap_or(COLORS.len());
+                self.editing = Editing::Color(index);
+            }
+            _ => {
+                let text = text_of(kind, value.as_ref());
+                self.editing = Editing::Text(Input::new().with_value(&text));
+            }
+        }
+        None
+    }
+
+    // the key pressed while capturing is added or removed
+    fn toggle_key(&mut self, code: KeyCode) -> Option<Action> {
+        let (key, _) = FIELDS[self.selected];
+        let mut keys: Vec<KeyCode> = current(key)
+            .and_then(|value| value.try_into().ok())
+            .unwrap_or_default();
+
+        if let Some(index) = keys.iter().position(|k| *k == code) {
+            if keys.len() == 1 {
+                return Some(Action::ShowError(format!("{} needs at least one key", key)));
+            }
+            keys.remove(index);
+        } else {
+            keys.push(code);
+        }
 
-            selected_button: 0,
+        match Value::try_from(keys) {
+            Ok(value) => save(key, Some(value)),
+            Err(e) => Some(Action::ShowError(format!("{}: {}", key, e))),
         }
     }
+
+    fn move_selection(&mut self, direction: NavDirection) {
+        match direction {
+            NavDirection::Up => self.selected = self.selected.saturating_sub(1),
+            NavDirection::Down => self.selected = (self.selected + 1).min(FIELDS.len() - 1),
+            _ => {}
+        }
+    }
+
+    fn draw_color_picker(&self, frame: &mut Frame, area: Rect, index: usize) {
+        let theme = &Config::global().theme;
+        let height = (COLORS.len() as u16 + 3).min(area.height);
+        let width = 26.min(area.width);
+        let picker = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, picker);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols::border::ROUNDED)
+            .title("Color")
+            .border_style(Style::default().fg(theme.highlight));
+        let inner = block.inner(picker);
+        frame.render_widget(block, picker);
+
+        let mut lines: Vec<Line> = COLORS
+            .iter()
+            .enumerate()
+            .map(|(i, color)| {
+                let marker = if i == index { "> " } else { "  " };
+                Line::from(vec![
+                    Span::styled(marker, Style::default().fg(theme.highlight)),
+                    Span::styled("██ ", Style::default().fg(*color)),
+                    Span::styled(color.to_string(), Style::default().fg(theme.text)),
+                ])
+            })
+            .collect();
+        let marker = if index == COLORS.len() { "> " } else { "  " };
+        lines.push(Line::from(vec![
+            Span::styled(marker, Style::default().fg(theme.highlight)),
+            Span::styled("Custom (#rrggbb)", Style::default().fg(theme.text)),
+        ]));
+
+        // keeps the selected color in view on small terminals
+        let skip = (index + 1).saturating_sub(inner.height as usize);
+        let lines: Vec<Line> = lines.into_iter().skip(skip).collect();
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
 }
 
 impl Screen for SettingsScreen {
+    add_screen_caching!();
+
+    fn key_context(&self) -> Option<KeyContext> {
+        Some(KeyContext::Settings)
+    }
+
+    // keys being captured go into the setting as they are
+    fn takes_text(&self) -> bool {
+        matches!(self.editing, Editing::Key | Editing::Text(_))
+    }
+
+    fn key_actions(&self) -> Vec<KeyAction> {
+        match self.editing {
+            Editing::None => KeyContext::Settings.actions().to_vec(),
+            Editing::Color(_) => vec![KeyAction::Up, KeyAction::Down, KeyAction::Select, KeyAction::Close],
+            Editing::Key | Editing::Text(_) => Vec::new(),
+        }
+    }
+
     fn draw(&mut self, frame: &mut Frame) {
         let area = frame.area();
-
         frame.render_widget(Clear, area);
+        let config = Config::global();
+        let theme = &config.theme;
 
-        let area = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(
-                [
-                    Constraint::Percentage(20),
-                    Constraint::Percentage(20),
-                    Constraint::Percentage(20),
-                    Constraint::Percentage(20), 
-                    Constraint::Percentage(20),
-                ]
-                .as_ref(),
-            )
-            .split(area);
-
-        for (i, anime) in self.settings.iter().enumerate() {
-            let block = Block::default()
-                .title(format!("{}: {}", i, anime))
-                .borders(Borders::ALL);
-            frame.render_widget(block, area[i]);
-        }
-
-        for (i, button) in self.buttons.iter().enumerate() {
-                Button::new(button)
-                .center()
-                .selected(i == self.selected_button)
-                .offset((0, 3*i as i16))
-                .render(frame, frame.area());
+        let bottom = Rect::new(area.x, area.y + 3, area.width, area.height.saturating_sub(3));
+        let context = Some(KeyContext::Settings);
+        let select = config.keymap.hint(&config.navigation, context, KeyAction::Select);
+        let close = config.keymap.hint(&config.navigation, context, KeyAction::Close);
+        let hint = match self.editing {
+            Editing::None => format!(" [{}] edit ", select),
+            Editing::Key => " press a key to add it, or remove it when it is there already ".to_string(),
+            Editing::Color(_) => format!(" [{}] pick  [{}] cancel ", select, close),
+            Editing::Text(_) => " [enter] save  [esc] cancel ".to_string(),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols::border::ROUNDED)
+            .title("Settings")
+            .title_bottom(hint)
+            .border_style(Style::default().fg(theme.primary));
+        frame.render_widget(block, bottom);
+
+        let list_area = bottom.inner(Margin::new(2, 1));
+        if list_area.height == 0 {
+            return;
         }
 
+        // section headers are lines without a setting
+        let mut lines: Vec<(Option<usize>, Line)> = Vec::new();
+        let effective = Value::try_from(&*Config::global()).ok();
+        for (i, (key, kind)) in FIELDS.iter().enumerate() {
+            let (section, name) = key.split_once('.').unwrap_or(("", key));
+            if i == 0 || !FIELDS[i - 1].0.starts_with(&format!("{}.", section)) {
+                if i != 0 {
+                    lines.push((None, Line::from("")));
+                }
+                lines.push((
+                    None,
+                    Line::styled(
+                        title_case(section),
+                        Style::default()
+                            .fg(theme.second_highlight)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                ));
+            }
+
+            let selected = i == self.selected && self.focus == Focus::Content;
+            let value = effective
+                .as_ref()
+                .and_then(|effective| overrides::lookup_value(effective, key));
+            let shown = match (&self.editing, selected) {
+                (Editing::Text(input), true) => input.value().to_string(),
+                (Editing::Key, true) => format!("{}  <press a key>", display(*kind, value)),
+                _ => display(*kind, value),
+            };
+
+            let name_color = if selected { theme.highlight } else { theme.text };
+            let value_color = match (kind, value.and_then(|v| v.as_str())) {
+                (Kind::Color, Some(name)) => name.parse().unwrap_or(theme.text),
+                _ => theme.second_text,
+            };
+            let mut spans = vec![
+                Span::styled(
+                    format!("{:<KEY_WIDTH$}", name.replace('_', " ")),
+                    Style::default().fg(name_color),
+                ),
+                Span::styled(shown, Style::default().fg(value_color)),
+            ];
+            if let Some(var) = overrides::env_var_for(key) {
+                spans.push(Span::styled(
+                    format!("  (set by ${})", var),
+                    Style::default().fg(theme.primary),
+                ));
+            }
+            lines.push((Some(i), Line::from(spans)));
+        }
+
+        // scrolls so the selected setting, and its header when it is the first, stays in view
+        let visible = list_area.height as usize;
+        let selected_line = lines
+            .iter()
+            .position(|(index, _)| *index == Some(self.selected))
+            .unwrap_or(0);
+        let first_in_section = if selected_line >= 1 && lines[selected_line - 1].0.is_none() {
+            selected_line - 1
+        } else {
+            selected_line
+        };
+        if first_in_section < self.scroll {
+            self.scroll = first_in_section;
+        } else if selected_line >= self.scroll + visible {
+            self.scroll = selected_line + 1 - visible;
+        }
+
+        self.row_areas.clear();
+        for (offset, (index, line)) in lines.into_iter().skip(self.scroll).take(visible).enumerate() {
+            let row = Rect::new(list_area.x, list_area.y + offset as u16, list_area.width, 1);
+            frame.render_widget(line, row);
+            if let Some(index) = index {
+                self.row_areas.push((index, row));
+                if let (Editing::Text(input), true) = (&self.editing, index == self.selected) {
+                    input.render_cursor(frame, row.x + KEY_WIDTH as u16, row.y, true);
+                }
+            }
+        }
+
+        if let Editing::Color(index) = self.editing {
+            self.draw_color_picker(frame, bottom, index);
+        }
     }
 
-    fn handle_keyboard(&mut self, key_event: KeyEvent) -> Option<Action> {
-        match key_event.code {
-            KeyCode::Up | KeyCode::Char('j') => {
-                if self.selected_button > 0 {
-                    self.selected_button -= 1;
+    fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
+        if self.focus == Focus::NavBar {
+            self.focus = Focus::Content;
+            return None;
+        }
+
+        match &mut self.editing {
+            Editing::Key => {
+                self.editing = Editing::None;
+                self.toggle_key(key.event.code)
+            }
+            Editing::Color(index) => {
+                match key.direction() {
+                    NavDirection::Up => *index = index.saturating_sub(1),
+                    NavDirection::Down => *index = (*index + 1).min(COLORS.len()),
+                    _ => {}
                 }
+
+                if key.is(KeyAction::Select) {
+                    let (key, _) = FIELDS[self.selected];
+                    if let Some(color) = COLORS.get(*index) {
+                        self.editing = Editing::None;
+                        return save(key, Some(Value::String(color.to_string())));
+                    }
+                    let text = text_of(Kind::Color, current(key).as_ref());
+                    self.editing = Editing::Text(Input::new().with_value(&text));
+                } else if key.is(KeyAction::Close) {
+                    self.editing = Editing::None;
+                }
+                None
             }
-            KeyCode::Down | KeyCode::Char('k') => {
-                if self.selected_button < self.buttons.len() - 1 {
-                    self.selected_button += 1;
+            Editing::Text(input) => {
+                match key.event.code {
+                    KeyCode::Esc => self.editing = Editing::None,
+                    // an empty value is allowed, it unsets optional settings
+                    KeyCode::Enter => {
+                        let text = input.value().to_string();
+                        self.editing = Editing::None;
+                        let (key, kind) = FIELDS[self.selected];
+                        return match parse(kind, &text) {
+                            Ok(value) => save(key, value),
+                            Err(e) => Some(Action::ShowError(format!("{}: {}", key, e))),
+                        };
+                    }
+                    _ => {
+                        input.handle_event(key.event, false);
+                    }
                 }
+                None
             }
-            KeyCode::Enter => {
-                match self.selected_button {
-                    0 => return Some(Action::SwitchScreen(OVERVIEW)),
-                    1 => return Some(Action::Quit),
-                    _ => {}
+            Editing::None => {
+                if key.focus_direction() == NavDirection::Up {
+                    self.focus = Focus::NavBar;
+                    return Some(Action::NavbarSelect(true));
                 }
+
+                self.move_selection(key.direction());
+                if key.is(KeyAction::Select) {
+                    return self.activate();
+                }
+                None
             }
-            _ => {} 
-        };
+        }
+    }
+
+    fn handle_mouse(&mut self, mouse_event: MouseEvent) -> Option<Action> {
+        if mouse_event.row < 3 {
+            self.focus = Focus::NavBar;
+            return Some(Action::NavbarSelect(true));
+        }
+        if !matches!(self.editing, Editing::None) {
+            return None;
+        }
+
+        match mouse_event.kind {
+            MouseEventKind::ScrollUp => self.move_selection(NavDirection::Up),
+            MouseEventKind::ScrollDown => self.move_selection(NavDirection::Down),
+            MouseEventKind::Down(_) => {
+                let pos = Position::new(mouse_event.column, mouse_event.row);
+                let clicked = self.row_areas.iter().find(|(_, area)| area.contains(pos))?.0;
+                self.focus = Focus::Content;
+                self.selected = clicked;
+                return self.activate();
+            }
+            _ => {}
+        }
         None
     }
 }
 
+// the value in use, None for optional settings that aren't set
+fn current(key: &str) -> Option<Value> {
+    let effective = Value::try_from(&*Config::global()).ok()?;
+    overrides::lookup_value(&effective, key).cloned()
+}
+
+// writes the change and has the app pick up the new config
+fn save(key: &str, value: Option<Value>) -> Option<Action> {
+    match Config::set_key(key, value) {
+        Ok(()) => Some(Action::ReloadConfig),
+        Err(e) => Some(Action::ShowError(format!("{}: {}", key, e))),
+    }
+}
+
+fn display(kind: Kind, value: Option<&Value>) -> String {
+    match (kind, value) {
+        (_, None) => "-".to_string(),
+        (Kind::Keys, Some(value)) => value
+            .clone()
+            .try_into::<Vec<KeyCode>>()
+            .map(|keys| {
+                keys.iter()
+                    .map(|k| k.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            })
+            .unwrap_or_default(),
+        (Kind::Toggle, Some(value)) => match value.as_bool() {
+            Some(true) => "on".to_string(),
+            _ => "off".to_string(),
+        },
+        (_, Some(value)) => text_of(kind, Some(value)),
+    }
+}
 
+// the value as it is typed in
+fn text_of(kind: Kind, value: Option<&Value>) -> String {
+    match (kind, value) {
+        (_, Some(Value::String(text))) => text.clone(),
+        (Kind::List, Some(Value::Array(items))) => items
+            .iter()
+            .filter_map(|item| item.as_str())
+            .collect::<Vec<&str>>()
+            .join(", "),
+        (_, Some(value)) => value.to_string(),
+        (_, None) => String::new(),
+    }
+}
+
+fn parse(kind: Kind, text: &str) -> Result<Option<Value>, String> {
+    let text = text.trim();
+    let number = || {
+        text.parse::<i64>()
+            .map(|n| Some(Value::Integer(n)))
+            .map_err(|_| "has to be a whole number".to_string())
+    };
+    match kind {
+        Kind::OptionalText | Kind::OptionalNumber if text.is_empty() => Ok(None),
+        Kind::Number | Kind::OptionalNumber => number(),
+        Kind::List => Ok(Some(Value::Array(
+            text.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| Value::String(item.to_string()))
+                .collect(),
+        ))),
+        _ => Ok(Some(Value::String(text.to_string()))),
+    }
+}
+
+// "navigation" -> "Navigation"
+fn title_case(text: &str) -> String {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
@@ -1,7 +1,7 @@
 pub mod button;
 pub mod animebox;
 pub mod navbar;
-pub mod popup;
+pub mod popups;
 pub mod navigatable;
 pub mod printable;
 pub mod infobox;
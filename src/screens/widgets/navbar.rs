@@ -3,7 +3,8 @@ use crate::{
     config::{navigation::NavDirection, Config},
     screens::{name_to_screen, screen_to_name},
 };
-use crossterm::event::{KeyEvent, MouseEvent};
+use crate::config::keymap::{KeyAction, KeyInput};
+use crossterm::event::MouseEvent;
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Margin, Position, Rect},
@@ -48,21 +49,27 @@ impl NavBar {
         self
     }
 
+    // marks the tab of a screen switched to some other way than the navbar
+    pub fn set_current(&mut self, screen: &str) {
+        let name = screen_to_name(screen);
+        if let Some(index) = self.options.iter().position(|option| *option == name) {
+            self.old_button = index;
+            self.selected_button = index;
+        }
+    }
+
     pub fn add_screen(mut self, screen: &'static str) -> Self {
         self.options.push(screen_to_name(screen));
         self
     }
 
-    pub fn handle_keyboard(&mut self, key_event: KeyEvent) -> Option<Action> {
-        let modifier = key_event.modifiers.contains(crossterm::event::KeyModifiers::CONTROL);
-        let nav = &Config::global().navigation;
-
-        if modifier && nav.get_direction(&key_event.code) == NavDirection::Down {
+    pub fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
+        if key.focus_direction() == NavDirection::Down {
             self.deselect();
             return Some(Action::NavbarSelect(false));
         }
 
-        match nav.get_direction(&key_event.code) {
+        match key.direction() {
             NavDirection::Left => {
                 if self.selected_button > 0 {
                     self.selected_button = self.selected_button.saturating_sub(1);
@@ -76,7 +83,7 @@ impl NavBar {
             _ => {}
         }
 
-        if nav.is_select(&key_event.code) {
+        if key.is(KeyAction::Select) {
             if self.options.is_empty() {
                 return None;
             }
@@ -0,0 +1,886 @@
+use super::selection::{Arrows, SelectionColor, SelectionPopup};
+use crate::app::{Action, Event};
+use crate::config::Config;
+use crate::config::keymap::{KeyAction, KeyInput};
+use crate::config::navigation::NavDirection;
+use crate::mal::models::anime::{Anime, AnimeId, DeleteOrUpdate, MyListStatus, status_is_known};
+use crate::player::{self, skip::SkipPreferences};
+use crate::screens::widgets::{infobox::InfoBox, navigatable::Navigatable};
+use crate::screens::{BackgroundUpdate, ExtraInfo, open_anime_page};
+use crate::utils::{
+    hooks::{self, HookPayload},
+    imageManager::ImageManager,
+    stringManipulation::{DisplayString, format_date},
+    terminalCapabilities::TERMINAL_RATIO,
+};
+use crossterm::event::{MouseEvent, MouseEventKind};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Position, Rect},
+    style::{Color, Style, Stylize},
+    symbols::{self, border},
+    widgets::{
+        Block, Borders, Clear, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState,
+        Wrap,
+    },
+};
+use std::cmp::min;
+use std::sync::{
+    Arc, Mutex,
+    mpsc::{Receiver, Sender},
+};
+use std::thread::JoinHandle;
+use tui_widgets::big_text::{BigText, PixelSize};
+
+const RATIO: f32 = 422.0 / 598.0;
+
+#[derive(PartialEq, Clone, Debug)]
+enum Focus {
+    PlayButtons,
+    StatusButtons,
+    Synopsis,
+}
+
+// #[derive(PartialEq, Clone, Debug)]
+enum LocalEvent {
+    UserChoice(usize, Anime, MyListStatus),
+    ExtraInfo(Anime),
+}
+
+#[derive(Clone)]
+pub struct AnimePopup {
+    untogglable: bool,
+    anime_id: AnimeId,
+    toggled: bool,
+    buttons: Vec<String>,
+    button_nav: Navigatable,
+    status_buttons: Vec<SelectionPopup>,
+    status_nav: Navigatable,
+    image_manager: Arc<Mutex<ImageManager>>,
+    focus: Focus,
+    background_transmitter: Sender<LocalEvent>,
+    app_info: ExtraInfo,
+    synopsis_scroll: u16,
+
+    //cache
+    popup_area: Option<Rect>,
+    synopsis_area: Option<Rect>,
+}
+
+impl AnimePopup {
+    pub fn new(info: ExtraInfo) -> Self {
+        let buttons = vec![
+            "Play".to_string(),
+            "Episodes".to_string(),
+            "Play from start".to_string(),
+            "Open".to_string(),
+            "Re-match source".to_string(),
+            "Auto skip: off".to_string(),
+            "Cast".to_string(),
+        ];
+        let image_manager = Arc::new(Mutex::new(ImageManager::new()));
+        let (tx, rx) = std::sync::mpsc::channel::<LocalEvent>();
+
+        ImageManager::init_with_threads(&image_manager, info.app_sx.clone());
+
+        let popup = Self {
+            untogglable: false,
+            app_info: info.clone(),
+            image_manager,
+            anime_id: AnimeId::default(),
+            toggled: false,
+            button_nav: Navigatable::new((buttons.len() as u16, 1)),
+            status_nav: Navigatable::new((1, 3)),
+            status_buttons: Vec::new(),
+            buttons,
+            focus: Focus::PlayButtons,
+            background_transmitter: tx,
+            synopsis_scroll: 0,
+            popup_area: None,
+            synopsis_area: None,
+        };
+        popup.spawn_background(info, rx);
+        popup
+    }
+
+    fn spawn_background(
+        &self,
+        info: ExtraInfo,
+        reveicer: Receiver<LocalEvent>,
+    ) -> Option<JoinHandle<()>> {
+        let mal_client = info.mal_client.clone();
+        let app_sx = info.app_sx.clone();
+        Some(std::thread::spawn(move || {
+            while let Ok(event) = reveicer.recv() {
+                match event {
+                    // send any userchoice to the mal backend
+                    LocalEvent::UserChoice(index, anime, previous) => {
+                        match info.mal_client.update_user_list(anime.clone()) {
+                            Ok(result) => {
+                                hooks::fire(
+                                    &Config::global().hooks.on_status_change,
+                                    HookPayload::new("status_change")
+                                        .anime(&anime)
+                                        .previous_status(&previous),
+                                );
+
+                                let update = BackgroundUpdate::new("popup")
+                                    .set("success", (index, result.clone()));
+                                info.app_sx.send(Event::BackgroundNotice(update)).ok();
+                            }
+                            Err(e) => {
+                                info.app_sx
+                                    .send(Event::BackgroundNotice(
+                                        BackgroundUpdate::new("popup")
+                                            .set("failure", (index, e.to_string())),
+                                    ))
+                                    .ok();
+                            }
+                        }
+                    }
+
+                    // update the number of released episodes
+                    LocalEvent::ExtraInfo(anime) => {
+                        let available_episodes =
+                            mal_client.get_available_episodes(anime.id).unwrap_or(None);
+                        if let Some(episodes) = available_episodes {
+                            app_sx
+                                .send(Event::StorageUpdate(
+                                    anime.id,
+                                    Box::new(move |anime: &mut Anime| {
+                                        anime.num_released_episodes = Some(episodes);
+                                        if anime.num_episodes == 0 {
+                                            anime.num_episodes = episodes;
+                                            anime.episode_count_ready = false;
+                                        }
+                                    }),
+                                ))
+                                .unwrap();
+                        }
+                    }
+                }
+            }
+        }))
+    }
+
+    // TODO: then this is not needed
+    pub fn apply_update(&mut self, mut update: BackgroundUpdate) {
+        if let Some((index, (_, update))) =
+            update.take::<(usize, (usize, DeleteOrUpdate))>("success")
+        {
+            self.app_info.anime_store.update(self.anime_id, |anime| {
+                anime.my_list_status = match update {
+                    DeleteOrUpdate::Deleted(_vec) => MyListStatus::default(),
+                    DeleteOrUpdate::Updated(status) => status,
+                }
+            });
+
+            if let Some(button) = self
+                .status_nav
+                .get_item_at_index_mut(&mut self.status_buttons, index)
+            {
+                button.set_color(SelectionColor::Status);
+            }
+        }
+
+        if let Some(index) = update.take::<usize>("failure")
+            && let Some(button) = self
+                .status_nav
+                .get_item_at_index_mut(&mut self.status_buttons, index)
+        {
+            button.set_color(SelectionColor::Error);
+        }
+
+        self.update_buttons();
+    }
+
+    pub fn set_play_button_episode(&mut self, episode: Option<u32>) -> &Self {
+        // if an anime is given set the button to its episode
+        if let Some(episode) = episode {
+            self.buttons[0] = format!("Play ▶ (EP {})", episode);
+            return self;
+        }
+
+        // if no aniem is set use the current anime of the popup
+        let anime = self
+            .app_info
+            .anime_store
+            .get(&self.anime_id)
+            .expect("(buttons) unexpected anime id given");
+
+        // if the anime has no episodes set the button to "no episodes"
+        if anime.num_episodes == 0 {
+            self.buttons[0] = "No episodes".to_string();
+
+        // noraml case
+        } else {
+            let episode = (anime.my_list_status.num_episodes_watched + 1).min(anime.num_episodes);
+            self.buttons[0] = if player::local_episode(&anime, &episode.to_string()).is_some() {
+                format!("Play local ▶ (EP {})", episode)
+            } else {
+                format!("Play ▶ (EP {})", episode)
+            };
+        }
+
+        // if the anime has released episodes and the next episode to play is higher than the available episodes
+        if let Some(available_episodes) = anime.num_released_episodes {
+            let episode_to_play =
+                (anime.my_list_status.num_episodes_watched + 1).min(anime.num_episodes);
+            if episode_to_play > available_episodes {
+                self.buttons[0] = format!("Try to play (EP {})", episode_to_play)
+            }
+        }
+        self
+    }
+    fn set_skip_button(&mut self) {
+        let auto_skip = SkipPreferences::load().auto_skip(self.anime_id);
+        self.buttons[5] = format!("Auto skip: {}", if auto_skip { "on" } else { "off" });
+    }
+
+    pub fn update_buttons(&mut self) -> &Self {
+        let anime = match self.app_info.anime_store.get(&self.anime_id) {
+            Some(anime) => anime,
+            None => {
+                return self;
+            }
+        };
+
+        self.set_play_button_episode(None);
+        self.set_skip_button();
+        let episode_options: Vec<String> = (0..=anime.num_episodes.max(1))
+            .map(|i| i.to_string())
+            .collect();
+
+        self.status_buttons = vec![
+            SelectionPopup::new()
+                .add_option("Add to list")
+                .add_option("Watching")
+                .add_option("Plan to watch")
+                .add_option("Completed")
+                .add_option("On Hold")
+                .add_option("Dropped")
+                .with_color(SelectionColor::Status)
+                .with_arrows(Arrows::Static)
+                .with_selected_option(anime.my_list_status.status.to_string())
+                .clone(),
+            SelectionPopup::new()
+                .add_option("Not rated")
+                .add_options(vec!["1", "2", "3", "4", "5", "6", "7", "8", "9", "10"])
+                .with_arrows(Arrows::Static)
+                .with_selected_option(anime.my_list_status.score.to_string())
+                .clone(),
+            SelectionPopup::new()
+                .add_options(episode_options)
+                .with_arrows(Arrows::Static)
+                .with_selected_option(anime.my_list_status.num_episodes_watched.to_string())
+                .with_displaying_format(format!("{{}} / {}", anime.num_episodes))
+                .clone(),
+        ];
+        self
+    }
+
+    pub fn set_anime(&mut self, anime_id: AnimeId) -> &Self {
+        self.anime_id = anime_id;
+        let anime = match self.app_info.anime_store.get(&self.anime_id) {
+            Some(anime) => {
+                self.untogglable = false;
+                anime
+            }
+            None => {
+                self.untogglable = true;
+                return self;
+            }
+        };
+
+        self.update_buttons();
+        if anime.num_released_episodes.is_none() {
+            self.background_transmitter
+                .send(LocalEvent::ExtraInfo((*anime).clone()))
+                .ok();
+        }
+        self
+    }
+
+    pub fn anime_id(&self) -> AnimeId {
+        self.anime_id
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.toggled
+    }
+
+    pub fn open(&mut self) -> &Self {
+        if !self.untogglable {
+            self.toggled = true;
+        }
+        self
+    }
+
+    pub fn close(&mut self) -> &Self {
+        self.toggled = false;
+        self
+    }
+
+    pub fn update_status(&mut self, selection: String, index: usize) {
+        let mut anime = (*self
+            .app_info
+            .anime_store
+            .get(&self.anime_id)
+            .expect("(Focus) unexpected anime id given"))
+        .clone();
+        let previous = anime.my_list_status.clone();
+
+        match index {
+            0 => {
+                anime.my_list_status.status =
+                    selection.to_lowercase().replace(" ", "_");
+            }
+            1 => {
+                anime.my_list_status.score = selection.parse().unwrap_or(0);
+            }
+            2 => {
+                anime.my_list_status.num_episodes_watched =
+                    selection.parse().unwrap_or(0);
+                if !status_is_known(anime.my_list_status.status.clone())
+                    && anime.my_list_status.num_episodes_watched == 0
+                {
+                    return;
+                } else if !status_is_known(
+                    anime.my_list_status.status.clone(),
+                ) {
+                    anime.my_list_status.status = "watching".to_string();
+                }
+            }
+            _ => return,
+        }
+
+        self.background_transmitter
+            .send(LocalEvent::UserChoice(index, anime.clone(), previous))
+            .ok();
+
+        self.set_play_button_episode(Some(
+            (anime.my_list_status.num_episodes_watched + 1)
+                .min(anime.num_episodes),
+        ));
+    }
+
+    fn status_buttons_open(&self) -> bool {
+        self.status_buttons.iter().any(|button| button.is_open())
+    }
+
+    // runs the play button at index, the same as selecting it
+    fn press_button(&mut self, index: usize) -> Option<Action> {
+        match index {
+            0 => {
+                // play normally
+                return Some(Action::PlayAnime(self.anime_id));
+            }
+            1 => {
+                // play a specific episode
+                return Some(Action::ShowEpisodes(self.anime_id));
+            }
+
+            2 => {
+                // play from start
+                return Some(Action::PlayEpisode(self.anime_id, "1".to_string()));
+            }
+            3 => {
+                // open the anime page in the browser
+                return open_anime_page(self.anime_id);
+            }
+            4 => {
+                // pick which show on the source this anime is played from
+                return Some(Action::RematchSource(self.anime_id));
+            }
+            5 => {
+                // jump over the opening and ending of this anime
+                let mut preferences = SkipPreferences::load();
+                preferences.toggle(self.anime_id);
+                if let Err(e) = preferences.save() {
+                    return Some(Action::ShowError(e));
+                }
+                self.set_skip_button();
+            }
+            6 => {
+                // play the next episode on a tv or another renderer
+                return Some(Action::ShowCast(self.anime_id));
+            }
+            _ => {}
+        }
+        None
+    }
+
+    pub fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
+        // the play buttons have their own keys wherever the focus is
+        let shortcut = match key.action {
+            Some(KeyAction::Play) => Some(0),
+            Some(KeyAction::PickEpisode) => Some(1),
+            Some(KeyAction::PlayFromStart) => Some(2),
+            Some(KeyAction::OpenInBrowser) => Some(3),
+            Some(KeyAction::ChangeSource) => Some(4),
+            Some(KeyAction::ToggleSkip) => Some(5),
+            Some(KeyAction::Cast) => Some(6),
+            _ => None,
+        };
+        if let Some(index) = shortcut
+            && !self.status_buttons_open()
+        {
+            return self.press_button(index);
+        }
+
+        match self.focus {
+            Focus::PlayButtons => {
+                match key.direction() {
+                    NavDirection::Down => {
+                        self.button_nav.move_down();
+                    }
+                    NavDirection::Up => {
+                        if self.button_nav.get_selected_index() == 0 {
+                            self.focus = Focus::StatusButtons;
+                        }
+                        self.button_nav.move_up();
+                    }
+                    NavDirection::Left => {
+                        self.focus = Focus::Synopsis;
+                    }
+                    _ => {}
+                }
+
+                if key.is(KeyAction::Select) {
+                    return self.press_button(self.button_nav.get_selected_index());
+                }
+            }
+            Focus::StatusButtons => {
+                if let Some((dropdown, index)) = self
+                    .status_nav
+                    .get_selected_item_mut_and_index(&mut self.status_buttons)
+                {
+                    match (dropdown.is_open(), key.direction()) {
+                        (false, NavDirection::Right) => {
+                            self.status_nav.move_right();
+                            return None;
+                        }
+                        (false, NavDirection::Left) => {
+                            if self.status_nav.get_selected_index() == 0 {
+                                self.focus = Focus::Synopsis;
+                            }
+                            self.status_nav.move_left();
+                            return None;
+                        }
+                        (false, NavDirection::Down) => {
+                            self.focus = Focus::PlayButtons;
+                            if let Some(button) = self
+                                .status_nav
+                                .get_selected_item_mut(&mut self.status_buttons)
+                            {
+                                button.close();
+                            }
+                        }
+                        _ => {
+                            if let Some(selection) = dropdown.handle_input(key) {
+                                dropdown.set_color(Color::White);
+                                self.update_status(selection, index);
+                                return None;
+                            }
+                            if key.is(KeyAction::Close){
+                                return None;
+                            }
+                        }
+                    }
+                }
+            }
+
+            Focus::Synopsis => match key.direction() {
+                NavDirection::Down => {
+                    self.synopsis_scroll = min(
+                        self.synopsis_scroll + 1,
+                        self.app_info
+                            .anime_store
+                            .get(&self.anime_id)
+                            .unwrap()
+                            .synopsis
+                            .len() as u16,
+                    );
+                }
+                NavDirection::Up => {
+                    self.synopsis_scroll = self.synopsis_scroll.saturating_sub(1);
+                }
+                NavDirection::Right => {
+                    self.focus = Focus::PlayButtons;
+                }
+                NavDirection::Left => {
+                    self.focus = Focus::StatusButtons;
+                }
+                _ => {}
+            },
+        }
+
+        if key.is(KeyAction::Close) {
+            self.close();
+        }
+
+        None
+    }
+
+    pub fn handle_mouse(&mut self, mouse_event: MouseEvent) -> Option<Action> {
+        let p_area = self.popup_area?;
+        let pos = Position::new(mouse_event.column, mouse_event.row);
+        let is_click = matches!(mouse_event.kind, MouseEventKind::Down(_));
+
+
+        // the status buttons
+        let dropdown = match self
+            .status_nav
+            .get_selected_item_mut(&mut self.status_buttons){
+            Some(d) if d.is_open() => Some(d),
+            _ => self.status_nav.get_hovered_item_mut(&mut self.status_buttons, mouse_event)
+        };
+
+        if let Some(dropdown) = dropdown {
+            self.focus = Focus::StatusButtons;
+            if let Some(selection) = dropdown.handle_mouse(mouse_event){
+                dropdown.set_color(Color::White);
+                let index = self.status_nav.get_selected_index();
+                self.update_status(selection, index);
+            };
+            return None;
+        }
+
+
+        // the synopsis area
+        if let Some(s_area) = self.synopsis_area {
+            if s_area.contains(pos) {
+                self.focus = Focus::Synopsis;
+                match mouse_event.kind {
+                    MouseEventKind::ScrollUp => {
+                        self.synopsis_scroll = self.synopsis_scroll.saturating_sub(1);
+                    }
+                    MouseEventKind::ScrollDown => {
+                        self.synopsis_scroll += 1;
+                    }
+                    _ => {}
+                }
+                return None;
+            }
+        }
+
+
+        // close the whole popup
+        if is_click && !p_area.contains(pos) {
+            self.close();
+            return None;
+        }
+
+
+        // the play buttons 
+        if self.button_nav.get_hovered_index(mouse_event).is_some() {
+            self.focus = Focus::PlayButtons;
+            if is_click {
+                return self.handle_keyboard(KeyInput::from_action(KeyAction::Select));
+            }
+            return None;
+        };
+
+        None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame) {
+        if !self.toggled {
+            return;
+        }
+
+        let anime = self
+            .app_info
+            .anime_store
+            .get(&self.anime_id)
+            .expect("(render) unexpected anime id given");
+
+        let area = frame.area();
+
+        let [height, width] = [area.height * 8 / 10, area.width * 7 / 10];
+        let popup_area = Rect::new(
+            area.x + (area.width.saturating_sub(width)) / 2,
+            area.y + (area.height.saturating_sub(height)) / 2,
+            width,
+            height,
+        );
+
+        self.popup_area = Some(popup_area);
+
+        // clear the space for the popup
+        frame.render_widget(Clear, popup_area);
+
+        // craete the border arond the whole popup
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_set(border::ROUNDED)
+            .style(Style::default().fg(Config::global().theme.secondary));
+        frame.render_widget(block, popup_area);
+
+        // split the popup up so we can get the area for the bottons ont he right side
+        let [left, right] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Fill(1), Constraint::Percentage(30)])
+            .areas(popup_area);
+        //buttons area, one line per button so the cover and synopsis keep their room
+        let [_, bottom_area] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Fill(1),
+                Constraint::Length(self.buttons.len() as u16 + 2),
+            ])
+            .areas(right);
+
+        // now create borders that makes the top and left connect to the rest
+        let (right_set, right_border) = (
+            symbols::border::Set {
+                bottom_left: symbols::line::ROUNDED_BOTTOM_RIGHT,
+                top_right: symbols::line::ROUNDED_BOTTOM_RIGHT,
+                ..symbols::border::ROUNDED
+            },
+            Borders::ALL,
+        );
+        let right_block = Block::default()
+            .borders(right_border)
+            .border_set(right_set)
+            .style(Style::default().fg(Config::global().theme.secondary));
+        let buttons_area = bottom_area.inner(Margin::new(1, 1));
+        frame.render_widget(right_block, bottom_area);
+
+        // add the buttons
+        self.button_nav
+            .construct(&self.buttons, buttons_area, |button, area, highlighted| {
+                let button_paragraph = Paragraph::new(button.to_string())
+                    .alignment(Alignment::Center)
+                    .style(Style::default().fg(
+                        if highlighted && self.focus == Focus::PlayButtons {
+                            Config::global().theme.highlight
+                        } else {
+                           Config::global().theme.secondary 
+                        },
+                    ));
+                frame.render_widget(button_paragraph, area);
+            });
+
+        // the rest of the popup
+        // the image
+        let image_height = bottom_area.y.saturating_sub(popup_area.y).saturating_sub(3);
+        let image_width = (image_height as f32 * RATIO * TERMINAL_RATIO) as u16;
+        let image_area = Rect {
+            x: popup_area.x + 4,
+            y: popup_area.y + 2,
+            width: image_width,
+            height: image_height,
+        };
+
+        ImageManager::render_image(&self.image_manager, anime.as_ref(), frame, image_area, true);
+
+        //title and info area
+        let [title_area, info_area] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Fill(1)])
+            .areas(popup_area.inner(Margin::new(1, 1)));
+        let title_area_x = image_area.x + image_area.width + 3;
+        let title_area = Rect {
+            x: title_area_x,
+            y: title_area.y,
+            width: popup_area.x
+                + popup_area
+                    .width
+                    .saturating_sub(title_area_x)
+                    .saturating_sub(2),
+            height: title_area.height,
+        };
+        let info_area = Rect {
+            x: title_area.x,
+            y: info_area.y,
+            width: title_area.width-1,
+            height: info_area.height.saturating_sub(buttons_area.height),
+        };
+
+        let title = if anime.alternative_titles.en.is_empty() {
+            anime.title.clone()
+        } else {
+            anime.alternative_titles.en.clone()
+        };
+
+        let title_text = Paragraph::new(title)
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Config::global().theme.secondary).bold());
+
+        frame.render_widget(title_text, title_area.inner(Margin::new(0, 1)));
+
+        //synopsis
+        // FIXME: this needs fixing
+        let synopsis_area = Rect {
+            x: left.x + 1,
+            y: bottom_area.y,
+            width: left.width.saturating_sub(1),
+            height: bottom_area.height.saturating_sub(1),
+        };
+
+        self.synopsis_area = Some(synopsis_area);
+
+        // Calculate the content height for scrollbar
+        let content_height = anime.synopsis.lines().count() as u16;
+        let visible_height = synopsis_area.height.saturating_sub(2); // Account for borders
+
+        // Create the paragraph widget
+        let synopsis_text = Paragraph::new(anime.synopsis.clone())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_set(border::ROUNDED)
+                    .title("Synopsis")
+                    .style(Style::default().fg(if self.focus == Focus::Synopsis {
+                        Config::global().theme.highlight
+                    } else {
+                        Config::global().theme.primary
+                    })),
+            )
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(Config::global().theme.text))
+            .scroll((self.synopsis_scroll, 0));
+
+        // Render the paragraph
+        frame.render_widget(synopsis_text, synopsis_area);
+
+        // FIXME: above this needs fixing
+        // Create and render scrollbar if content is longer than visible area
+        if content_height > visible_height {
+            let scrollbar = Scrollbar::default()
+                .orientation(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(Some("↑"))
+                .end_symbol(Some("↓"))
+                .track_symbol(Some("│"))
+                .thumb_symbol("█")
+                .style(Style::default().fg(if self.focus == Focus::Synopsis {
+                    Config::global().theme.highlight
+                } else {
+                    Config::global().theme.primary
+                }));
+
+            let mut scrollbar_state = ScrollbarState::new(content_height as usize)
+                .position(self.synopsis_scroll as usize);
+
+            frame.render_stateful_widget(
+                scrollbar,
+                synopsis_area.inner(Margin {
+                    vertical: 1,
+                    horizontal: 0,
+                }), // Position scrollbar inside borders
+                &mut scrollbar_state,
+            );
+        }
+
+        // right side next to image and above buttons
+        let [_score, info_area, _buttons] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(0),
+                Constraint::Fill(1),
+                Constraint::Length(3),
+            ])
+            .areas(info_area);
+
+        // score text
+        let big_text = BigText::builder()
+            .style(Style::default().fg(Color::White))
+            .pixel_size(PixelSize::Sextant)
+            .lines(vec![anime.mean.to_string().into()])
+            .build();
+        let [_, big_area_vertical] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Fill(1), Constraint::Length(5)])
+            .areas(info_area);
+        let [_, big_text_area] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Fill(1), Constraint::Length(20)])
+            .areas(big_area_vertical);
+
+        // info area
+        let [_, info_area_one, _, info_area_two] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Length(8),
+                Constraint::Length(2),
+                Constraint::Length(8),
+            ])
+            .areas(info_area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_set(border::ROUNDED)
+            .title("Anime Info")
+            .style(Style::default().fg(Config::global().theme.primary));
+
+        frame.render_widget(block, info_area);
+        frame.render_widget(big_text, big_text_area);
+
+        let startseason = DisplayString::new()
+            .add(anime.start_season.to_string())
+            .uppercase(0)
+            .build("{0}");
+
+        InfoBox::new()
+            .add_ranked_item("Ranked", anime.rank.to_string())
+            .add_ranked_item("Popularity", anime.popularity.to_string())
+            .add_text_item("Members", anime.num_list_users.to_string())
+            .add_row()
+            .add_text_item("Start", startseason)
+            .add_text_item("type", anime.media_type.to_string())
+            .add_text_item("studio", anime.studios_as_string())
+            .add_row()
+            .add_text_item(
+                "Episodes",
+                format!(
+                    "{}/{}",
+                    anime.num_released_episodes.unwrap_or(0),
+                    if anime.episode_count_ready {
+                        anime.num_episodes.to_string()
+                    } else {
+                        "?".to_string()
+                    }
+                ),
+            )
+            .add_text_item("Duration", anime.average_episode_duration.to_string())
+            .add_text_item("Rating", anime.rating.to_string())
+            .add_row()
+            .add_text_item("Status", anime.status.to_string())
+            .add_text_item("Source", anime.source.to_string())
+            .add_text_item("Id", anime.id.to_string())
+            .render(frame, info_area_one, Margin::new(8, 0), Config::global().theme.primary);
+
+        InfoBox::new()
+            .add_text_item("Added", format_date(&anime.created_at))
+            .add_row()
+            .add_text_item("Updated", format_date(&anime.updated_at))
+            .add_row()
+            .add_text_item("Started", format_date(&anime.start_date))
+            .add_row()
+            .add_text_item("Ended", format_date(&anime.end_date))
+            .render(frame, info_area_two, Margin::new(8, 0), Config::global().theme.primary);
+
+        // buttons within info area
+        let status_buttons_area = Rect {
+            x: _buttons.x + (_buttons.width / 10),
+            y: _buttons.y,
+            width: _buttons.width * 8 / 10,
+            height: 3,
+        };
+
+        self.status_nav.construct_mut(
+            &mut self.status_buttons,
+            status_buttons_area,
+            |dropdown, area, highlighted| {
+                dropdown.render(
+                    frame,
+                    area,
+                    highlighted && self.focus == Focus::StatusButtons,
+                );
+            },
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use super::{PopupState, bordered, centered};
+use crate::app::{Action, Event};
+use crate::config::Config;
+use crate::config::keymap::{KeyAction, KeyContext, KeyInput};
+use crate::mal::models::anime::{Anime, AnimeId};
+use crate::screens::{BackgroundUpdate, ExtraInfo};
+use crossterm::event::{MouseEvent, MouseEventKind};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Margin},
+    style::Style,
+    widgets::{Paragraph, Wrap},
+};
+
+#[derive(Clone, Debug)]
+enum BingeState {
+    Countdown { next_episode: u32, remaining: u64 },
+    SearchingSequel,
+    Sequel(AnimeId, String),
+    NoSequel,
+}
+
+#[derive(Clone)]
+pub struct BingePopup {
+    popup: PopupState,
+    anime_id: AnimeId,
+    title: String,
+    state: BingeState,
+    // ticks and lookups from an earlier binge are ignored
+    generation: u64,
+    app_info: ExtraInfo,
+}
+
+impl BingePopup {
+    pub fn new(info: ExtraInfo) -> Self {
+        Self {
+            popup: PopupState::default(),
+            anime_id: AnimeId::default(),
+            title: String::new(),
+            state: BingeState::NoSequel,
+            generation: 0,
+            app_info: info,
+        }
+    }
+
+    pub fn start_countdown(&mut self, anime: &Anime, next_episode: u32) {
+        let seconds = Config::global().player.binge_countdown;
+        self.open(anime, BingeState::Countdown { next_episode, remaining: seconds });
+
+        let generation = self.generation;
+        let app_sx = self.app_info.app_sx.clone();
+        std::thread::spawn(move || {
+            // without a countdown the next episode starts right away
+            if seconds == 0 {
+                let update = BackgroundUpdate::new("binge_popup").set("tick", (generation, 0u64));
+                app_sx.send(Event::BackgroundNotice(update)).ok();
+                return;
+            }
+            for remaining in (0..seconds).rev() {
+                std::thread::sleep(std::time::Duration::from_secs(1));
+                let update = BackgroundUpdate::new("binge_popup")
+                    .set("tick", (generation, remaining));
+                if app_sx.send(Event::BackgroundNotice(update)).is_err() {
+                    return;
+                }
+            }
+        });
+    }
+
+    // the season is done, look for a sequel to continue with
+    pub fn offer_sequel(&mut self, anime: &Anime) {
+        self.open(anime, BingeState::SearchingSequel);
+
+        let generation = self.generation;
+        let anime_id = anime.id;
+        let info = self.app_info.clone();
+        std::thread::spawn(move || {
+            let sequel = info
+                .mal_client
+                .get_anime_details(anime_id)
+                .and_then(|details| details.related_anime)
+                .and_then(|related| related.into_iter().find(|r| r.relation_type == "sequel"))
+                .and_then(|sequel| info.mal_client.get_anime_details(sequel.node.id as AnimeId));
+
+            let update = match sequel {
+                Some(sequel) => BackgroundUpdate::new("binge_popup")
+                    .set("sequel", (generation, sequel.id, sequel.title.clone()))
+                    .set("animes", vec![sequel]),
+                None => BackgroundUpdate::new("binge_popup").set("no_sequel", generation),
+            };
+            info.app_sx.send(Event::BackgroundNotice(update)).ok();
+        });
+    }
+
+    fn open(&mut self, anime: &Anime, state: BingeState) {
+        self.generation += 1;
+        self.anime_id = anime.id;
+        self.title = if anime.alternative_titles.en.is_empty() || anime.alternative_titles.en == "N/A" {
+            anime.title.clone()
+        } else {
+            anime.alternative_titles.en.clone()
+        };
+        self.state = state;
+        self.popup.open();
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.popup.is_open()
+    }
+
+    pub fn close(&mut self) {
+        self.generation += 1;
+        self.popup.close();
+    }
+
+    pub fn apply_update(&mut self, mut update: BackgroundUpdate) -> Option<Action> {
+        if let Some((generation, remaining)) = update.take::<(u64, u64)>("tick")
+            && generation == self.generation
+            && let BingeState::Countdown { next_episode, .. } = self.state
+        {
+            self.state = BingeState::Countdown { next_episode, remaining };
+            if remaining == 0 {
+                return self.confirm();
+            }
+        }
+
+        if let Some((generation, sequel_id, title)) =
+            update.take::<(u64, AnimeId, String)>("sequel")
+            && generation == self.generation
+        {
+            self.state = BingeState::Sequel(sequel_id, title);
+        }
+
+        if let Some(generation) = update.take::<u64>("no_sequel")
+            && generation == self.generation
+        {
+            self.state = BingeState::NoSequel;
+        }
+
+        None
+    }
+
+    fn confirm(&mut self) -> Option<Action> {
+        let action = match &self.state {
+            BingeState::Countdown { next_episode, .. } => {
+                Some(Action::PlayEpisode(self.anime_id, next_episode.to_string()))
+            }
+            BingeState::Sequel(sequel_id, _) => {
+                Some(Action::PlayEpisode(*sequel_id, "1".to_string()))
+            }
+            BingeState::SearchingSequel => return None,
+            BingeState::NoSequel => None,
+        };
+        self.close();
+        action
+    }
+
+    pub fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
+        if key.is(KeyAction::Select) {
+            return self.confirm();
+        }
+
+        if key.is(KeyAction::Close) {
+            self.close();
+        }
+
+        None
+    }
+
+    pub fn handle_mouse(&mut self, mouse_event: MouseEvent) -> Option<Action> {
+        if let MouseEventKind::Down(_) = mouse_event.kind {
+            if self.popup.contains(mouse_event) {
+                return self.confirm();
+            }
+            self.close();
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame) {
+        if !self.popup.is_open() {
+            return;
+        }
+
+        let config = Config::global();
+        let context = Some(KeyContext::BingePopup);
+        let select = config.keymap.hint(&config.navigation, context, KeyAction::Select);
+        let close = config.keymap.hint(&config.navigation, context, KeyAction::Close);
+
+        let text = match &self.state {
+            BingeState::Countdown { next_episode, remaining } => format!(
+                "{}\n\nEpisode {} starts in {}s\n\n[{}] play now   [{}] stop",
+                self.title, next_episode, remaining, select, close
+            ),
+            BingeState::SearchingSequel => {
+                format!("{}\n\nSeason complete!\n\nLooking for a sequel...", self.title)
+            }
+            BingeState::Sequel(_, sequel) => format!(
+                "{}\n\nSeason complete! Continue with\n\"{}\"?\n\n[{}] play   [{}] stop",
+                self.title, sequel, select, close
+            ),
+            BingeState::NoSequel => {
+                format!("{}\n\nSeason complete!\n\nNo sequel found", self.title)
+            }
+        };
+
+        let theme = &config.theme;
+        let area = centered(frame.area(), 50, 9);
+        let popup_area = self.popup.draw(frame, area, bordered("Binge", theme.highlight));
+
+        let paragraph = Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(theme.text));
+        frame.render_widget(paragraph, popup_area.inner(Margin::new(1, 1)));
+    }
+}
@@ -0,0 +1,250 @@
+use super::{PopupState, bordered, centered};
+use crate::app::{Action, Event};
+use crate::config::Config;
+use crate::config::keymap::{KeyAction, KeyInput};
+use crate::config::navigation::NavDirection;
+use crate::mal::models::anime::{Anime, AnimeId};
+use crate::player::{self, AnimePlayer, PlayResult, PlaybackPosition, cast::Renderer};
+use crate::screens::widgets::navigatable::Navigatable;
+use crate::screens::{BackgroundUpdate, ExtraInfo};
+use crate::send_error;
+use crossterm::event::{MouseEvent, MouseEventKind};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Margin},
+    style::Style,
+    widgets::{Paragraph, Wrap},
+};
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+    mpsc::{Receiver, Sender},
+};
+use std::thread::JoinHandle;
+
+// finds renderers and casts to them in the background
+enum CastEvent {
+    Discover,
+    Cast(Box<Anime>, String, Renderer),
+}
+
+#[derive(Clone)]
+enum CastState {
+    Searching,
+    Picking(Vec<Renderer>),
+    // the renderer and how far it got
+    Casting(String, PlaybackPosition),
+}
+
+pub struct CastPopup {
+    popup: PopupState,
+    state: CastState,
+    anime: Option<Anime>,
+    episode: String,
+    nav: Navigatable,
+    // set to stop the running cast
+    stop: Arc<AtomicBool>,
+    background_transmitter: Sender<CastEvent>,
+}
+
+impl CastPopup {
+    pub fn new(info: ExtraInfo) -> Self {
+        let (tx, rx) = std::sync::mpsc::channel::<CastEvent>();
+        let popup = Self {
+            popup: PopupState::default(),
+            state: CastState::Searching,
+            anime: None,
+            episode: String::new(),
+            nav: Navigatable::new((10, 1)),
+            stop: Arc::new(AtomicBool::new(false)),
+            background_transmitter: tx,
+        };
+        popup.spawn_background(info, rx);
+        popup
+    }
+
+    fn spawn_background(&self, info: ExtraInfo, receiver: Receiver<CastEvent>) -> JoinHandle<()> {
+        let stop = self.stop.clone();
+        std::thread::spawn(move || {
+            let player = AnimePlayer::new();
+            while let Ok(event) = receiver.recv() {
+                let update = match event {
+                    CastEvent::Discover => match player::cast::discover() {
+                        Ok(renderers) => BackgroundUpdate::new("cast_popup").set("renderers", renderers),
+                        Err(e) => BackgroundUpdate::new("cast_popup").set("failure", e),
+                    },
+                    CastEvent::Cast(anime, episode, renderer) => {
+                        stop.store(false, Ordering::SeqCst);
+                        let sender = info.app_sx.clone();
+                        let result = player.cast_episode(&anime, &episode, &renderer, &stop, |position| {
+                            let update = BackgroundUpdate::new("cast_popup").set("position", position);
+                            sender.send(Event::BackgroundNotice(update)).ok();
+                        });
+                        match result {
+                            Ok(details) => BackgroundUpdate::new("cast_popup").set("finished", (anime.id, details)),
+                            Err(e) => BackgroundUpdate::new("cast_popup").set("failure", e.to_string()),
+                        }
+                    }
+                };
+                info.app_sx.send(Event::BackgroundNotice(update)).ok();
+            }
+        })
+    }
+
+    // returns the finished episode so the list can be updated
+    pub fn apply_update(&mut self, mut update: BackgroundUpdate) -> Option<Action> {
+        if let Some(renderers) = update.take::<Vec<Renderer>>("renderers")
+            && matches!(self.state, CastState::Searching)
+        {
+            self.state = CastState::Picking(renderers);
+            self.nav.back_to_start();
+        }
+
+        if let Some(position) = update.take::<PlaybackPosition>("position")
+            && let CastState::Casting(_, current) = &mut self.state
+        {
+            *current = position;
+        }
+
+        if let Some(message) = update.take::<String>("failure") {
+            self.close();
+            self.state = CastState::Searching;
+            send_error!("Casting failed:\n{}", message);
+        }
+
+        if let Some((anime_id, details)) = update.take::<(AnimeId, PlayResult)>("finished") {
+            self.close();
+            self.state = CastState::Searching;
+            return Some(Action::CastFinished(anime_id, details));
+        }
+
+        None
+    }
+
+    // shows the running cast, or searches for renderers to cast the next episode to
+    pub fn open(&mut self, anime: &Anime) {
+        self.popup.open();
+        if matches!(self.state, CastState::Casting(..)) {
+            return;
+        }
+
+        let episode = std::cmp::min(
+            anime.my_list_status.num_episodes_watched + 1,
+            anime.num_episodes,
+        )
+        .max(1);
+        self.anime = Some(anime.clone());
+        self.episode = episode.to_string();
+        self.state = CastState::Searching;
+        self.background_transmitter.send(CastEvent::Discover).ok();
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.popup.is_open()
+    }
+
+    // a running cast keeps going in the background
+    pub fn close(&mut self) {
+        self.popup.close();
+    }
+
+    fn select(&mut self) {
+        match &self.state {
+            CastState::Picking(renderers) => {
+                let (Some(renderer), Some(anime)) = (self.nav.get_selected_item(renderers), &self.anime) else {
+                    return;
+                };
+                self.background_transmitter
+                    .send(CastEvent::Cast(Box::new(anime.clone()), self.episode.clone(), renderer.clone()))
+                    .ok();
+                self.state = CastState::Casting(renderer.name.clone(), PlaybackPosition::default());
+            }
+            CastState::Casting(..) => {
+                self.stop.store(true, Ordering::SeqCst);
+            }
+            CastState::Searching => {}
+        }
+    }
+
+    pub fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
+        match key.direction() {
+            NavDirection::Up => self.nav.move_up(),
+            NavDirection::Down => self.nav.move_down(),
+            _ => {}
+        }
+
+        if key.is(KeyAction::Select) {
+            self.select();
+        }
+
+        if key.is(KeyAction::Close) {
+            self.close();
+        }
+
+        None
+    }
+
+    pub fn handle_mouse(&mut self, mouse_event: MouseEvent) -> Option<Action> {
+        if self.popup.clicked_outside(mouse_event) {
+            self.close();
+            return None;
+        }
+        let is_click = matches!(mouse_event.kind, MouseEventKind::Down(_));
+
+        if self.nav.is_hovered(mouse_event) {
+            self.nav.handle_scroll(mouse_event);
+        }
+
+        let hovered = self.nav.get_hovered_index(mouse_event).is_some();
+        if is_click && (hovered || matches!(self.state, CastState::Casting(..))) {
+            self.select();
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame) {
+        if !self.popup.is_open() {
+            return;
+        }
+        let theme = &Config::global().theme;
+
+        let area = centered(frame.area(), 70, self.nav.visible_elements() as u16 + 4);
+        let title = self.anime.as_ref().map(|a| a.title.as_str()).unwrap_or_default();
+        let title = format!("Cast \"{}\" episode {}", title, self.episode);
+        let popup_area = self.popup.draw(frame, area, bordered(title, theme.secondary));
+
+        let list_area = popup_area.inner(Margin::new(2, 2));
+
+        let message = match &self.state {
+            CastState::Searching => "Looking for renderers...".to_string(),
+            CastState::Picking(renderers) if renderers.is_empty() => "No renderers found".to_string(),
+            CastState::Casting(renderer, position) => {
+                let details = position.result(&self.episode, false);
+                format!(
+                    "Casting to {}\n\n{} / {}\n\nPress select to stop",
+                    renderer, details.current_time, details.total_time
+                )
+            }
+            CastState::Picking(renderers) => {
+                self.nav.construct(renderers, list_area, |renderer, area, highlighted| {
+                    let paragraph = Paragraph::new(renderer.to_string())
+                        .alignment(Alignment::Left)
+                        .style(Style::default().fg(if highlighted {
+                            theme.highlight
+                        } else {
+                            theme.text
+                        }));
+                    frame.render_widget(paragraph, area);
+                });
+                return;
+            }
+        };
+
+        let paragraph = Paragraph::new(message)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(theme.primary));
+        frame.render_widget(paragraph, list_area);
+    }
+}
@@ -0,0 +1,208 @@
+use super::{BUTTON_HEIGHT, PopupState, bordered, centered};
+use crate::app::Action;
+use crate::config::Config;
+use crate::config::keymap::{KeyAction, KeyInput};
+use crate::config::navigation::NavDirection;
+use crate::mal::models::anime::{Anime, AnimeId};
+use crate::player::PlayResult;
+use crossterm::event::{MouseEvent, MouseEventKind};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Position, Rect},
+    style::Style,
+    symbols::border,
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CompletionFocus {
+    Buttons,
+    Score,
+}
+
+// asks if a played episode should count as watched, and for a score while at it
+#[derive(Clone)]
+pub struct CompletionPopup {
+    popup: PopupState,
+    anime_id: AnimeId,
+    title: String,
+    details: Option<PlayResult>,
+    // the "mark watched" button is selected
+    watched: bool,
+    score: u8,
+    focus: CompletionFocus,
+    button_areas: [Rect; 2],
+}
+
+impl CompletionPopup {
+    pub fn new() -> Self {
+        Self {
+            popup: PopupState::default(),
+            anime_id: AnimeId::default(),
+            title: String::new(),
+            details: None,
+            watched: true,
+            score: 0,
+            focus: CompletionFocus::Buttons,
+            button_areas: [Rect::default(); 2],
+        }
+    }
+
+    pub fn open(&mut self, anime: &Anime, details: PlayResult) {
+        self.anime_id = anime.id;
+        self.title = if anime.alternative_titles.en.is_empty() || anime.alternative_titles.en == "N/A" {
+            anime.title.clone()
+        } else {
+            anime.alternative_titles.en.clone()
+        };
+        // suggest what the threshold decided
+        self.watched = details.completed;
+        self.score = anime.my_list_status.score;
+        self.details = Some(details);
+        self.focus = CompletionFocus::Buttons;
+        self.popup.open();
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.popup.is_open()
+    }
+
+    fn finish(&mut self, watched: bool) -> Option<Action> {
+        self.popup.close();
+        let details = self.details.take()?;
+        Some(Action::FinishEpisode(self.anime_id, details, watched, Some(self.score)))
+    }
+
+    // closing without choosing keeps the progress and the score as they were
+    fn dismiss(&mut self) -> Option<Action> {
+        self.popup.close();
+        let details = self.details.take()?;
+        Some(Action::FinishEpisode(self.anime_id, details, false, None))
+    }
+
+    pub fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
+        if key.is(KeyAction::Select) {
+            return self.finish(self.watched);
+        }
+
+        if key.is(KeyAction::Close) {
+            return self.dismiss();
+        }
+
+        match (self.focus, key.direction()) {
+            (_, NavDirection::Up) => self.focus = CompletionFocus::Buttons,
+            (_, NavDirection::Down) => self.focus = CompletionFocus::Score,
+            (CompletionFocus::Buttons, NavDirection::Left) => self.watched = true,
+            (CompletionFocus::Buttons, NavDirection::Right) => self.watched = false,
+            (CompletionFocus::Score, NavDirection::Left) => self.score = self.score.saturating_sub(1),
+            (CompletionFocus::Score, NavDirection::Right) => self.score = (self.score + 1).min(10),
+            _ => {}
+        }
+
+        None
+    }
+
+    pub fn handle_mouse(&mut self, mouse_event: MouseEvent) -> Option<Action> {
+        let pos = Position::new(mouse_event.column, mouse_event.row);
+
+        match mouse_event.kind {
+            MouseEventKind::Down(_) => {
+                if self.button_areas[0].contains(pos) {
+                    return self.finish(true);
+                }
+                if self.button_areas[1].contains(pos) {
+                    return self.finish(false);
+                }
+                if self.popup.clicked_outside(mouse_event) {
+                    return self.dismiss();
+                }
+            }
+            MouseEventKind::ScrollUp => self.score = (self.score + 1).min(10),
+            MouseEventKind::ScrollDown => self.score = self.score.saturating_sub(1),
+            _ => {}
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame) {
+        if !self.popup.is_open() {
+            return;
+        }
+        let Some(details) = &self.details else {
+            return;
+        };
+        let theme = &Config::global().theme;
+
+        let area = centered(frame.area(), 56, 12);
+        let popup_area = self.popup.draw(frame, area, bordered("Episode finished", theme.highlight));
+
+        let [text_area, buttons_area, score_area] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Fill(1),
+                Constraint::Length(BUTTON_HEIGHT),
+                Constraint::Length(1),
+            ])
+            .areas(popup_area.inner(Margin::new(2, 1)));
+
+        let text = format!(
+            "{}\n\nEpisode {} - watched {}% ({} / {})",
+            self.title, details.episode, details.percentage, details.current_time, details.total_time
+        );
+        frame.render_widget(
+            Paragraph::new(text)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true })
+                .style(Style::default().fg(theme.text)),
+            text_area,
+        );
+
+        let [mark_area, skip_area] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Fill(1), Constraint::Fill(1)])
+            .areas(buttons_area);
+        self.button_areas = [mark_area, skip_area];
+
+        let buttons_focused = self.focus == CompletionFocus::Buttons;
+        for (label, button_area, selected) in [
+            (format!("Mark EP {} watched", details.episode), mark_area, self.watched),
+            ("Not watched".to_string(), skip_area, !self.watched),
+        ] {
+            let color = if selected && buttons_focused {
+                theme.highlight
+            } else if selected {
+                theme.secondary
+            } else {
+                theme.primary
+            };
+            frame.render_widget(
+                Paragraph::new(label)
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .border_set(border::ROUNDED),
+                    )
+                    .alignment(Alignment::Center)
+                    .style(Style::default().fg(color)),
+                button_area,
+            );
+        }
+
+        let score = if self.score == 0 {
+            "Not rated".to_string()
+        } else {
+            self.score.to_string()
+        };
+        frame.render_widget(
+            Paragraph::new(format!("Rate now: < {} >", score))
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(if buttons_focused {
+                    theme.text
+                } else {
+                    theme.highlight
+                })),
+            score_area,
+        );
+    }
+}
@@ -0,0 +1,280 @@
+use super::{PopupState, bordered, centered};
+use crate::app::{Action, Event};
+use crate::config::Config;
+use crate::config::keymap::{KeyAction, KeyContext, KeyInput};
+use crate::config::navigation::NavDirection;
+use crate::mal::models::anime::{Anime, AnimeId};
+use crate::player::{AnimePlayer, EpisodeEntry, PlayError};
+use crate::screens::widgets::navigatable::Navigatable;
+use crate::screens::{BackgroundUpdate, ExtraInfo};
+use crate::send_error;
+use crossterm::event::{MouseEvent, MouseEventKind};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Margin},
+    style::Style,
+    widgets::Paragraph,
+};
+use std::sync::mpsc::{Receiver, Sender};
+use std::thread::JoinHandle;
+
+// fetches the episode list for an anime in the background
+enum EpisodeEvent {
+    Fetch(Anime),
+}
+
+#[derive(Clone)]
+pub struct EpisodePopup {
+    popup: PopupState,
+    loading: bool,
+    anime_id: AnimeId,
+    title: String,
+    watched: u32,
+    episodes: Vec<EpisodeEntry>,
+    // episodes on disk (downloaded, queued or in the library)
+    local: Vec<String>,
+    // index of the first episode of a range to play as a playlist
+    range_start: Option<usize>,
+    nav: Navigatable,
+    background_transmitter: Sender<EpisodeEvent>,
+}
+
+impl EpisodePopup {
+    pub fn new(info: ExtraInfo) -> Self {
+        let (tx, rx) = std::sync::mpsc::channel::<EpisodeEvent>();
+        let popup = Self {
+            popup: PopupState::default(),
+            loading: false,
+            anime_id: AnimeId::default(),
+            title: String::new(),
+            watched: 0,
+            episodes: Vec::new(),
+            local: Vec::new(),
+            range_start: None,
+            nav: Navigatable::new((10, 4)),
+            background_transmitter: tx,
+        };
+        popup.spawn_background(info, rx);
+        popup
+    }
+
+    fn spawn_background(&self, info: ExtraInfo, receiver: Receiver<EpisodeEvent>) -> JoinHandle<()> {
+        std::thread::spawn(move || {
+            let player = AnimePlayer::new();
+            while let Ok(event) = receiver.recv() {
+                match event {
+                    EpisodeEvent::Fetch(anime) => {
+                        let update = match player.get_episode_list(&anime) {
+                            Ok(episodes) => {
+                                let local: Vec<String> = episodes
+                                    .iter()
+                                    .filter(|e| crate::player::local_episode(&anime, &e.episode).is_some())
+                                    .map(|e| e.episode.clone())
+                                    .collect();
+                                BackgroundUpdate::new("episode_popup")
+                                    .set("episodes", (anime.id, episodes, local))
+                            }
+                            // the source picker takes over from here
+                            Err(PlayError::AmbiguousMatch(candidates)) => {
+                                BackgroundUpdate::new("episode_popup")
+                                    .set("candidates", (anime.id, candidates))
+                            }
+                            Err(e) => BackgroundUpdate::new("episode_popup")
+                                .set("failure", (anime.id, e.to_string())),
+                        };
+                        info.app_sx.send(Event::BackgroundNotice(update)).ok();
+                    }
+                }
+            }
+        })
+    }
+
+    pub fn apply_update(&mut self, mut update: BackgroundUpdate) {
+        if let Some((anime_id, episodes, local)) =
+            update.take::<(AnimeId, Vec<EpisodeEntry>, Vec<String>)>("episodes")
+            && anime_id == self.anime_id
+        {
+            self.loading = false;
+            self.local = local;
+            self.episodes = episodes;
+
+            // start at the next episode to watch
+            let next = self
+                .episodes
+                .iter()
+                .position(|e| e.number() > self.watched as f32)
+                .unwrap_or(0);
+            self.nav.set_selected_index(next);
+        }
+
+        if let Some((anime_id, message)) = update.take::<(AnimeId, String)>("failure")
+            && anime_id == self.anime_id
+        {
+            self.close();
+            send_error!("Failed to fetch episodes:\n{}", message);
+        }
+    }
+
+    pub fn open(&mut self, anime: &Anime) {
+        self.anime_id = anime.id;
+        self.title = anime.title.clone();
+        self.watched = anime.my_list_status.num_episodes_watched;
+        self.episodes.clear();
+        self.range_start = None;
+        self.nav.back_to_start();
+        self.loading = true;
+        self.popup.open();
+        self.background_transmitter
+            .send(EpisodeEvent::Fetch(anime.clone()))
+            .ok();
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.popup.is_open()
+    }
+
+    pub fn close(&mut self) {
+        self.popup.close();
+        self.loading = false;
+        self.range_start = None;
+    }
+
+    fn select(&mut self) -> Option<Action> {
+        let episode = self.nav.get_selected_item(&self.episodes)?.episode.clone();
+        self.close();
+        Some(Action::PlayEpisode(self.anime_id, episode))
+    }
+
+    // the first press marks where the range starts, the second plays everything up to the selected episode
+    fn mark_range(&mut self) -> Option<Action> {
+        let selected = self.nav.get_selected_index();
+        if selected >= self.episodes.len() {
+            return None;
+        }
+
+        let Some(start) = self.range_start else {
+            self.range_start = Some(selected);
+            return None;
+        };
+
+        let (first, last) = (start.min(selected), start.max(selected));
+        let episodes = self.episodes[first..=last]
+            .iter()
+            .map(|entry| entry.episode.clone())
+            .collect();
+        self.close();
+        Some(Action::PlayEpisodes(self.anime_id, episodes))
+    }
+
+    pub fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
+        match key.direction() {
+            NavDirection::Up => self.nav.move_up(),
+            NavDirection::Down => self.nav.move_down(),
+            NavDirection::Left => self.nav.move_left(),
+            NavDirection::Right => self.nav.move_right(),
+            _ => {}
+        }
+
+        if key.is(KeyAction::Select) && !self.loading {
+            return self.select();
+        }
+
+        if key.is(KeyAction::Download) && !self.loading {
+            let entry = self.nav.get_selected_item(&self.episodes)?;
+            self.local.push(entry.episode.clone());
+            return Some(Action::DownloadEpisode(self.anime_id, entry.episode.clone()));
+        }
+
+        if key.is(KeyAction::Playlist) && !self.loading {
+            return self.mark_range();
+        }
+
+        if key.is(KeyAction::Close) {
+            self.close();
+        }
+
+        None
+    }
+
+    pub fn handle_mouse(&mut self, mouse_event: MouseEvent) -> Option<Action> {
+        if self.popup.clicked_outside(mouse_event) {
+            self.close();
+            return None;
+        }
+        let is_click = matches!(mouse_event.kind, MouseEventKind::Down(_));
+
+        if self.nav.is_hovered(mouse_event) {
+            self.nav.handle_scroll(mouse_event);
+        }
+
+        if self.nav.get_hovered_index(mouse_event).is_some() && is_click && !self.loading {
+            return self.select();
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame) {
+        if !self.popup.is_open() {
+            return;
+        }
+
+        let config = Config::global();
+        let context = Some(KeyContext::EpisodePopup);
+        let area = centered(frame.area(), 76, 10 + 4);
+        let block = bordered(format!("Episodes of \"{}\"", self.title), config.theme.secondary)
+            .title_bottom(format!(
+                " ✓ watched  ↓ on disk  [{}] download  [{}] {} ",
+                config.keymap.hint(&config.navigation, context, KeyAction::Download),
+                config.keymap.hint(&config.navigation, context, KeyAction::Playlist),
+                if self.range_start.is_some() { "play to here" } else { "play from here" }
+            ));
+        let popup_area = self.popup.draw(frame, area, block);
+
+        let list_area = popup_area.inner(Margin::new(2, 2));
+
+        if self.loading || self.episodes.is_empty() {
+            let text = if self.loading { "Loading..." } else { "No episodes found" };
+            let paragraph = Paragraph::new(text)
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(Config::global().theme.primary));
+            frame.render_widget(paragraph, list_area);
+            return;
+        }
+
+        let watched = self.watched as f32;
+        let local = &self.local;
+        let range_start = self
+            .range_start
+            .and_then(|index| self.episodes.get(index))
+            .map(|entry| entry.episode.clone());
+        self.nav
+            .construct(&self.episodes, list_area, |entry, area, highlighted| {
+                let is_watched = entry.number() <= watched;
+                let is_range_start = range_start.as_ref() == Some(&entry.episode);
+                let text = format!(
+                    "{}{} EP {} {}{}",
+                    if is_watched { "✓" } else { " " },
+                    if local.contains(&entry.episode) { "↓" } else { " " },
+                    entry.episode,
+                    if entry.sub { "S" } else { "-" },
+                    if entry.dub { "D" } else { "-" },
+                );
+
+                let color = if highlighted {
+                    Config::global().theme.highlight
+                } else if is_range_start {
+                    Config::global().theme.second_highlight
+                } else if is_watched {
+                    Config::global().theme.primary
+                } else {
+                    Config::global().theme.text
+                };
+
+                let paragraph = Paragraph::new(text)
+                    .alignment(Alignment::Left)
+                    .style(Style::default().fg(color));
+                frame.render_widget(paragraph, area);
+            });
+    }
+}
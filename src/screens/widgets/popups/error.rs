@@ -0,0 +1,125 @@
+use crate::app::Action;
+use crate::config::Config;
+use crate::config::keymap::{KeyAction, KeyInput};
+use crossterm::event::{MouseEvent, MouseEventKind};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Rect},
+    style::Style,
+    symbols::border,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+#[derive(Clone)]
+pub struct ErrorPopup {
+    toggled: bool,
+    error_message: String,
+    height: u16,
+    width: u16,
+}
+
+impl ErrorPopup {
+    pub fn new() -> Self {
+        Self {
+            toggled: false,
+            error_message: String::new(),
+            height: 10,
+            width: 40,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.toggled
+    }
+
+    pub fn set_error(&mut self, message: String) -> &Self {
+        let content_width = self.width.saturating_sub(2);
+        let total_lines: u16 = message
+            .lines()
+            .map(|line| {
+                if line.is_empty() {
+                    1
+                } else {
+                    (line.len() as u16).div_ceil(content_width)
+                }
+            })
+            .sum();
+
+        self.height = total_lines + 2;
+        self.error_message = message;
+        self
+    }
+
+    pub fn open(&mut self) -> &Self {
+        self.toggled = true;
+        self
+    }
+
+    pub fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
+        if !self.toggled {
+            return None;
+        }
+        if key.is(KeyAction::Close) {
+            self.toggled = false;
+            self.error_message.clear();
+        }
+        None
+    }
+
+    pub fn handle_mouse(&mut self, mouse_event: MouseEvent) -> Option<Action> {
+        if !self.toggled {
+            return None;
+        }
+        match mouse_event.kind {
+            MouseEventKind::Down(_) => {
+                self.toggled = false;
+                self.error_message.clear();
+                None
+            }
+            _ => None,
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        if !self.toggled {
+            return;
+        }
+
+        let area = frame.area();
+
+        let max_width = std::cmp::min(
+            self.width,
+            std::cmp::max(area.width * 4 / 5, area.width.saturating_sub(4)),
+        );
+        let max_height = std::cmp::min(
+            self.height,
+            std::cmp::max(area.height * 4 / 5, area.height.saturating_sub(4)),
+        );
+
+        let popup_area = Rect::new(
+            area.x + (area.width.saturating_sub(max_width)) / 2,
+            area.y + (area.height.saturating_sub(max_height)) / 2,
+            max_width,
+            max_height,
+        );
+
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_set(border::ROUNDED)
+            .title("Error")
+            .style(Style::default().fg(Config::global().theme.error));
+
+        frame.render_widget(block.clone(), popup_area);
+
+        let paragraph = Paragraph::new(self.error_message.clone())
+            .block(block)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .scroll((0, 0))
+            .style(Style::default().fg(Config::global().theme.error));
+
+        frame.render_widget(paragraph, popup_area);
+    }
+}
@@ -0,0 +1,144 @@
+use super::{PopupState, bordered, centered};
+use crate::app::Action;
+use crate::config::Config;
+use crate::config::keymap::{KeyAction, KeyContext, KeyInput};
+use crate::config::navigation::NavDirection;
+use crossterm::event::{MouseEvent, MouseEventKind};
+use ratatui::{
+    Frame,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Padding, Paragraph, Wrap},
+};
+
+// the keys that work where the help was opened, made from the keymap
+pub struct HelpPopup {
+    popup: PopupState,
+    title: String,
+    keys: Vec<(String, &'static str)>,
+    mouse: &'static str,
+    scroll: u16,
+    // lines that fit last time it was drawn, scrolling stops at the end
+    visible: u16,
+}
+
+impl HelpPopup {
+    pub fn new() -> Self {
+        Self {
+            popup: PopupState::default(),
+            title: String::new(),
+            keys: Vec::new(),
+            mouse: "",
+            scroll: 0,
+            visible: 0,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.popup.is_open()
+    }
+
+    pub fn open(&mut self, title: &str, keys: Vec<(String, &'static str)>, mouse: &'static str) {
+        self.title = title.to_string();
+        self.keys = keys;
+        self.mouse = mouse;
+        self.scroll = 0;
+        self.popup.open();
+    }
+
+    pub fn close(&mut self) {
+        self.popup.close();
+        self.keys.clear();
+    }
+
+    // keys, a blank line and the mouse
+    fn line_count(&self) -> u16 {
+        self.keys.len() as u16 + 2
+    }
+
+    fn scroll_by(&mut self, direction: NavDirection) {
+        let last = self.line_count().saturating_sub(self.visible);
+        match direction {
+            NavDirection::Up => self.scroll = self.scroll.saturating_sub(1),
+            NavDirection::Down => self.scroll = (self.scroll + 1).min(last),
+            _ => {}
+        }
+    }
+
+    pub fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
+        if key.is(KeyAction::Close) || key.is(KeyAction::Help) {
+            self.close();
+            return None;
+        }
+        self.scroll_by(key.direction());
+        None
+    }
+
+    pub fn handle_mouse(&mut self, mouse_event: MouseEvent) -> Option<Action> {
+        match mouse_event.kind {
+            MouseEventKind::Down(_) => self.close(),
+            MouseEventKind::ScrollUp => self.scroll_by(NavDirection::Up),
+            MouseEventKind::ScrollDown => self.scroll_by(NavDirection::Down),
+            _ => {}
+        }
+        None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame) {
+        if !self.popup.is_open() {
+            return;
+        }
+        let theme = &Config::global().theme;
+        let area = frame.area();
+
+        let key_width = self.keys.iter().map(|(keys, _)| keys.chars().count()).max().unwrap_or(0);
+        let text_width = self
+            .keys
+            .iter()
+            .map(|(_, description)| description.len())
+            .max()
+            .unwrap_or(0)
+            .max(self.mouse.len().min(50));
+        let width = ((key_width + text_width + 7) as u16).min(area.width.saturating_sub(4));
+        let height = (self.line_count() + 2).min(area.height.saturating_sub(2));
+        let popup_area = centered(area, width, height);
+
+        let close = Config::global().keymap.hint(
+            &Config::global().navigation,
+            Some(KeyContext::HelpPopup),
+            KeyAction::Close,
+        );
+        let block = bordered(format!(" Keys: {} ", self.title), theme.highlight)
+            .title_bottom(format!(" [{}] close ", close))
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(popup_area);
+        self.popup.draw(frame, popup_area, block);
+
+        let mut lines: Vec<Line> = self
+            .keys
+            .iter()
+            .map(|(keys, description)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:<width$}  ", keys, width = key_width),
+                        Style::default().fg(theme.second_highlight),
+                    ),
+                    Span::styled(*description, Style::default().fg(theme.text)),
+                ])
+            })
+            .collect();
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("mouse: {}", self.mouse),
+            Style::default().fg(theme.second_text),
+        )));
+
+        // the mouse line wraps, give it the room it needs
+        self.visible = inner.height.saturating_sub(1);
+        self.scroll = self.scroll.min(self.line_count().saturating_sub(self.visible));
+        let paragraph = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        frame.render_widget(paragraph, inner);
+    }
+}
@@ -0,0 +1,98 @@
+mod anime;
+mod binge;
+mod cast;
+mod completion;
+mod episode;
+mod error;
+mod help;
+mod palette;
+mod season;
+mod selection;
+mod source;
+
+use crossterm::event::{MouseEvent, MouseEventKind};
+use ratatui::{
+    Frame,
+    layout::{Position, Rect},
+    style::{Color, Style},
+    symbols::border,
+    text::Line,
+    widgets::{Block, Borders, Clear},
+};
+use std::cmp::min;
+
+pub use anime::AnimePopup;
+pub use binge::BingePopup;
+pub use cast::CastPopup;
+pub use completion::CompletionPopup;
+pub use episode::EpisodePopup;
+pub use error::ErrorPopup;
+pub use help::HelpPopup;
+pub use palette::CommandPalette;
+pub use season::SeasonPopup;
+pub use selection::{Arrows, SelectionPopup};
+pub use source::SourcePopup;
+
+// the height of a bordered button
+const BUTTON_HEIGHT: u16 = 3;
+
+// what every popup keeps: if it is open and where it was drawn last, clicks are checked against that
+#[derive(Debug, Clone, Default)]
+pub struct PopupState {
+    toggled: bool,
+    area: Option<Rect>,
+}
+
+impl PopupState {
+    pub fn open(&mut self) {
+        self.toggled = true;
+    }
+
+    pub fn close(&mut self) {
+        self.toggled = false;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.toggled
+    }
+
+    pub fn contains(&self, mouse_event: MouseEvent) -> bool {
+        let pos = Position::new(mouse_event.column, mouse_event.row);
+        self.area.is_some_and(|area| area.contains(pos))
+    }
+
+    // a click next to the popup, which closes it
+    pub fn clicked_outside(&self, mouse_event: MouseEvent) -> bool {
+        matches!(mouse_event.kind, MouseEventKind::Down(_))
+            && self.area.is_some()
+            && !self.contains(mouse_event)
+    }
+
+    // clears the area and draws the popup's border in it
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect, block: Block) -> Rect {
+        self.area = Some(area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+        area
+    }
+}
+
+// the middle of the screen, at most width x height
+pub fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let (width, height) = (min(area.width, width), min(area.height, height));
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+// the rounded border with a title the popups are drawn in
+pub fn bordered<'a>(title: impl Into<Line<'a>>, color: Color) -> Block<'a> {
+    Block::default()
+        .borders(Borders::ALL)
+        .border_set(border::ROUNDED)
+        .title(title)
+        .style(Style::default().fg(color))
+}
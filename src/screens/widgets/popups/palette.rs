@@ -0,0 +1,257 @@
+use super::season::{AVAILABLE_SEASONS, FIRST_YEAR};
+use super::{PopupState, bordered};
+use crate::app::Action;
+use crate::config::Config;
+use crate::config::keymap::{KeyAction, KeyInput};
+use crate::config::navigation::NavDirection;
+use crate::mal::{MalClient, models::anime::Anime};
+use crate::screens::widgets::navigatable::Navigatable;
+use crate::screens::{screen_to_name, screens::*};
+use crate::utils::{input::Input, stringManipulation::fuzzy_score};
+use crossterm::event::{MouseEvent, MouseEventKind};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use std::cmp::min;
+use std::rc::Rc;
+
+// one line of the command palette
+#[derive(Clone)]
+pub struct PaletteEntry {
+    label: String,
+    // shown next to the label, eg. "anime" or "season"
+    kind: &'static str,
+    action: Action,
+}
+
+impl PaletteEntry {
+    fn new(label: impl Into<String>, kind: &'static str, action: Action) -> Self {
+        Self {
+            label: label.into(),
+            kind,
+            action,
+        }
+    }
+}
+
+// the best matches shown, a short query matches most of the store otherwise
+const PALETTE_MATCHES: usize = 100;
+
+// a text field matching what is typed against the commands and every anime loaded
+pub struct CommandPalette {
+    popup: PopupState,
+    input: Input,
+    entries: Vec<PaletteEntry>,
+    matches: Vec<PaletteEntry>,
+    nav: Navigatable,
+}
+
+impl CommandPalette {
+    pub fn new() -> Self {
+        Self {
+            popup: PopupState::default(),
+            input: Input::new(),
+            entries: Vec::new(),
+            matches: Vec::new(),
+            nav: Navigatable::new((12, 1)),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.popup.is_open()
+    }
+
+    // the commands come first, then the animes by title
+    pub fn open(&mut self, animes: &[Rc<Anime>]) {
+        let mut entries = Self::commands();
+
+        let mut animes: Vec<&Rc<Anime>> = animes.iter().collect();
+        animes.sort_by(|a, b| a.title.cmp(&b.title));
+        entries.extend(animes.into_iter().map(|anime| {
+            let english = &anime.alternative_titles.en;
+            let label = if english.is_empty() || *english == anime.title {
+                anime.title.clone()
+            } else {
+                format!("{} ({})", anime.title, english)
+            };
+            PaletteEntry::new(label, "anime", Action::ShowOverlay(anime.id))
+        }));
+
+        self.entries = entries;
+        self.input = Input::new();
+        self.refilter();
+        self.popup.open();
+    }
+
+    pub fn close(&mut self) {
+        self.popup.close();
+        self.entries.clear();
+        self.matches.clear();
+    }
+
+    fn commands() -> Vec<PaletteEntry> {
+        let mut commands: Vec<PaletteEntry> = [OVERVIEW, SEASONS, SEARCH, LIST, DOWNLOADS, PROFILE, SETTINGS]
+            .into_iter()
+            .map(|screen| {
+                PaletteEntry::new(
+                    format!("Go to {}", screen_to_name(screen)),
+                    "screen",
+                    Action::SwitchScreen(screen),
+                )
+            })
+            .collect();
+
+        for status in ["all", "watching", "plan to watch", "completed", "on hold", "dropped"] {
+            commands.push(PaletteEntry::new(
+                format!("List: {}", status),
+                "filter",
+                Action::FilterList(status.to_string()),
+            ));
+        }
+        for ranking in ["all", "airing", "upcoming", "tv", "ova", "movie", "special", "popularity", "favorite"] {
+            commands.push(PaletteEntry::new(
+                format!("Top anime: {}", ranking),
+                "filter",
+                Action::ShowRanking(ranking.to_string()),
+            ));
+        }
+
+        if MalClient::user_is_logged_in() {
+            commands.push(PaletteEntry::new("Log out", "account", Action::LogOut));
+        } else {
+            commands.push(PaletteEntry::new("Log in", "account", Action::SwitchScreen(LOGIN)));
+        }
+        commands.push(PaletteEntry::new("Quit", "app", Action::Quit));
+
+        let (current_year, _) = MalClient::current_season();
+        for year in (FIRST_YEAR..=current_year).rev() {
+            for season in AVAILABLE_SEASONS.iter().rev() {
+                commands.push(PaletteEntry::new(
+                    format!("Season: {} {}", season, year),
+                    "season",
+                    Action::ChangeSeason(year, season.to_string()),
+                ));
+            }
+        }
+        commands
+    }
+
+    fn refilter(&mut self) {
+        let query = self.input.value();
+        let mut scored: Vec<(i32, &PaletteEntry)> = self
+            .entries
+            .iter()
+            .filter_map(|entry| Some((fuzzy_score(query, &entry.label)?, entry)))
+            .collect();
+        // stable, so ties keep the commands first
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        self.matches = scored
+            .into_iter()
+            .take(PALETTE_MATCHES)
+            .map(|(_, entry)| entry.clone())
+            .collect();
+        self.nav.back_to_start();
+    }
+
+    fn run(&mut self) -> Option<Action> {
+        let action = self.nav.get_selected_item(&self.matches)?.action.clone();
+        self.close();
+        Some(action)
+    }
+
+    pub fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
+        match key.direction() {
+            NavDirection::Up => self.nav.move_up(),
+            NavDirection::Down => self.nav.move_down(),
+            _ if key.is(KeyAction::Select) => return self.run(),
+            _ if key.is(KeyAction::Close) => self.close(),
+            _ => {
+                if self.input.handle_event(key.event, true).is_some() {
+                    self.refilter();
+                }
+            }
+        }
+        None
+    }
+
+    pub fn handle_mouse(&mut self, mouse_event: MouseEvent) -> Option<Action> {
+        if self.popup.clicked_outside(mouse_event) {
+            self.close();
+            return None;
+        }
+        let is_click = matches!(mouse_event.kind, MouseEventKind::Down(_));
+
+        if self.nav.is_hovered(mouse_event) {
+            self.nav.handle_scroll(mouse_event);
+        }
+
+        if self.nav.get_hovered_index(mouse_event).is_some() && is_click {
+            return self.run();
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame) {
+        if !self.popup.is_open() {
+            return;
+        }
+        let theme = &Config::global().theme;
+
+        // near the top, so it doesn't jump around while the matches change
+        let area = frame.area();
+        let width = min(area.width, 80);
+        let height = min(area.height.saturating_sub(2), self.nav.visible_elements() as u16 + 5);
+        let popup_area = Rect::new(
+            area.x + (area.width.saturating_sub(width)) / 2,
+            area.y + min(area.height.saturating_sub(height), 3),
+            width,
+            height,
+        );
+        let block = bordered("Commands", theme.highlight)
+            .title_bottom(format!(" {} of {} ", self.matches.len(), self.entries.len()));
+        self.popup.draw(frame, popup_area, block);
+
+        let [input_area, _, list_area] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Fill(1)])
+            .areas(popup_area.inner(Margin::new(2, 1)));
+
+        let prompt = Line::from(vec![
+            Span::styled("> ", Style::default().fg(theme.second_highlight)),
+            Span::styled(self.input.value(), Style::default().fg(theme.text)),
+        ]);
+        frame.render_widget(Paragraph::new(prompt), input_area);
+        self.input.render_cursor(frame, input_area.x + 2, input_area.y, true);
+
+        if self.matches.is_empty() {
+            let paragraph = Paragraph::new("Nothing matches")
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(theme.primary));
+            frame.render_widget(paragraph, list_area);
+            return;
+        }
+
+        self.nav.construct(&self.matches, list_area, |entry, area, highlighted| {
+            let [label_area, kind_area] = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Fill(1), Constraint::Length(8)])
+                .areas(area);
+            let color = if highlighted { theme.highlight } else { theme.text };
+            frame.render_widget(
+                Paragraph::new(entry.label.as_str()).style(Style::default().fg(color)),
+                label_area,
+            );
+            frame.render_widget(
+                Paragraph::new(entry.kind)
+                    .alignment(Alignment::Right)
+                    .style(Style::default().fg(theme.primary)),
+                kind_area,
+            );
+        });
+    }
+}
@@ -0,0 +1,429 @@
+use crate::config::Config;
+use crate::config::keymap::{KeyAction, KeyInput};
+use crate::config::navigation::NavDirection;
+use crate::mal::MalClient;
+use crossterm::event::{KeyCode, MouseEvent};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Position, Rect},
+    style::Style,
+    symbols::border,
+    widgets::{Block, Borders, Clear, Padding, Paragraph},
+};
+use std::cmp::min;
+
+pub(super) const AVAILABLE_SEASONS: [&str; 4] = ["Winter", "Spring", "Summer", "Fall"];
+pub(super) const FIRST_YEAR: u16 = 1917;
+const FIRST_SEASON: &str = "Winter";
+
+#[derive(Clone)]
+pub struct SeasonPopup {
+    toggled: bool,
+    year_scroll: u16,
+    season_scroll: u16,
+    year_selected: bool,
+    available_years: Vec<String>,
+    all_years: Vec<String>,
+    entered_number: String,
+
+    //cache
+    activate_area: Option<Rect>,
+    popup_area: Option<Rect>,
+    previous_year: u16,
+}
+impl SeasonPopup {
+    pub fn new() -> Self {
+        let (year, season) = MalClient::current_season();
+        let season_scroll = AVAILABLE_SEASONS
+            .iter()
+            .position(|&s| s.to_lowercase() == season.to_lowercase())
+            .unwrap_or(0) as u16;
+
+        let all_years: Vec<String> = (FIRST_YEAR..=year).rev().map(|y| y.to_string()).collect();
+
+        Self {
+            toggled: false,
+            year_scroll: 0,
+            season_scroll,
+            available_years: all_years.clone(),
+            all_years,
+            year_selected: false,
+            entered_number: String::new(),
+            activate_area: None,
+            popup_area: None,
+            previous_year: year,
+        }
+    }
+
+    fn filter_years(&mut self) {
+        if self.entered_number.is_empty() {
+            self.available_years = self.all_years.clone();
+        } else {
+            self.available_years = self
+                .all_years
+                .iter()
+                .filter(|year| year.contains(&self.entered_number))
+                .cloned()
+                .collect();
+        }
+        self.year_scroll = 0;
+    }
+
+    pub fn hide(&mut self) -> &Self {
+        self.popup_area = None;
+        self.toggled = false;
+        self.entered_number.clear();
+        self.filter_years();
+        self
+    }
+
+    pub fn toggle(&mut self) -> &Self {
+        self.toggled = !self.toggled;
+
+        if self.toggled {
+            self.year_scroll = self
+                .available_years
+                .iter()
+                .position(|y| y.parse::<u16>().unwrap_or(0) == self.previous_year)
+                .unwrap_or(0) as u16;
+        }
+        self
+    }
+
+    pub fn is_toggled(&self) -> bool {
+        self.toggled
+    }
+
+    // shows a season that was picked somewhere else
+    pub fn select(&mut self, year: u16, season: &str) {
+        self.previous_year = year;
+        if let Some(index) = AVAILABLE_SEASONS.iter().position(|s| s.eq_ignore_ascii_case(season)) {
+            self.season_scroll = index as u16;
+        }
+    }
+
+    pub fn handle_keyboard(&mut self, key: KeyInput) -> Option<(u16, String)> {
+        // for writing (search of numbers)
+        match key.event.code {
+            KeyCode::Backspace => {
+                if !self.entered_number.is_empty() {
+                    self.entered_number.pop();
+                    self.filter_years();
+                }
+                return None;
+            }
+            KeyCode::Char(c) => {
+                if c.is_ascii_digit() {
+                    self.entered_number.push(c);
+                    self.filter_years();
+                    return None;
+                }
+            }
+            _ => {}
+        }
+
+        // for navigation
+        match key.direction() {
+            NavDirection::Right => {
+                self.year_selected = false;
+                return None;
+            }
+
+            NavDirection::Left => {
+                self.year_selected = true;
+                return None;
+            }
+
+            NavDirection::Up => {
+                if self.year_selected {
+                    self.year_scroll = self.year_scroll.saturating_sub(1);
+                } else {
+                    self.season_scroll = self.season_scroll.saturating_sub(1);
+                }
+                return None;
+            }
+            NavDirection::Down => {
+                if self.year_selected {
+                    if self.year_scroll < (self.available_years.len().saturating_sub(1)) as u16 {
+                        self.year_scroll += 1;
+                    }
+                } else if self.season_scroll < (AVAILABLE_SEASONS.len().saturating_sub(1)) as u16 {
+                    self.season_scroll += 1;
+                }
+                return None;
+            }
+            _ => {},
+        };
+
+        // for selecting
+        if key.is(KeyAction::Select) {
+            if !self.toggled {
+                self.toggle();
+                return None;
+            }
+
+            let (_year, _) = MalClient::current_season();
+            let season = AVAILABLE_SEASONS
+                .get(self.season_scroll as usize)
+                .unwrap_or(&FIRST_SEASON)
+                .to_string();
+
+            let year = self
+                .available_years
+                .get(self.year_scroll as usize)
+                .and_then(|y| y.parse::<u16>().ok())
+                .unwrap_or(_year);
+
+            self.previous_year = year;
+            self.hide();
+
+            return Some((year, season))
+        }
+
+        if key.is(KeyAction::Close) {
+            self.hide();
+        } 
+
+        None
+    }
+
+    pub fn handle_mouse(&mut self, mouse_event: MouseEvent) -> Option<(u16, String)> {
+        // if the season area has been rendered yet
+        let activate_area = self.activate_area?;
+        let mouse_pos = Position::new(mouse_event.column, mouse_event.row);
+        if activate_area.contains(mouse_pos) {
+            if let crossterm::event::MouseEventKind::Down(_) = mouse_event.kind {
+                self.toggle();
+            }
+        }
+
+        // the popup below the season area
+        let popup_area = self.popup_area?;
+        let [left, right] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Fill(1), Constraint::Fill(1)])
+            .areas(popup_area);
+
+        // right side
+        if right.contains(mouse_pos) {
+            self.year_selected = false;
+            match mouse_event.kind {
+                crossterm::event::MouseEventKind::ScrollUp => {
+                    self.season_scroll = self.season_scroll.saturating_sub(1);
+                }
+
+                crossterm::event::MouseEventKind::ScrollDown => {
+                    if self.season_scroll < (AVAILABLE_SEASONS.len().saturating_sub(1)) as u16 {
+                        self.season_scroll += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+        // left side
+        else if left.contains(mouse_pos) {
+            self.year_selected = true;
+            match mouse_event.kind {
+                crossterm::event::MouseEventKind::ScrollUp => {
+                    self.year_scroll = self.year_scroll.saturating_sub(1);
+                }
+
+                crossterm::event::MouseEventKind::ScrollDown => {
+                    if self.year_scroll < (self.available_years.len().saturating_sub(1)) as u16 {
+                        self.year_scroll += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // now when selecting an option or clicking outside close the popup:
+        if matches!(mouse_event.kind, crossterm::event::MouseEventKind::Down(_)) {
+            if popup_area.contains(mouse_pos) {
+                let (_year, _) = MalClient::current_season();
+                let season = AVAILABLE_SEASONS
+                    .get(self.season_scroll as usize)
+                    .unwrap_or(&FIRST_SEASON)
+                    .to_string();
+
+                let year = self
+                    .available_years
+                    .get(self.year_scroll as usize)
+                    .and_then(|y| y.parse::<u16>().ok())
+                    .unwrap_or(_year);
+
+                self.previous_year = year;
+                self.hide();
+                return Some((year, season));
+            }
+
+            self.hide();
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, season_area: Rect) {
+        self.activate_area = Some(season_area);
+
+        if !self.toggled {
+            return;
+        }
+
+        let area = frame.area();
+
+        let [height, width] = [min(8, area.height), season_area.width * 7 / 20];
+        let popup_area = Rect::new(
+            season_area.x + (season_area.width.saturating_sub(width)) / 2,
+            season_area.y + season_area.height.saturating_sub(1),
+            width,
+            height,
+        );
+        self.popup_area = Some(popup_area);
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_set(border::ROUNDED)
+            .style(Style::default().fg(Config::global().theme.primary));
+        frame.render_widget(block.clone(), popup_area);
+
+        let text = if self.entered_number.is_empty() {
+            self.entered_number.clone()
+        } else {
+            format!("Search: {}", self.entered_number)
+        };
+        let paragraph = Paragraph::new(text)
+            .block(block)
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Config::global().theme.primary));
+        frame.render_widget(paragraph, popup_area);
+
+        let [year_area, middle_area, season_area] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(40),
+                Constraint::Percentage(20),
+                Constraint::Percentage(40),
+            ])
+            .areas(popup_area);
+        let season_area = Rect {
+            x: season_area.x + 1,
+            y: season_area.y + 1,
+            width: season_area.width.saturating_sub(2),
+            height: season_area.height.saturating_sub(2),
+        };
+        let year_area = Rect {
+            x: year_area.x + 1,
+            y: year_area.y + 1,
+            width: year_area.width.saturating_sub(2),
+            height: year_area.height.saturating_sub(2),
+        };
+
+        let divider = "|";
+        let left_arrow = if self.year_selected { "◀" } else { " " };
+        let right_arrow = if !self.year_selected { "▶" } else { " " };
+        let [middle_area_left, middle_area, middle_area_right] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Fill(1),
+                Constraint::Length(1),
+                Constraint::Fill(1),
+            ])
+            .areas(middle_area);
+
+        let middle_area = Rect {
+            x: middle_area.x,
+            y: middle_area.y + 2,
+            width: middle_area.width,
+            height: middle_area.height.saturating_sub(3),
+        };
+
+        let middle_area_left = Rect {
+            x: middle_area_left.x,
+            y: middle_area_left.y + 2,
+            width: middle_area_left.width,
+            height: middle_area_left.height.saturating_sub(3),
+        };
+
+        let middle_area_right = Rect {
+            x: middle_area_right.x,
+            y: middle_area_right.y + 2,
+            width: middle_area_right.width,
+            height: middle_area_right.height.saturating_sub(3),
+        };
+
+        let left_paragraph = Paragraph::new(left_arrow)
+            .block(Block::default().padding(Padding::new(0, 0, middle_area_left.height / 2, 0)))
+            .alignment(Alignment::Left)
+            .style(Style::default().fg(if self.year_selected {
+                Config::global().theme.highlight
+            } else {
+                Config::global().theme.primary
+            }));
+        let middle_paragraph = Paragraph::new(divider)
+            .block(Block::default().padding(Padding::new(0, 0, middle_area.height / 2, 0)))
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Config::global().theme.primary));
+        let right_paragraph = Paragraph::new(right_arrow)
+            .block(Block::default().padding(Padding::new(0, 0, middle_area_right.height / 2, 0)))
+            .alignment(Alignment::Right)
+            .style(Style::default().fg(if !self.year_selected {
+                Config::global().theme.highlight
+            } else {
+                Config::global().theme.primary
+            }));
+
+        frame.render_widget(left_paragraph, middle_area_left);
+        frame.render_widget(middle_paragraph, middle_area);
+        frame.render_widget(right_paragraph, middle_area_right);
+
+        for (i, season) in AVAILABLE_SEASONS.iter().enumerate() {
+            let y_position = (3 + season_area.y + i as u16).saturating_sub(self.season_scroll);
+            if y_position >= season_area.y + season_area.height {
+                break;
+            }
+            let individual_season_area = Rect {
+                x: season_area.x,
+                y: y_position,
+                width: season_area.width,
+                height: 1,
+            };
+            let paragraph = Paragraph::new(season.to_string())
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(
+                    if !self.year_selected && self.season_scroll == i as u16 {
+                        Config::global().theme.highlight
+                    } else {
+                        Config::global().theme.primary
+                    },
+                ));
+            frame.render_widget(paragraph, individual_season_area);
+        }
+
+        for (i, year) in self.available_years.iter().enumerate() {
+            let y_position = (3 + year_area.y + i as u16).saturating_sub(self.year_scroll);
+            if y_position >= year_area.y + year_area.height {
+                break;
+            } else if y_position < year_area.y {
+                continue;
+            }
+            let individual_year_area = Rect {
+                x: year_area.x,
+                y: y_position,
+                width: year_area.width,
+                height: 1,
+            };
+            let paragraph = Paragraph::new(year.to_string())
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(
+                    if self.year_selected && self.year_scroll == i as u16 {
+                        Config::global().theme.highlight
+                    } else {
+                        Config::global().theme.primary
+                    },
+                ));
+            frame.render_widget(paragraph, individual_year_area);
+        }
+    }
+}
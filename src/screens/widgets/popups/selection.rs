@@ -0,0 +1,438 @@
+use crate::config::Config;
+use crate::config::keymap::{KeyAction, KeyInput};
+use crate::config::navigation::NavDirection;
+use crossterm::event::MouseEvent;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Position, Rect},
+    style::{Color, Style},
+    symbols::border,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Arrows {
+    None,
+    Static,
+    Dynamic,
+}
+
+// theme colors are looked up when drawn, so a reloaded theme shows right away
+#[derive(Debug, Clone, Copy)]
+pub enum SelectionColor {
+    Primary,
+    Error,
+    // the color of the list status that is selected
+    Status,
+    Fixed(Color),
+}
+
+impl From<Color> for SelectionColor {
+    fn from(color: Color) -> Self {
+        SelectionColor::Fixed(color)
+    }
+}
+
+#[derive(Clone)]
+pub struct SelectionPopup {
+    is_open: bool,
+    options: Vec<String>,
+    selected_index: usize,
+    next_index: usize,
+    arrows: Arrows,
+    longest_word: usize,
+    displaying_format: String,
+    color: SelectionColor,
+    area: Option<Rect>,
+    popup_area: Option<Rect>,
+    scroll: usize,
+}
+
+impl SelectionPopup {
+    pub fn new() -> Self {
+        Self {
+            is_open: false,
+            options: Vec::new(),
+            selected_index: 0,
+            next_index: 0,
+            arrows: Arrows::None,
+            longest_word: 0,
+            displaying_format: String::new(),
+            color: SelectionColor::Primary,
+            area: None,
+            popup_area: None,
+            scroll: 0,
+        }
+    }
+
+    pub fn get_selected_option(&self) -> Option<String> {
+        if self.options.is_empty() {
+            None
+        } else {
+            Some(self.options[self.selected_index].clone())
+        }
+    }
+
+    pub fn with_arrows(mut self, arrow_type: Arrows) -> Self {
+        self.arrows = arrow_type;
+        self
+    }
+
+    pub fn with_selected_option(mut self, option: String) -> Self {
+        if let Some(index) = self
+            .options
+            .iter()
+            .position(|o| o.to_lowercase() == option.to_lowercase())
+        {
+            self.selected_index = index;
+            self.next_index = index;
+        } else {
+            self.selected_index = 0;
+            self.next_index = 0;
+        }
+        self
+    }
+
+    pub fn with_color(mut self, color: impl Into<SelectionColor>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    pub fn set_color(&mut self, color: impl Into<SelectionColor>) {
+        self.color = color.into();
+    }
+
+    fn current_color(&self) -> Color {
+        let theme = &Config::global().theme;
+        match self.color {
+            SelectionColor::Primary => theme.primary,
+            SelectionColor::Error => theme.error,
+            SelectionColor::Status => self
+                .get_selected_option()
+                .map(|option| theme.status_color(option))
+                .unwrap_or(theme.primary),
+            SelectionColor::Fixed(color) => color,
+        }
+    }
+
+    pub fn add_option(mut self, option: impl Into<String>) -> Self {
+        let option = option.into();
+        if option.len() > self.longest_word {
+            self.longest_word = option.len();
+        }
+        self.options.push(option);
+        self
+    }
+
+    pub fn add_options<I, S>(mut self, options: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        for option in options {
+            self = self.add_option(option);
+        }
+        self
+    }
+
+    pub fn with_displaying_format<T: Into<String>>(mut self, text: T) -> Self {
+        self.displaying_format = text.into();
+        self
+    }
+
+    pub fn toggle(&mut self) -> &Self {
+        self.is_open = !self.is_open;
+        self
+    }
+    pub fn open(&mut self) -> &Self {
+        self.is_open = true;
+        self
+    }
+
+    pub fn close(&mut self) -> &Self {
+        self.is_open = false;
+        self
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    pub fn is_hovered(&self, mouse_event: MouseEvent) -> bool {
+        if let Some(box_area) = self.area {
+            let pos = Position::new(mouse_event.column, mouse_event.row);
+            if box_area.contains(pos) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    pub fn handle_input(&mut self, key: KeyInput) -> Option<String> {
+        if !self.is_open {
+            if key.is(KeyAction::Select) {
+                self.open();
+            }
+            return None; 
+        }
+
+        match key.direction() {
+            NavDirection::Up => {
+                self.next_index = self.next_index.saturating_sub(1);
+                return None;
+            }
+            NavDirection::Down => {
+                if self.next_index < self.options.len().saturating_sub(1) {
+                    self.next_index += 1;
+                }
+                return None;
+            }
+            _ => {},
+        }
+
+        if key.is(KeyAction::Select) {
+            if self.options.is_empty() {
+                return None;
+            }
+
+            let selected_option = self.options[self.next_index].clone();
+            self.selected_index = self.next_index;
+            self.close();
+            return Some(selected_option)
+        }
+
+        if key.is(KeyAction::Close) {
+            self.close();
+        }
+
+        None
+    }
+
+    // selects the option with this name, if there is one
+    pub fn select_option(&mut self, option: &str) -> Option<String> {
+        let index = self.options.iter().position(|o| o == option)?;
+        self.selected_index = index;
+        self.next_index = index;
+        self.close();
+        Some(self.options[index].clone())
+    }
+
+    // moves to the option after the selected one, wrapping around
+    pub fn select_next(&mut self) -> Option<String> {
+        if self.options.is_empty() {
+            return None;
+        }
+        self.selected_index = (self.selected_index + 1) % self.options.len();
+        self.next_index = self.selected_index;
+        self.close();
+        Some(self.options[self.selected_index].clone())
+    }
+
+    pub fn handle_mouse(&mut self, mouse_event: MouseEvent) -> Option<String> {
+        let mouse_clicked = matches!(mouse_event.kind, crossterm::event::MouseEventKind::Down(_));
+        let pos = Position::new(mouse_event.column, mouse_event.row);
+
+        // this is the button area itself
+        let box_area = self.area?;
+        if box_area.contains(pos) && mouse_clicked {
+            self.toggle();
+            return None;
+        }
+
+
+        // this handles inside the popup box
+        let popup_area = self.popup_area?;
+
+        // for scrolling:
+        if popup_area.contains(pos) {
+            match mouse_event.kind {
+                crossterm::event::MouseEventKind::ScrollUp => {
+                    self.next_index = self.next_index.saturating_sub(1);
+                    return None;
+                }
+
+                crossterm::event::MouseEventKind::ScrollDown => {
+                    if self.next_index < (self.options.len().saturating_sub(1)) {
+                        self.next_index += 1;
+                    }
+                    return None;
+                }
+                _ => {}
+            }
+        }
+
+
+        // for clicking and highlighting
+        for (i, row) in popup_area.inner(Margin::new(0, 1)).rows().enumerate() {
+            if row.contains(pos) && i <= self.options.len() {
+                self.next_index = i + self.scroll;
+                if mouse_clicked {
+                    let selected_option = self.options[self.next_index].clone();
+                    self.selected_index = self.next_index;
+                    self.close();
+                    return Some(selected_option);
+                }
+            }
+        }
+
+        // this happens only whenever the mouse isn't clicked on the button or inside the popup
+        if mouse_clicked {
+            self.close();
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, highlighted: bool) {
+        self.area = Some(area);
+        let option = self
+            .options
+            .get(self.selected_index)
+            .unwrap_or(&"No options available".to_string())
+            .clone();
+        let option = if self.displaying_format.is_empty() {
+            option
+        } else {
+            self.displaying_format.replace("{}", &option)
+        };
+
+        let filter = Paragraph::new(option)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_set(border::ROUNDED),
+            )
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(if highlighted {
+                Config::global().theme.highlight
+            } else {
+                self.current_color()
+            }));
+        frame.render_widget(filter, area);
+
+        if self.is_open {
+            let terminal_height = frame.area().height;
+            let available_space_below = terminal_height.saturating_sub(area.y + area.height);
+            let needed_height = self.options.len() as u16 + 2;
+            let popup_height = std::cmp::min(needed_height, available_space_below);
+            if popup_height < 3 {
+                return;
+            }
+
+            let options_area = Rect::new(area.x, area.y + area.height, area.width, popup_height);
+            frame.render_widget(Clear, options_area);
+            self.popup_area = Some(options_area);
+
+            let options_block = Block::default()
+                .borders(Borders::ALL)
+                .border_set(border::ROUNDED)
+                .style(Style::default().fg(Config::global().theme.primary));
+            frame.render_widget(options_block, options_area);
+
+            let max_visible_options = (popup_height.saturating_sub(2)) as usize;
+
+            // Auto-scroll to keep next_index visible
+            if self.next_index < self.scroll {
+                self.scroll = self.next_index;
+            } else if self.next_index >= self.scroll + max_visible_options {
+                self.scroll = self.next_index + 1 - max_visible_options;
+            }
+
+            let visible_options = self
+                .options
+                .iter()
+                .enumerate()
+                .skip(self.scroll)
+                .take(max_visible_options);
+
+            for (display_row, (original_index, option)) in visible_options.enumerate() {
+                let option_area = Rect::new(
+                    options_area.x + 1,
+                    options_area.y + display_row as u16 + 1,
+                    options_area.width.saturating_sub(2),
+                    1,
+                );
+
+                let [left_side, option_area, right_side] = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([
+                        Constraint::Fill(1),
+                        Constraint::Length(std::cmp::min(
+                            self.longest_word as u16 + 2,
+                            option_area.width.saturating_sub(2),
+                        )),
+                        Constraint::Fill(1),
+                    ])
+                    .areas(option_area);
+
+                if original_index == self.next_index {
+                    let mut text = option.to_string();
+
+                    if self.arrows == Arrows::Dynamic {
+                        text = format!("▶ {} ◀", option);
+                    }
+
+                    let option_paragraph = Paragraph::new(text)
+                        .alignment(Alignment::Center)
+                        .style(Style::default().fg(Config::global().theme.highlight));
+                    frame.render_widget(option_paragraph, option_area);
+
+                    if self.arrows != Arrows::Static {
+                        continue;
+                    }
+
+                    let left_paragraph = Paragraph::new("▶")
+                        .alignment(Alignment::Right)
+                        .style(Style::default().fg(Config::global().theme.highlight));
+
+                    let right_paragraph = Paragraph::new("◀")
+                        .alignment(Alignment::Left)
+                        .style(Style::default().fg(Config::global().theme.highlight));
+
+                    frame.render_widget(left_paragraph, left_side);
+                    frame.render_widget(right_paragraph, right_side);
+                } else {
+                    let option_paragraph = Paragraph::new(option.to_string())
+                        .alignment(Alignment::Center)
+                        .style(Style::default().fg(Config::global().theme.primary));
+                    frame.render_widget(option_paragraph, option_area);
+                }
+            }
+
+            if self.options.len() > max_visible_options {
+                let scroll_info_area = Rect::new(
+                    options_area.x + options_area.width.saturating_sub(1),
+                    options_area.y + 1,
+                    1,
+                    options_area.height.saturating_sub(2),
+                );
+
+                if self.scroll > 0 {
+                    frame.render_widget(
+                        Paragraph::new("↑").style(Style::default().fg(Config::global().theme.highlight)),
+                        Rect::new(scroll_info_area.x, scroll_info_area.y, 1, 1),
+                    );
+                }
+
+                if self.scroll + max_visible_options < self.options.len() {
+                    frame.render_widget(
+                        Paragraph::new("↓").style(Style::default().fg(Config::global().theme.highlight)),
+                        Rect::new(
+                            scroll_info_area.x,
+                            scroll_info_area.y + scroll_info_area.height.saturating_sub(1),
+                            1,
+                            1,
+                        ),
+                    );
+                }
+            }
+        }
+    }
+}
+
+// #[derive(Clone)]
+// pub struct SearchPopup {
+//     pub toggled: bool,
+//     pub query: String,
+// }
@@ -0,0 +1,206 @@
+use super::{PopupState, bordered, centered};
+use crate::app::{Action, Event, Resume};
+use crate::config::Config;
+use crate::config::keymap::{KeyAction, KeyInput};
+use crate::config::navigation::NavDirection;
+use crate::mal::models::anime::{Anime, AnimeId};
+use crate::player::{AnimePlayer, ShowCandidate};
+use crate::screens::widgets::navigatable::Navigatable;
+use crate::screens::{BackgroundUpdate, ExtraInfo};
+use crate::send_error;
+use crossterm::event::{MouseEvent, MouseEventKind};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Margin},
+    style::Style,
+    widgets::Paragraph,
+};
+use std::sync::mpsc::{Receiver, Sender};
+use std::thread::JoinHandle;
+
+// fetches the sources for an anime in the background
+enum SourceEvent {
+    Search(Anime),
+}
+
+#[derive(Clone)]
+pub struct SourcePopup {
+    popup: PopupState,
+    loading: bool,
+    anime_id: AnimeId,
+    title: String,
+    // what to go back to once a source is picked
+    resume: Resume,
+    candidates: Vec<ShowCandidate>,
+    nav: Navigatable,
+    background_transmitter: Sender<SourceEvent>,
+}
+
+impl SourcePopup {
+    pub fn new(info: ExtraInfo) -> Self {
+        let (tx, rx) = std::sync::mpsc::channel::<SourceEvent>();
+        let popup = Self {
+            popup: PopupState::default(),
+            loading: false,
+            anime_id: AnimeId::default(),
+            title: String::new(),
+            resume: Resume::Nothing,
+            candidates: Vec::new(),
+            nav: Navigatable::new((10, 1)),
+            background_transmitter: tx,
+        };
+        popup.spawn_background(info, rx);
+        popup
+    }
+
+    fn spawn_background(&self, info: ExtraInfo, receiver: Receiver<SourceEvent>) -> JoinHandle<()> {
+        std::thread::spawn(move || {
+            let player = AnimePlayer::new();
+            while let Ok(event) = receiver.recv() {
+                match event {
+                    SourceEvent::Search(anime) => {
+                        let update = match player.search_candidates(&anime) {
+                            Ok(candidates) => BackgroundUpdate::new("source_popup")
+                                .set("candidates", (anime.id, candidates)),
+                            Err(e) => BackgroundUpdate::new("source_popup")
+                                .set("failure", (anime.id, e.to_string())),
+                        };
+                        info.app_sx.send(Event::BackgroundNotice(update)).ok();
+                    }
+                }
+            }
+        })
+    }
+
+    pub fn apply_update(&mut self, mut update: BackgroundUpdate) {
+        if let Some((anime_id, candidates)) =
+            update.take::<(AnimeId, Vec<ShowCandidate>)>("candidates")
+            && anime_id == self.anime_id
+        {
+            self.loading = false;
+            self.candidates = candidates;
+            self.nav.back_to_start();
+        }
+
+        if let Some((anime_id, message)) = update.take::<(AnimeId, String)>("failure")
+            && anime_id == self.anime_id
+        {
+            self.close();
+            send_error!("Failed to search for sources:\n{}", message);
+        }
+    }
+
+    // opens the picker with already fetched candidates
+    pub fn open_with_candidates(
+        &mut self,
+        anime: &Anime,
+        resume: Resume,
+        candidates: Vec<ShowCandidate>,
+    ) {
+        self.set_anime(anime, resume);
+        self.candidates = candidates;
+        self.popup.open();
+    }
+
+    // opens the picker and searches for candidates in the background
+    pub fn open_and_search(&mut self, anime: &Anime, resume: Resume) {
+        self.set_anime(anime, resume);
+        self.loading = true;
+        self.popup.open();
+        self.background_transmitter
+            .send(SourceEvent::Search(anime.clone()))
+            .ok();
+    }
+
+    fn set_anime(&mut self, anime: &Anime, resume: Resume) {
+        self.anime_id = anime.id;
+        self.title = anime.title.clone();
+        self.resume = resume;
+        self.candidates.clear();
+        self.nav.back_to_start();
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.popup.is_open()
+    }
+
+    pub fn close(&mut self) {
+        self.popup.close();
+        self.loading = false;
+    }
+
+    fn select(&mut self) -> Option<Action> {
+        let candidate = self.nav.get_selected_item(&self.candidates)?.clone();
+        self.close();
+        Some(Action::SelectSource(self.anime_id, candidate, self.resume.clone()))
+    }
+
+    pub fn handle_keyboard(&mut self, key: KeyInput) -> Option<Action> {
+        match key.direction() {
+            NavDirection::Up => self.nav.move_up(),
+            NavDirection::Down => self.nav.move_down(),
+            _ => {}
+        }
+
+        if key.is(KeyAction::Select) && !self.loading {
+            return self.select();
+        }
+
+        if key.is(KeyAction::Close) {
+            self.close();
+        }
+
+        None
+    }
+
+    pub fn handle_mouse(&mut self, mouse_event: MouseEvent) -> Option<Action> {
+        if self.popup.clicked_outside(mouse_event) {
+            self.close();
+            return None;
+        }
+        let is_click = matches!(mouse_event.kind, MouseEventKind::Down(_));
+
+        if self.nav.is_hovered(mouse_event) {
+            self.nav.handle_scroll(mouse_event);
+        }
+
+        if self.nav.get_hovered_index(mouse_event).is_some() && is_click && !self.loading {
+            return self.select();
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame) {
+        if !self.popup.is_open() {
+            return;
+        }
+
+        let area = centered(frame.area(), 70, self.nav.visible_elements() as u16 + 4);
+        let title = format!("Pick a source for \"{}\"", self.title);
+        let popup_area = self.popup.draw(frame, area, bordered(title, Config::global().theme.secondary));
+
+        let list_area = popup_area.inner(Margin::new(2, 2));
+
+        if self.loading || self.candidates.is_empty() {
+            let text = if self.loading { "Searching..." } else { "No sources found" };
+            let paragraph = Paragraph::new(text)
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(Config::global().theme.primary));
+            frame.render_widget(paragraph, list_area);
+            return;
+        }
+
+        self.nav
+            .construct(&self.candidates, list_area, |candidate, area, highlighted| {
+                let paragraph = Paragraph::new(candidate.to_string())
+                    .alignment(Alignment::Left)
+                    .style(Style::default().fg(if highlighted {
+                        Config::global().theme.highlight
+                    } else {
+                        Config::global().theme.text
+                    }));
+                frame.render_widget(paragraph, area);
+            });
+    }
+}
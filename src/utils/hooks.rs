@@ -0,0 +1,130 @@
+use crate::mal::models::anime::{Anime, MyListStatus};
+use crate::player::PlayResult;
+use crate::player::providers::Stream;
+use crate::send_error;
+use serde::Serialize;
+use shell_escape::escape;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// what a hook gets on stdin, fields that dont apply to the event are null
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct HookPayload {
+    pub event: &'static str,
+    pub anime: Option<Anime>,
+    pub list_status: Option<MyListStatus>,
+    pub previous_list_status: Option<MyListStatus>,
+    pub play_result: Option<PlayResult>,
+    pub episode: Option<String>,
+    pub url: Option<String>,
+    pub referrer: Option<String>,
+}
+
+impl HookPayload {
+    pub fn new(event: &'static str) -> Self {
+        Self {
+            event,
+            ..Default::default()
+        }
+    }
+
+    pub fn anime(mut self, anime: &Anime) -> Self {
+        self.list_status = Some(anime.my_list_status.clone());
+        self.anime = Some(anime.clone());
+        self
+    }
+
+    pub fn previous_status(mut self, status: &MyListStatus) -> Self {
+        self.previous_list_status = Some(status.clone());
+        self
+    }
+
+    pub fn episode(mut self, episode: &str) -> Self {
+        self.episode = Some(episode.to_string());
+        self
+    }
+
+    pub fn stream(mut self, stream: &Stream) -> Self {
+        self.url = Some(stream.url.clone());
+        self.referrer = stream.referer().map(str::to_string);
+        self
+    }
+
+    pub fn play_result(mut self, result: &PlayResult) -> Self {
+        self.play_result = Some(result.clone());
+        self
+    }
+
+    // the old style {variables}, kept so simple hooks dont need to parse json
+    fn substitute(&self, command: &str) -> String {
+        let field = |value: Option<&str>| escape(value.unwrap_or_default().to_string().into()).to_string();
+        let title = self.anime.as_ref().map(|a| a.title.as_str());
+        let status = self.list_status.as_ref().map(|s| s.status.as_str());
+
+        command
+            .replace("{title}", &field(title))
+            .replace("{episode}", &field(self.episode.as_deref()))
+            .replace("{status}", &field(status))
+            .replace("{url}", &field(self.url.as_deref()))
+            .replace("{referer}", &field(self.referrer.as_deref()))
+            .replace("{referrer}", &field(self.referrer.as_deref()))
+    }
+}
+
+// runs the hook and waits for it, quiet hooks dont get to write over the tui
+pub fn run_hook(command: &str, payload: &HookPayload, quiet: bool) -> Result<(), String> {
+    let cmd = payload.substitute(command);
+
+    #[cfg(unix)]
+    let mut command = {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(&cmd);
+        command
+    };
+
+    #[cfg(windows)]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(&cmd);
+        command
+    };
+
+    command.stdin(Stdio::piped());
+    if quiet {
+        command.stdout(Stdio::null()).stderr(Stdio::null());
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to run hook: {}", e))?;
+
+    // a hook that doesnt read stdin closes it early, that is fine
+    if let Some(mut stdin) = child.stdin.take()
+        && let Ok(json) = serde_json::to_string(payload)
+    {
+        stdin.write_all(json.as_bytes()).ok();
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to run hook: {}", e))?;
+
+    if !status.success() {
+        return Err(format!("Hook exited with status: {:?}", status.code()));
+    }
+
+    Ok(())
+}
+
+// runs the hook in the background if it is configured, failures are shown as errors
+pub fn fire(hook: &Option<String>, payload: HookPayload) {
+    let Some(command) = hook.clone() else {
+        return;
+    };
+
+    std::thread::spawn(move || {
+        if let Err(e) = run_hook(&command, &payload, true) {
+            send_error!("{} hook: {}", payload.event, e);
+        }
+    });
+}
@@ -35,6 +35,16 @@ impl Input {
         self
     }
 
+    // starts out holding a value, with the cursor at its end
+    pub fn with_value(mut self, value: &str) -> Self {
+        if !value.is_empty() {
+            self.value = value.to_string();
+            self.cursor = value.len() as u16;
+            self.empty = false;
+        }
+        self
+    }
+
     pub fn max_length(mut self, length: usize) -> Self {
         self.max_length = Some(length);
         self
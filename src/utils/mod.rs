@@ -8,6 +8,7 @@ pub mod input;
 pub mod functionStreaming;
 pub mod store;
 pub mod errorBus;
+pub mod hooks;
 
 #[macro_export]
 macro_rules! send_error {
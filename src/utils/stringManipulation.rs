@@ -132,3 +132,99 @@ pub fn levenshtein_distance(s1: &str, s2: &str) -> usize {
 
     matrix[len1][len2]
 }
+
+// 0.0 - 1.0 where 1.0 means the titles are the same (ignoring case)
+pub fn title_similarity(s1: &str, s2: &str) -> f32 {
+    let s1 = s1.to_lowercase();
+    let s2 = s2.to_lowercase();
+    let longest = s1.chars().count().max(s2.chars().count());
+    if longest == 0 {
+        return 0.0;
+    }
+
+    1.0 - levenshtein_distance(&s1, &s2) as f32 / longest as f32
+}
+
+// how well the query matches the text when its letters are found in order, higher is better
+// letters right after each other and at the start of words count the most
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let query: Vec<char> = query.to_lowercase().chars().filter(|c| !c.is_whitespace()).collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for (i, c) in text.iter().enumerate() {
+        if next == query.len() {
+            break;
+        }
+        if *c != query[next] {
+            continue;
+        }
+
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == i) {
+            score += 5;
+        }
+        if i == 0 || !text[i - 1].is_alphanumeric() {
+            score += 3;
+        }
+        if let Some(p) = previous {
+            score -= (i - p - 1).min(3) as i32;
+        }
+        previous = Some(i);
+        next += 1;
+    }
+
+    // shorter texts are closer to what was typed
+    (next == query.len()).then(|| score * 10 - text.len() as i32 / 4)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_titles_are_fully_similar() {
+        assert_eq!(title_similarity("Attack on Titan", "attack ON titan"), 1.0);
+        assert_eq!(title_similarity("", ""), 0.0);
+    }
+
+    #[test]
+    fn similarity_drops_with_each_edit() {
+        let close = title_similarity("Shingeki no Kyojin", "Shingeki no Kyojin 2");
+        let far = title_similarity("Shingeki no Kyojin", "Kimetsu no Yaiba");
+        assert_eq!(close, 0.9);
+        assert!(far < 0.5);
+        assert_eq!(title_similarity("abcd", "abce"), 0.75);
+    }
+
+    #[test]
+    fn fuzzy_finds_letters_in_order() {
+        assert!(fuzzy_score("frn", "Sousou no Frieren").is_some());
+        assert_eq!(fuzzy_score("nrf", "Frieren"), None);
+        assert_eq!(fuzzy_score(" ", "anything"), Some(0));
+    }
+
+    #[test]
+    fn fuzzy_prefers_word_starts_and_runs() {
+        let start = fuzzy_score("b", "Bleach").unwrap();
+        let inside = fuzzy_score("b", "Abelia").unwrap();
+        assert!(start > inside);
+
+        let run = fuzzy_score("fri", "Frieren").unwrap();
+        let spread = fuzzy_score("fri", "Fire Force Rising").unwrap();
+        assert!(run > spread);
+    }
+
+    #[test]
+    fn fuzzy_prefers_shorter_titles() {
+        let short = fuzzy_score("bleach", "Bleach").unwrap();
+        let long = fuzzy_score("bleach", "Bleach: Sennen Kessen-hen").unwrap();
+        assert!(short > long);
+    }
+}